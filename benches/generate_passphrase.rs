@@ -1,5 +1,6 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use phraze::*;
+use rand::thread_rng;
 
 fn criterion_benchmark(c: &mut Criterion) {
     // Define a Criterion group, just so we can set a sample_size
@@ -22,7 +23,161 @@ fn criterion_benchmark(c: &mut Criterion) {
             )
         })
     });
+
+    group.bench_function("generate 1000 passphrases, new RNG each time", |b| {
+        b.iter(|| {
+            for _ in 0..1000 {
+                generate_passphrase(
+                    number_of_words_to_put_in_passphrase,
+                    separator,
+                    title_case,
+                    wordlist,
+                );
+            }
+        })
+    });
+
+    group.bench_function("generate 1000 passphrases, reusing one RNG", |b| {
+        b.iter(|| {
+            let mut rng = thread_rng();
+            for _ in 0..1000 {
+                generate_passphrase_with_rng(
+                    &mut rng,
+                    number_of_words_to_put_in_passphrase,
+                    separator,
+                    title_case,
+                    wordlist,
+                );
+            }
+        })
+    });
+}
+
+fn long_passphrase_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Generate a long passphrase");
+    group.sample_size(1200).significance_level(0.1);
+
+    let wordlist = fetch_list(ListChoice::Long);
+
+    group.bench_function("12 words from the Long list", |b| {
+        b.iter(|| generate_passphrase(12, "-", false, wordlist))
+    });
+
+    group.bench_function("12 Title Case words from the Long list", |b| {
+        b.iter(|| generate_passphrase(12, "-", true, wordlist))
+    });
+}
+
+/// Build a custom list (a Vec<String>, just like read_in_custom_list returns) without doing any
+/// file IO, by copying a built-in list.
+fn make_custom_list(list_choice: ListChoice) -> Vec<String> {
+    fetch_list(list_choice)
+        .iter()
+        .map(|word| word.to_string())
+        .collect()
+}
+
+fn options_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Generate a passphrase with options");
+    group.sample_size(1200).significance_level(0.1);
+
+    let number_of_words_to_put_in_passphrase = 7;
+    let wordlist = fetch_list(ListChoice::Medium);
+    let custom_list = make_custom_list(ListChoice::Medium);
+
+    group.bench_function("custom list", |b| {
+        b.iter(|| {
+            generate_passphrase(
+                number_of_words_to_put_in_passphrase,
+                "-",
+                false,
+                &custom_list,
+            )
+        })
+    });
+
+    for separator in ["_n", "_s", "_b"] {
+        group.bench_function(format!("{} separators", separator), |b| {
+            b.iter(|| {
+                generate_passphrase(
+                    number_of_words_to_put_in_passphrase,
+                    separator,
+                    false,
+                    wordlist,
+                )
+            })
+        });
+    }
+
+    group.bench_function("title case", |b| {
+        b.iter(|| generate_passphrase(number_of_words_to_put_in_passphrase, "-", true, wordlist))
+    });
+
+    // Non-ASCII words skip the ASCII fast path for title case, so compare them with and without
+    let accented_list: Vec<String> = wordlist.iter().map(|word| format!("é{}", word)).collect();
+    for title_case in [false, true] {
+        group.bench_function(
+            format!("non-ASCII custom list, title case {}", title_case),
+            |b| {
+                b.iter(|| {
+                    generate_passphrase(
+                        number_of_words_to_put_in_passphrase,
+                        "-",
+                        title_case,
+                        &accented_list,
+                    )
+                })
+            },
+        );
+    }
+}
+
+/// Mean word length of each built-in list, in characters, as reported in the readme. If a list's
+/// contents change by accident, this is likely to catch it.
+fn expected_mean_word_length(list_choice: ListChoice) -> f64 {
+    match list_choice {
+        ListChoice::Medium => 7.07,
+        ListChoice::Long => 7.98,
+        ListChoice::Eff => 6.99,
+        ListChoice::Mnemonicode => 5.75,
+        ListChoice::Effshort => 4.54,
+        ListChoice::Qwerty => 4.24,
+        ListChoice::Alpha => 4.12,
+        ListChoice::Emoji => 1.00,
+    }
+}
+
+fn all_lists_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Generate a passphrase from each list");
+    group.sample_size(1200).significance_level(0.1);
+
+    for list_choice in ListChoice::all() {
+        let wordlist = fetch_list(list_choice);
+        let mean_word_length = wordlist
+            .iter()
+            .map(|word| grapheme_length(word))
+            .sum::<usize>() as f64
+            / wordlist.len() as f64;
+        assert_eq!(
+            format!("{:.2}", mean_word_length),
+            format!("{:.2}", expected_mean_word_length(list_choice)),
+            "Mean word length of {:?} list has changed",
+            list_choice
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("7 words", format!("{:?}", list_choice)),
+            &wordlist,
+            |b, wordlist| b.iter(|| generate_passphrase(7, "-", false, wordlist)),
+        );
+    }
 }
 
-criterion_group!(benches, criterion_benchmark);
+criterion_group!(
+    benches,
+    all_lists_benchmark,
+    criterion_benchmark,
+    long_passphrase_benchmark,
+    options_benchmark
+);
 criterion_main!(benches);
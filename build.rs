@@ -2,35 +2,130 @@ use std::env;
 use std::fs::File;
 use std::io::{BufRead, BufReader, Write};
 use std::path::Path;
+use unicode_normalization::is_nfc;
 
 // https://doc.rust-lang.org/cargo/reference/build-scripts.html#case-study-code-generation
 
 /// Write the words from the word list file into a Rust Array for program's use.
 fn words(mut f_dest: &File, const_name: &str, fname_src: &str, list_size: usize) {
+    let words = read_list(fname_src, list_size);
+
     // Declare a new Rust constant that is an array of slices.
     // To maximize efficiency, make it the exact size of this word list.
     write!(f_dest, "const {const_name}: &[&str; {list_size}] = &[").unwrap();
 
-    // Read words in and add them to this array
-    let f_src = BufReader::new(File::open(fname_src).unwrap());
-    for word in f_src.lines() {
-        match word {
-            // We're writing a Rust Array programmtically, so need the word to be surround by
-            // double quotes and have a comma between words.
-            Ok(word) => write!(f_dest, "\"{word}\",").unwrap(),
-            Err(_e) => panic!("Error reading line from built-in list"),
-        }
+    // Add the words to this array
+    for word in words {
+        // We're writing a Rust Array programmtically, so need the word to be surround by
+        // double quotes and have a comma between words.
+        write!(f_dest, "\"{word}\",").unwrap();
     }
 
     // Close array syntax
     f_dest.write_all(b"];").unwrap();
 }
 
+/// Read in and validate the words of a word list file.
+fn read_list(fname_src: &str, list_size: usize) -> Vec<String> {
+    // Rebuild if the list changes
+    println!("cargo:rerun-if-changed={fname_src}");
+
+    let f_src = BufReader::new(File::open(fname_src).unwrap());
+    let words: Vec<String> = f_src
+        .lines()
+        .map(|word| match word {
+            Ok(word) => word,
+            Err(_e) => panic!("Error reading line from built-in list"),
+        })
+        .collect();
+    validate_list(fname_src, &words, list_size);
+    words
+}
+
+/// For the `compressed-lists` feature: rather than writing out arrays, compress all of the lists
+/// into one blob of raw DEFLATE data. Words are separated by a newline, and lists, in the order
+/// given, by a blank line. The library decompresses this the first time a list is fetched.
+fn compressed_words(mut f_dest: &File, out_dir: &str, lists: &[(&str, usize)]) {
+    let text = lists
+        .iter()
+        .map(|(fname_src, list_size)| read_list(fname_src, *list_size).join("\n"))
+        .collect::<Vec<String>>()
+        .join("\n\n");
+    let compressed = miniz_oxide::deflate::compress_to_vec(text.as_bytes(), 10);
+    std::fs::write(Path::new(out_dir).join("wordlists.deflate"), compressed).unwrap();
+    f_dest
+        .write_all(b"const WORDLISTS_COMPRESSED: &[u8] = include_bytes!(concat!(env!(\"OUT_DIR\"), \"/wordlists.deflate\"));")
+        .unwrap();
+}
+
+/// Check a built-in list before we build it into Phraze, so a bad edit to a list file fails the
+/// build rather than quietly weakening passphrases. Each list must:
+///
+/// - have exactly the expected number of words
+/// - have no blank words, and no whitespace around words
+/// - be sorted (by byte value, like `LC_ALL=C sort`), which also makes it easy to check that it
+///   has no duplicates, and keeps diffs against upstream lists readable
+/// - be in Unicode Normalization Form C, so words that look the same are the same
+/// - not contain `"` or `\`, which would break the Rust array we write
+fn validate_list(fname_src: &str, words: &[String], list_size: usize) {
+    if words.len() != list_size {
+        panic!(
+            "{fname_src} has {} words, but should have {list_size}",
+            words.len()
+        );
+    }
+    for (i, word) in words.iter().enumerate() {
+        let line_number = i + 1;
+        if word.trim().is_empty() || word.trim() != word {
+            panic!(
+                "Word on line {line_number} of {fname_src} is blank or has whitespace around it"
+            );
+        }
+        if word.contains('"') || word.contains('\\') {
+            panic!("Word on line {line_number} of {fname_src} contains a quote or backslash");
+        }
+        if !is_nfc(word) {
+            panic!(
+                "Word on line {line_number} of {fname_src} isn't in Unicode Normalization Form C"
+            );
+        }
+        if i > 0 {
+            let previous = &words[i - 1];
+            if previous == word {
+                panic!("Word '{word}' on line {line_number} of {fname_src} is a duplicate");
+            }
+            if previous > word {
+                panic!("{fname_src} isn't sorted: '{previous}' comes before '{word}' on line {line_number}");
+            }
+        }
+    }
+}
+
 fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
     let out_dir = env::var("OUT_DIR").unwrap();
     let dest_path = Path::new(&out_dir).join("wordlists.rs");
     let f = File::create(dest_path).unwrap();
 
+    if env::var_os("CARGO_FEATURE_COMPRESSED_LISTS").is_some() {
+        // Must be in the same order as the lists in src/compressed_lists.rs
+        compressed_words(
+            &f,
+            &out_dir,
+            &[
+                ("word-lists/orchard-street-long.txt", 17576),
+                ("word-lists/orchard-street-medium.txt", 8192),
+                ("word-lists/orchard-street-qwerty.txt", 1296),
+                ("word-lists/orchard-street-alpha.txt", 1296),
+                ("word-lists/eff-long.txt", 7776),
+                ("word-lists/eff-short-1.txt", 1296),
+                ("word-lists/mnemonicode.txt", 1633),
+                ("word-lists/emoji.txt", 975),
+            ],
+        );
+        return;
+    }
+
     words(&f, "WL_LONG", "word-lists/orchard-street-long.txt", 17576);
     words(
         &f,
@@ -48,4 +143,5 @@ fn main() {
     words(&f, "WL_EFF", "word-lists/eff-long.txt", 7776);
     words(&f, "WL_EFFSHORT", "word-lists/eff-short-1.txt", 1296);
     words(&f, "WL_MNEMONICODE", "word-lists/mnemonicode.txt", 1633);
+    words(&f, "WL_EMOJI", "word-lists/emoji.txt", 975);
 }
@@ -0,0 +1,6 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use phraze::fuzzing::fuzz_custom_list;
+
+fuzz_target!(|data: &[u8]| fuzz_custom_list(data));
@@ -0,0 +1,6 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use phraze::fuzzing::fuzz_separator;
+
+fuzz_target!(|data: &[u8]| fuzz_separator(data));
@@ -0,0 +1,77 @@
+use crate::grapheme_length;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The first `length` characters of a word (as counted by `grapheme_length`), or the whole word
+/// if it's that short already.
+pub fn abbreviate(word: &str, length: usize) -> &str {
+    match word.grapheme_indices(true).nth(length) {
+        Some((end, _)) => &word[..end],
+        None => word,
+    }
+}
+
+/// The fewest characters that tell every word on a list apart, so that abbreviating every word to
+/// that many characters (with `abbreviate`) never gives two words the same abbreviation. Words
+/// that are already that short are kept whole, so they can't be the start of a longer word
+/// either: with "sun" and "sunflower" on a list, 3 characters aren't enough. The list's words
+/// must all be different.
+pub fn unique_prefix_length<T: AsRef<str>>(list: &[T]) -> usize {
+    let mut abbreviations: Vec<&str> = Vec::with_capacity(list.len());
+    let longest = list
+        .iter()
+        .map(|word| grapheme_length(word.as_ref()))
+        .max()
+        .unwrap_or(0);
+    for length in 1..longest {
+        abbreviations.clear();
+        abbreviations.extend(list.iter().map(|word| abbreviate(word.as_ref(), length)));
+        abbreviations.sort_unstable();
+        if abbreviations.windows(2).all(|pair| pair[0] != pair[1]) {
+            return length;
+        }
+    }
+    longest
+}
+
+/// Work out how many characters to abbreviate a list's words to, with `unique_prefix_length`.
+/// Returns an error if that wouldn't make any word shorter, since then abbreviating is pointless.
+pub fn abbreviation_length<T: AsRef<str>>(list: &[T]) -> Result<usize, String> {
+    let length = unique_prefix_length(list);
+    if list
+        .iter()
+        .all(|word| grapheme_length(word.as_ref()) <= length)
+    {
+        return Err(format!(
+            "Can't abbreviate words from this list: it takes all {} characters to tell some of its words apart, so no word would get any shorter",
+            length
+        ));
+    }
+    Ok(length)
+}
+
+#[test]
+fn can_abbreviate_words() {
+    assert_eq!(abbreviate("cormorant", 3), "cor");
+    assert_eq!(abbreviate("ox", 3), "ox");
+    assert_eq!(abbreviate("e\u{301}lan", 1), "e\u{301}");
+}
+
+#[test]
+fn can_find_the_shortest_unique_prefix() {
+    assert_eq!(unique_prefix_length(&["cormorant", "horse", "battery"]), 1);
+    assert_eq!(unique_prefix_length(&["stable", "staple", "horse"]), 4);
+    // "sun" stays whole, so it needs 4 characters to tell it apart from "sunflower"
+    assert_eq!(unique_prefix_length(&["sun", "sunflower"]), 4);
+    assert_eq!(abbreviation_length(&["sun", "sunflower"]), Ok(4));
+    assert!(abbreviation_length(&["tea", "teas"]).is_err());
+}
+
+#[test]
+fn abbreviations_of_the_qwerty_list_are_unique() {
+    use crate::{fetch_list, ListChoice};
+    use std::collections::HashSet;
+    let list = fetch_list(ListChoice::Qwerty);
+    let length = abbreviation_length(list).unwrap();
+    let abbreviations: HashSet<&str> = list.iter().map(|word| abbreviate(word, length)).collect();
+    assert_eq!(abbreviations.len(), list.len());
+}
@@ -0,0 +1,65 @@
+use crate::{generate_passphrase, PassphraseConfig};
+
+/// Generate a passphrase on Tokio's blocking thread pool (via `tokio::task::spawn_blocking`),
+/// so an async service can make passphrases without tying up its async worker threads. Must be
+/// called from within a Tokio runtime. `list` can be a built-in list from `fetch_list`, or a
+/// list of your own in something that can be sent to another thread, like an `Arc<[String]>`.
+///
+/// Each passphrase is made with the `thread_rng` of whichever blocking thread runs it. That RNG
+/// never leaves its thread, so there's no RNG state shared between tasks.
+///
+/// Note that generating a passphrase only takes a microsecond or so, which for most services is
+/// less than the cost of handing the work off to another thread. Calling `generate_passphrase`
+/// directly from async code is usually fine; this is only worthwhile for services generating a
+/// lot of passphrases at once (e.g. bulk requests), or using very large custom lists.
+pub async fn generate_passphrase_async<L, T>(
+    config: PassphraseConfig,
+    list: L,
+) -> Result<String, String>
+where
+    L: AsRef<[T]> + Send + 'static,
+    T: AsRef<str>,
+{
+    let handle = tokio::task::spawn_blocking(move || {
+        generate_passphrase(
+            config.number_of_words,
+            &config.separator,
+            config.title_case,
+            list.as_ref(),
+        )
+    });
+    match handle.await {
+        Ok(passphrase) => Ok(passphrase),
+        Err(e) => Err(format!("Passphrase generation task failed: {}", e)),
+    }
+}
+
+#[test]
+fn can_generate_passphrases_asynchronously() {
+    use crate::{fetch_list, ListChoice};
+    use std::sync::Arc;
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let config = PassphraseConfig {
+        number_of_words: 5,
+        separator: " ".to_string(),
+        title_case: false,
+    };
+    let passphrase = runtime
+        .block_on(generate_passphrase_async(
+            config.clone(),
+            fetch_list(ListChoice::Medium),
+        ))
+        .unwrap();
+    assert_eq!(passphrase.split(' ').count(), 5);
+
+    let custom_list: Arc<[String]> = vec!["one".to_string(), "two".to_string()].into();
+    let passphrase = runtime
+        .block_on(generate_passphrase_async(config, custom_list))
+        .unwrap();
+    assert!(passphrase
+        .split(' ')
+        .all(|word| word == "one" || word == "two"));
+}
@@ -0,0 +1,157 @@
+use std::hint::black_box;
+
+/// Passphrases generated in one run, kept together in a single buffer until they've been
+/// printed. When the buffer is dropped, it's overwritten with zeros.
+///
+/// With the `mlock` feature, the buffer is also locked into RAM, so the operating system can't
+/// swap the passphrases out to disk. Locking can fail, most often because the process's
+/// RLIMIT_MEMLOCK is too low for the batch. In that case the batch still works, just unlocked;
+/// check `is_locked`.
+///
+/// Note that each passphrase is put together in ordinary memory first, then copied into the
+/// buffer, and the original is wiped. Printing a passphrase also passes it through standard
+/// output's own (unlocked) buffer.
+pub struct PassphraseBatch {
+    // Fields are dropped in order, so the lock has to come before `text`: the memory needs to
+    // be unlocked before it's freed
+    #[cfg(feature = "mlock")]
+    lock: Option<region::LockGuard>,
+    // All of the passphrases, back to back
+    text: Vec<u8>,
+    // Where each passphrase ends in `text`
+    ends: Vec<usize>,
+}
+
+impl PassphraseBatch {
+    /// Make an empty batch, with room for `capacity` bytes of passphrases. With the `mlock`
+    /// feature, this is the amount of memory we try to lock, and a locked batch can't grow past
+    /// it.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let text = Vec::with_capacity(capacity);
+        PassphraseBatch {
+            #[cfg(feature = "mlock")]
+            lock: region::lock(text.as_ptr(), text.capacity()).ok(),
+            text,
+            ends: vec![],
+        }
+    }
+
+    /// Whether the batch's memory is locked into RAM. Always false without the `mlock` feature.
+    pub fn is_locked(&self) -> bool {
+        #[cfg(feature = "mlock")]
+        return self.lock.is_some();
+        #[cfg(not(feature = "mlock"))]
+        false
+    }
+
+    /// Move a passphrase into the batch, wiping the original, and return the batch's copy.
+    /// Returns an error if the batch is locked and there isn't room for the passphrase, since
+    /// growing the buffer would move the passphrases to memory that isn't locked.
+    pub fn push(&mut self, passphrase: String) -> Result<&str, String> {
+        let mut bytes = passphrase.into_bytes();
+        if self.is_locked() && self.text.len() + bytes.len() > self.text.capacity() {
+            wipe(&mut bytes);
+            return Err("Ran out of locked memory for passphrases".to_string());
+        }
+        let start = self.text.len();
+        self.text.extend_from_slice(&bytes);
+        wipe(&mut bytes);
+        self.ends.push(self.text.len());
+        Ok(self.passphrase(start, self.text.len()))
+    }
+
+    /// Every passphrase in the batch, in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        let starts = std::iter::once(0).chain(self.ends.iter().copied());
+        starts
+            .zip(self.ends.iter().copied())
+            .map(|(start, end)| self.passphrase(start, end))
+    }
+
+    fn passphrase(&self, start: usize, end: usize) -> &str {
+        // Only whole Strings are ever added to `text`, so this is always valid UTF-8
+        std::str::from_utf8(&self.text[start..end]).unwrap()
+    }
+}
+
+impl Drop for PassphraseBatch {
+    fn drop(&mut self) {
+        // Wipe the passphrases before the memory is unlocked and freed
+        wipe(&mut self.text);
+    }
+}
+
+/// A generous upper bound on how many bytes one passphrase can take up, for sizing a
+/// `PassphraseBatch`. Title Case can make a word's first letter a few bytes longer, and each
+/// separator might be a single character of up to 4 bytes.
+pub fn longest_possible_passphrase<'a>(
+    number_of_words: usize,
+    words: impl Iterator<Item = &'a str>,
+    separator: &str,
+) -> usize {
+    let longest_word = words.map(|word| word.len()).max().unwrap_or(0);
+    number_of_words * (longest_word + 4)
+        + number_of_words.saturating_sub(1) * separator.len().max(4)
+}
+
+/// A generous upper bound on how many more bytes a word that isn't on the list (like one from
+/// --inject-word) adds to a passphrase, counting the extra separator it brings with it.
+pub fn longest_possible_extra_word(word: &str, separator: &str) -> usize {
+    word.len() + 4 + separator.len().max(4)
+}
+
+/// Overwrite bytes with zeros, in a way the compiler shouldn't optimize away.
+fn wipe(bytes: &mut Vec<u8>) {
+    bytes.fill(0);
+    black_box(bytes);
+}
+
+#[test]
+fn can_store_a_batch_of_passphrases() {
+    let mut batch = PassphraseBatch::with_capacity(64);
+    assert_eq!(
+        batch.push("first-passphrase".to_string()).unwrap(),
+        "first-passphrase"
+    );
+    assert_eq!(batch.push("".to_string()).unwrap(), "");
+    assert_eq!(
+        batch.push("thïrd-passphrâse".to_string()).unwrap(),
+        "thïrd-passphrâse"
+    );
+    let passphrases: Vec<&str> = batch.iter().collect();
+    assert_eq!(
+        passphrases,
+        vec!["first-passphrase", "", "thïrd-passphrâse"]
+    );
+    assert!(longest_possible_passphrase(2, ["abc", "λόγος"].into_iter(), "-") >= 27);
+    assert!(longest_possible_extra_word("elmhurst", "--") >= "-Elmhurst".len());
+}
+
+#[cfg(feature = "mlock")]
+#[test]
+fn falls_back_to_unlocked_memory() {
+    // Nothing to lock, so locking fails, but the batch can still grow as needed
+    let mut batch = PassphraseBatch::with_capacity(0);
+    assert!(!batch.is_locked());
+    for _ in 0..100 {
+        batch.push("unlocked-passphrase".to_string()).unwrap();
+    }
+    assert_eq!(batch.iter().count(), 100);
+
+    // A small batch will usually lock, even with a low RLIMIT_MEMLOCK. If it does, it can't
+    // grow past its capacity.
+    let mut batch = PassphraseBatch::with_capacity(32);
+    batch.push("locked-passphrase".to_string()).unwrap();
+    if batch.is_locked() {
+        assert!(batch.push("x".repeat(batch.text.capacity())).is_err());
+    }
+}
+
+#[cfg(feature = "mlock")]
+#[test]
+fn can_drop_a_locked_batch() {
+    let mut batch = PassphraseBatch::with_capacity(4096);
+    batch.push("locked-passphrase".to_string()).unwrap();
+    // Unlocking after the memory was freed would panic here
+    drop(batch);
+}
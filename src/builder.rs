@@ -0,0 +1,214 @@
+use crate::{
+    assemble_passphrase, fetch_list, generate_words, try_calculate_number_words_needed, ListChoice,
+};
+use rand::rngs::ThreadRng;
+use rand::{thread_rng, Rng};
+use std::fmt;
+
+/// How to capitalize the words in a passphrase.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum CaseMode {
+    /// Leave words exactly as they appear in the word list
+    AsIs,
+    /// Make the first letter of each word uppercase and the rest lowercase
+    Title,
+}
+
+/// A generated passphrase, along with the words it was built from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Passphrase {
+    text: String,
+    words: Vec<String>,
+    entropy: f64,
+}
+
+impl Passphrase {
+    /// The passphrase itself, with casing and separators applied.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// The words picked from the word list, exactly as they appear in the list.
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    /// Estimated entropy of the passphrase, in bits. Only the words count toward this;
+    /// separators and casing are not credited.
+    pub fn entropy(&self) -> f64 {
+        self.entropy
+    }
+}
+
+impl fmt::Display for Passphrase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl From<Passphrase> for String {
+    fn from(passphrase: Passphrase) -> String {
+        passphrase.text
+    }
+}
+
+/// The recommended way to generate passphrases from Rust code. Start with
+/// `PassphraseBuilder::new()`, change whichever settings you like, then call `generate`. Any
+/// setting you don't change uses the same default as Phraze's CLI: the Orchard Street Medium
+/// list, a "-" separator, words as they appear in the list, enough words for an 80-bit minimum
+/// entropy, and `thread_rng`.
+///
+/// The builder only covers these basic settings. Phraze's CLI doesn't build its passphrases
+/// with it, since most of its options (restrictions, --compound, --inject-digit and so on)
+/// don't have a builder setting yet, so there's no conversion from the CLI's arguments to a
+/// builder.
+///
+/// ```
+/// use phraze::builder::{CaseMode, PassphraseBuilder};
+///
+/// let passphrase = PassphraseBuilder::new()
+///     .words(6)
+///     .separator(" ")
+///     .case(CaseMode::Title)
+///     .generate()
+///     .unwrap();
+/// assert_eq!(passphrase.words().len(), 6);
+/// assert_eq!(passphrase.as_str().split(' ').count(), 6);
+/// ```
+///
+/// You can use any word list, like one read in from a file, and any RNG:
+///
+/// ```
+/// use phraze::builder::PassphraseBuilder;
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+///
+/// let my_list = vec!["alpha".to_string(), "beta".to_string(), "gamma".to_string()];
+/// let mut builder = PassphraseBuilder::new()
+///     .list(&my_list)
+///     .words(4)
+///     .rng(StdRng::seed_from_u64(7));
+/// let first = builder.generate().unwrap();
+/// let second = builder.generate().unwrap();
+/// assert_eq!(first.words().len(), 4);
+/// assert!(first.words().iter().all(|word| my_list.contains(word)));
+/// println!("{} and {}", first, second);
+/// ```
+pub struct PassphraseBuilder<'a, T: AsRef<str> = &'static str, R: Rng = ThreadRng> {
+    number_of_words: Option<usize>,
+    separator: String,
+    case: CaseMode,
+    list: &'a [T],
+    rng: R,
+}
+
+impl PassphraseBuilder<'static> {
+    /// Start a new builder with all of the default settings.
+    pub fn new() -> Self {
+        PassphraseBuilder {
+            number_of_words: None,
+            separator: "-".to_string(),
+            case: CaseMode::AsIs,
+            list: fetch_list(ListChoice::Medium),
+            rng: thread_rng(),
+        }
+    }
+}
+
+impl Default for PassphraseBuilder<'static> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T: AsRef<str>, R: Rng> PassphraseBuilder<'a, T, R> {
+    /// Set exactly how many words to put in the passphrase. If this isn't set, the builder uses
+    /// enough words to reach 80 bits of entropy with the chosen list.
+    pub fn words(mut self, number_of_words: usize) -> Self {
+        self.number_of_words = Some(number_of_words);
+        self
+    }
+
+    /// Set the separator to put between words. Phraze's special separators (`_n`, `_s` and
+    /// `_b`) work here too.
+    pub fn separator(mut self, separator: &str) -> Self {
+        self.separator = separator.to_string();
+        self
+    }
+
+    /// Set how to capitalize the words.
+    pub fn case(mut self, case: CaseMode) -> Self {
+        self.case = case;
+        self
+    }
+
+    /// Use a different word list. This can be a built-in list from `fetch_list` or a list of your
+    /// own (e.g. from `file_reader::read_in_custom_list`).
+    pub fn list<'b, U: AsRef<str>>(self, list: &'b [U]) -> PassphraseBuilder<'b, U, R> {
+        PassphraseBuilder {
+            number_of_words: self.number_of_words,
+            separator: self.separator,
+            case: self.case,
+            list,
+            rng: self.rng,
+        }
+    }
+
+    /// Use a different RNG. The builder keeps this RNG and uses it for every passphrase it
+    /// generates.
+    pub fn rng<S: Rng>(self, rng: S) -> PassphraseBuilder<'a, T, S> {
+        PassphraseBuilder {
+            number_of_words: self.number_of_words,
+            separator: self.separator,
+            case: self.case,
+            list: self.list,
+            rng,
+        }
+    }
+
+    /// Generate a passphrase with the current settings. Call this as many times as you like.
+    /// Returns an error if the list is empty, or if it has only one word and no number of words
+    /// was set (since one word is worth 0 bits, no number of them reaches 80 bits).
+    pub fn generate(&mut self) -> Result<Passphrase, String> {
+        let number_of_words = try_calculate_number_words_needed(
+            self.number_of_words,
+            None,
+            0,
+            self.list.len(),
+            None,
+        )?;
+        let words = generate_words(&mut self.rng, number_of_words, self.list);
+        let text = assemble_passphrase(
+            &mut self.rng,
+            &words,
+            &self.separator,
+            self.case == CaseMode::Title,
+        );
+        Ok(Passphrase {
+            text,
+            words: words.iter().map(|word| word.to_string()).collect(),
+            entropy: (self.list.len() as f64).log2() * number_of_words as f64,
+        })
+    }
+}
+
+#[test]
+fn gives_an_error_for_lists_too_short_to_generate_from() {
+    let empty: [&str; 0] = [];
+    assert!(PassphraseBuilder::new().list(&empty).generate().is_err());
+    assert!(PassphraseBuilder::new()
+        .list(&empty)
+        .words(3)
+        .generate()
+        .is_err());
+
+    // One word can't reach a minimum entropy, but can still make a passphrase of a set length
+    assert!(PassphraseBuilder::new().list(&["a"]).generate().is_err());
+    let passphrase = PassphraseBuilder::new()
+        .list(&["a"])
+        .words(3)
+        .generate()
+        .unwrap();
+    assert_eq!(passphrase.as_str(), "a-a-a");
+    assert_eq!(passphrase.entropy(), 0.0);
+}
@@ -0,0 +1,77 @@
+use std::collections::HashSet;
+
+/// How many words from the list go into each slot with --compound.
+pub const WORDS_PER_COMPOUND: usize = 2;
+
+/// Join picked words into compound slots, two at a time, like "maple" and "otter" into
+/// "mapleotter" (or "maple'otter" with a joiner of "'"). There should be an even number of words.
+pub fn join_compounds(words: &[&str], joiner: &str) -> Vec<String> {
+    words
+        .chunks(WORDS_PER_COMPOUND)
+        .map(|pair| pair.join(joiner))
+        .collect()
+}
+
+/// Entropy, in bits, of one compound slot, given what each word from the list is worth. The
+/// two words are picked independently, so this is twice a word's worth, as long as no two pairs
+/// of words join into the same compound (see `compound_collisions`).
+pub fn compound_slot_entropy(entropy_per_word: f64) -> f64 {
+    entropy_per_word * WORDS_PER_COMPOUND as f64
+}
+
+/// Find pairs of words from the list that join, with nothing between them, into the same
+/// compound as another pair, like "sun" + "flowerpot" and "sunflower" + "pot". Each is given as
+/// the first pair's words, then the other pair's. Compounds like these could've been made two
+/// ways, so they make slots worth a little less than `compound_slot_entropy` says. Uniquely
+/// decodable lists (like all of Phraze's built-in ones) don't have any.
+pub fn compound_collisions<'a>(list: &[&'a str]) -> Vec<[&'a str; 4]> {
+    let mut sorted = list.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    let words: HashSet<&str> = sorted.iter().copied().collect();
+
+    let mut collisions = vec![];
+    for first in &sorted {
+        // A longer word that starts with this one leaves some letters over, like "flower" from
+        // "sun" and "sunflower". Any word that starts with those letters, and ends in another
+        // word, completes a collision.
+        for longer in starting_with(&sorted, first).filter(|longer| longer != first) {
+            let left_over = &longer[first.len()..];
+            for second in starting_with(&sorted, left_over) {
+                let other_second = &second[left_over.len()..];
+                if !other_second.is_empty() && words.contains(other_second) {
+                    collisions.push([*first, second, longer, other_second]);
+                }
+            }
+        }
+    }
+    collisions
+}
+
+/// Words from a sorted list that start with `prefix`, which all come together, starting with
+/// `prefix` itself if it's on the list.
+fn starting_with<'a, 'b>(
+    sorted: &'b [&'a str],
+    prefix: &'b str,
+) -> impl Iterator<Item = &'a str> + 'b {
+    let start = sorted.partition_point(|word| *word < prefix);
+    sorted[start..]
+        .iter()
+        .copied()
+        .take_while(move |word| word.starts_with(prefix))
+}
+
+#[test]
+fn can_find_compounds_that_can_be_made_two_ways() {
+    let list = ["sun", "sunflower", "flowerpot", "pot", "tea"];
+    assert_eq!(
+        compound_collisions(&list),
+        vec![["sun", "flowerpot", "sunflower", "pot"]]
+    );
+    // "sun" starts "sunflower", but no word is the left-over "flower" followed by another word
+    assert!(compound_collisions(&["sun", "sunflower", "pot", "tea"]).is_empty());
+    assert_eq!(
+        join_compounds(&["maple", "otter", "stone", "fig"], "'"),
+        ["maple'otter", "stone'fig"]
+    );
+}
@@ -0,0 +1,47 @@
+use crate::{ListChoice, WORDLISTS_COMPRESSED};
+use miniz_oxide::inflate::decompress_to_vec;
+use std::sync::OnceLock;
+
+/// The decompressed built-in lists, in the order build.rs compressed them.
+static LISTS: OnceLock<Vec<&'static [&'static str]>> = OnceLock::new();
+
+/// Find a built-in list, decompressing all of them if this is the first time we've needed one.
+/// The decompressed words are leaked, so they live for the rest of the program. That's what
+/// lets `fetch_list` keep returning `&'static` slices, like it does without this feature.
+pub fn decompressed_list(list_choice: ListChoice) -> &'static [&'static str] {
+    let lists = LISTS.get_or_init(|| {
+        // build.rs made this blob, so there's no way for it to be invalid
+        let text =
+            decompress_to_vec(WORDLISTS_COMPRESSED).expect("Built-in word lists are corrupt");
+        let text: &'static str = String::from_utf8(text)
+            .expect("Built-in word lists aren't valid UTF-8")
+            .leak();
+        text.split("\n\n")
+            .map(|list| &*list.split('\n').collect::<Vec<&str>>().leak())
+            .collect()
+    });
+    // Must be in the same order as the lists in build.rs
+    let index = match list_choice {
+        ListChoice::Long => 0,
+        ListChoice::Medium => 1,
+        ListChoice::Qwerty => 2,
+        ListChoice::Alpha => 3,
+        ListChoice::Eff => 4,
+        ListChoice::Effshort => 5,
+        ListChoice::Mnemonicode => 6,
+        ListChoice::Emoji => 7,
+    };
+    lists[index]
+}
+
+#[test]
+fn can_decompress_lists() {
+    let medium: Vec<&str> = include_str!("../word-lists/orchard-street-medium.txt")
+        .lines()
+        .collect();
+    assert_eq!(decompressed_list(ListChoice::Medium), medium);
+    let mnemonicode: Vec<&str> = include_str!("../word-lists/mnemonicode.txt")
+        .lines()
+        .collect();
+    assert_eq!(decompressed_list(ListChoice::Mnemonicode), mnemonicode);
+}
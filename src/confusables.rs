@@ -0,0 +1,80 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+/// Words from Phraze's built-in lists that can be misread as another word on one of those lists,
+/// in some common fonts, one per line: both "modern" and "modem", for example. Made by finding
+/// every pair of built-in list words that `visual_skeleton` can't tell apart.
+const VISUAL_CONFUSABLES: &str = include_str!("../word-lists/visual-confusables.txt");
+
+/// Letters that run together to look like other letters in many common fonts, especially
+/// sans-serif fonts at small sizes, and what each looks like.
+const CONFUSABLE_SEQUENCES: &[(&str, &str)] = &[("rn", "m"), ("cl", "d"), ("vv", "w")];
+
+/// What a word looks like, if every letter sequence in `CONFUSABLE_SEQUENCES` is read as what it
+/// looks like: "modern" looks like "modem". Two words with the same skeleton are easy to mix up
+/// when reading them off a screen or a printout.
+pub fn visual_skeleton(word: &str) -> String {
+    let mut skeleton = String::with_capacity(word.len());
+    let mut rest = word;
+    while let Some(c) = rest.chars().next() {
+        match CONFUSABLE_SEQUENCES
+            .iter()
+            .find(|(sequence, _)| rest.starts_with(sequence))
+        {
+            Some((sequence, looks_like)) => {
+                skeleton.push_str(looks_like);
+                rest = &rest[sequence.len()..];
+            }
+            None => {
+                skeleton.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    skeleton
+}
+
+/// Whether a word is on the built-in blocklist of words that look like other words
+/// (`VISUAL_CONFUSABLES`).
+pub fn is_known_visual_confusable(word: &str) -> bool {
+    static CONFUSABLES: OnceLock<HashSet<&'static str>> = OnceLock::new();
+    CONFUSABLES
+        .get_or_init(|| VISUAL_CONFUSABLES.lines().collect())
+        .contains(word)
+}
+
+/// Find the words on a list that could be misread as another word: ones on the built-in
+/// blocklist, plus any that look like another word on the same list (which catches the same
+/// kind of pair in custom lists).
+pub fn visually_confusable_words<'a>(list: &[&'a str]) -> HashSet<&'a str> {
+    let mut by_skeleton: HashMap<String, Vec<&str>> = HashMap::new();
+    for word in list {
+        by_skeleton
+            .entry(visual_skeleton(word))
+            .or_default()
+            .push(word);
+    }
+    let mut confusable: HashSet<&str> = by_skeleton
+        .into_values()
+        .filter(|words| words.len() > 1)
+        .flatten()
+        .collect();
+    confusable.extend(
+        list.iter()
+            .copied()
+            .filter(|word| is_known_visual_confusable(word)),
+    );
+    confusable
+}
+
+#[test]
+fn can_find_words_that_look_alike() {
+    assert_eq!(visual_skeleton("modern"), "modem");
+    assert_eq!(visual_skeleton("clear"), "dear");
+    assert_eq!(visual_skeleton("vvhale"), "whale");
+    assert_eq!(visual_skeleton("café"), "café");
+
+    let confusable = visually_confusable_words(&["barn", "bam", "otter", "modern"]);
+    // "barn" and "bam" look alike, and "modern" is on the blocklist, since it looks like "modem"
+    assert_eq!(confusable, HashSet::from(["barn", "bam", "modern"]));
+}
@@ -0,0 +1,161 @@
+/// Work out how many rolls of a die with `base` sides it takes to pick one word from a list of
+/// the given length. This only works if the list length is an exact power of the base (like
+/// 7,776 = 6^5 for the EFF long list and 6-sided dice); otherwise some words would be more
+/// likely than others, so we return an error.
+pub fn rolls_per_word(list_length: usize, base: usize) -> Result<usize, String> {
+    if base < 2 {
+        return Err("Dice need at least 2 sides".to_string());
+    }
+    let mut rolls = 0;
+    let mut words_covered: usize = 1;
+    while words_covered < list_length {
+        words_covered = match words_covered.checked_mul(base) {
+            Some(words_covered) => words_covered,
+            None => break,
+        };
+        rolls += 1;
+    }
+    if words_covered == list_length && list_length > 1 {
+        Ok(rolls)
+    } else {
+        Err(format!(
+            "List has {} words, which isn't a power of {}, so it can't be used with {}-sided dice",
+            list_length, base, base
+        ))
+    }
+}
+
+/// How many words of a list to keep so it can be used with dice of `base` sides: the largest
+/// power of `base` that's no more than the list's length (like 7,776 of 8,192 words, for
+/// 6-sided dice).
+pub fn dice_compatible_length(list_length: usize, base: usize) -> Result<usize, String> {
+    if base < 2 {
+        return Err("Dice need at least 2 sides".to_string());
+    }
+    if list_length < base {
+        return Err(format!(
+            "List has {} words, which is too few to use with {}-sided dice",
+            list_length, base
+        ));
+    }
+    let mut words_covered = base;
+    while let Some(next) = words_covered.checked_mul(base) {
+        if next > list_length {
+            break;
+        }
+        words_covered = next;
+    }
+    Ok(words_covered)
+}
+
+/// Convert a word's position in a list into the dice rolls that pick it. Dice faces are
+/// numbered from 1 to `base` (so count the 0 on a 10-sided die as 10), and the first roll is
+/// the most significant.
+pub fn index_to_rolls(index: usize, base: usize, rolls_per_word: usize) -> Vec<usize> {
+    let mut rolls = vec![0; rolls_per_word];
+    let mut remaining = index;
+    for roll in rolls.iter_mut().rev() {
+        *roll = remaining % base + 1;
+        remaining /= base;
+    }
+    rolls
+}
+
+/// Convert one word's dice rolls back into its position in the list.
+pub fn rolls_to_index(rolls: &[usize], base: usize) -> Result<usize, String> {
+    let mut index = 0;
+    for roll in rolls {
+        if *roll < 1 || *roll > base {
+            return Err(format!(
+                "Roll of {} isn't possible with {}-sided dice",
+                roll, base
+            ));
+        }
+        index = index * base + (roll - 1);
+    }
+    Ok(index)
+}
+
+/// Parse dice rolls typed in by the user, and group them into words. Rolls can be separated by
+/// spaces, commas or dashes. With dice of 9 or fewer sides, every roll is a single digit, so
+/// rolls can also be run together, like "16345 22166".
+pub fn parse_rolls(
+    input: &str,
+    base: usize,
+    rolls_per_word: usize,
+) -> Result<Vec<Vec<usize>>, String> {
+    let mut rolls = vec![];
+    for token in input.split(|c: char| !c.is_ascii_digit()) {
+        if token.is_empty() {
+            continue;
+        }
+        if base <= 9 {
+            // Each digit is its own roll
+            rolls.extend(token.chars().map(|c| c as usize - '0' as usize));
+        } else {
+            match token.parse::<usize>() {
+                Ok(roll) => rolls.push(roll),
+                Err(_) => return Err(format!("Roll '{}' isn't a number", token)),
+            }
+        }
+    }
+    if rolls.is_empty() || rolls.len() % rolls_per_word != 0 {
+        return Err(format!(
+            "Got {} rolls, but each word takes {} rolls",
+            rolls.len(),
+            rolls_per_word
+        ));
+    }
+    Ok(rolls
+        .chunks(rolls_per_word)
+        .map(|word_rolls| word_rolls.to_vec())
+        .collect())
+}
+
+/// Make a printable worksheet for picking words with physical dice, away from a computer: every
+/// word on the list next to the rolls that pick it, grouped by the first roll. The list's length
+/// must be a power of `base`, like for `rolls_per_word`. With dice of 9 or fewer sides, rolls are
+/// written run together, like "16345", the way printed dice lists usually show them.
+pub fn dice_worksheet<T: AsRef<str>>(
+    list: &[T],
+    list_name: &str,
+    base: usize,
+) -> Result<String, String> {
+    let rolls_per_word = rolls_per_word(list.len(), base)?;
+    let show_rolls = |index: usize| {
+        let rolls = index_to_rolls(index, base, rolls_per_word);
+        if base <= 9 {
+            rolls.iter().map(|roll| roll.to_string()).collect()
+        } else {
+            format_rolls(&rolls)
+        }
+    };
+    let width = show_rolls(list.len() - 1).len();
+    let mut worksheet = format!(
+        "Phraze dice worksheet: {} ({} words)\n",
+        list_name,
+        list.len()
+    );
+    worksheet += &format!(
+        "For each word, roll {} {}-sided dice (or one die {} times), read the rolls in order, and find the word next to them. Each word adds {:.2} bits of entropy.\n",
+        rolls_per_word,
+        base,
+        rolls_per_word,
+        (list.len() as f64).log2()
+    );
+    let group_size = list.len() / base;
+    for (index, word) in list.iter().enumerate() {
+        // Start a new group whenever the first roll changes, so words are easier to find
+        if index % group_size == 0 && (group_size > 1 || index == 0) {
+            worksheet.push('\n');
+        }
+        worksheet += &format!("{:>width$}  {}\n", show_rolls(index), word.as_ref());
+    }
+    Ok(worksheet)
+}
+
+/// Show one word's dice rolls, like "1-6-3-4-5".
+pub fn format_rolls(rolls: &[usize]) -> String {
+    let rolls: Vec<String> = rolls.iter().map(|roll| roll.to_string()).collect();
+    rolls.join("-")
+}
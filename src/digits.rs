@@ -0,0 +1,108 @@
+use rand::seq::index::sample;
+use rand::Rng;
+use std::collections::HashSet;
+
+/// Replace `count` randomly chosen letters of a passphrase with random digits, leaving
+/// everything else (including the passphrase's length) as it was. For validators that insist
+/// on a digit somewhere in the middle of a password rather than tacked on the end.
+pub fn inject_digits(rng: &mut impl Rng, passphrase: &str, count: usize) -> Result<String, String> {
+    let mut chars: Vec<char> = passphrase.chars().collect();
+    let letter_positions: Vec<usize> = chars
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.is_alphabetic())
+        .map(|(position, _)| position)
+        .collect();
+    if letter_positions.len() < count {
+        return Err(format!(
+            "Passphrase only has {} letters, so can't replace {} of them with digits",
+            letter_positions.len(),
+            count
+        ));
+    }
+    for i in sample(rng, letter_positions.len(), count) {
+        chars[letter_positions[i]] = char::from_digit(rng.gen_range(0..10), 10).unwrap();
+    }
+    Ok(chars.into_iter().collect())
+}
+
+/// The fewest letters a passphrase of `number_of_words` words from these lists could have, which
+/// is how many letters we can count on being able to replace.
+pub fn fewest_letters<T: AsRef<str>>(number_of_words: usize, list: &[T]) -> usize {
+    let fewest_in_a_word = list
+        .iter()
+        .map(|word| word.as_ref().chars().filter(|c| c.is_alphabetic()).count())
+        .min()
+        .unwrap_or(0);
+    fewest_in_a_word * number_of_words
+}
+
+/// The number of different letters a replaced letter could have been, counting both cases of
+/// each letter if the passphrase is in Title Case.
+pub fn distinct_letters<'a>(words: impl Iterator<Item = &'a str>, title_case: bool) -> usize {
+    let mut letters = HashSet::new();
+    for word in words {
+        for c in word.chars().filter(|c| c.is_alphabetic()) {
+            letters.insert(c);
+            if title_case {
+                letters.extend(c.to_uppercase());
+            }
+        }
+    }
+    letters.len()
+}
+
+/// Estimate how much injecting `count` digits changes a passphrase's entropy, in bits. This can
+/// be negative.
+///
+/// Injecting digits adds two things an attacker has to guess: which letters were replaced (at
+/// least log2 of "`fewest_letters` choose `count`", since every passphrase has at least that many
+/// letters to choose from) and which digit replaced each one (log2(10) bits each). But it also
+/// throws away the letters it replaces. Two passphrases whose words only differ at the replaced
+/// letters look exactly the same afterward, so we take away log2(`distinct_letters`) bits for
+/// each replaced letter, which is the most those letters could have been worth. This gives a
+/// conservative estimate: the real change is usually a little better.
+pub fn injected_digits_entropy(
+    count: usize,
+    fewest_letters: usize,
+    distinct_letters: usize,
+) -> f64 {
+    if count == 0 {
+        return 0.0;
+    }
+    // log2 of "n choose k", added up a term at a time so it never overflows
+    let positions: f64 = (0..count)
+        .map(|i| ((fewest_letters - i) as f64).log2() - ((i + 1) as f64).log2())
+        .sum();
+    positions + count as f64 * 10f64.log2() - count as f64 * (distinct_letters as f64).log2()
+}
+
+#[test]
+fn can_inject_digits_in_place_of_letters() {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    let mut rng = StdRng::seed_from_u64(5);
+    for _ in 0..100 {
+        let injected = inject_digits(&mut rng, "alpha-beta-gamma", 2).unwrap();
+        assert_eq!(injected.chars().count(), 16);
+        assert_eq!(injected.chars().filter(|c| c.is_ascii_digit()).count(), 2);
+        // Separators are never replaced
+        assert_eq!(injected.matches('-').count(), 2);
+    }
+    assert!(inject_digits(&mut rng, "ab-1", 3).is_err());
+}
+
+#[test]
+fn can_estimate_entropy_of_injected_digits() {
+    let list = ["tab", "bat", "tat"];
+    assert_eq!(fewest_letters(4, &list), 12);
+    assert_eq!(distinct_letters(list.into_iter(), false), 3);
+    assert_eq!(distinct_letters(list.into_iter(), true), 6);
+    assert_eq!(injected_digits_entropy(0, 12, 3), 0.0);
+    // 12 positions, 10 digits, minus up to 3 possible letters
+    let expected = 12f64.log2() + 10f64.log2() - 3f64.log2();
+    assert!((injected_digits_entropy(1, 12, 3) - expected).abs() < 1e-9);
+    // 12 choose 2 is 66
+    let expected = 66f64.log2() + 2.0 * 10f64.log2() - 2.0 * 3f64.log2();
+    assert!((injected_digits_entropy(2, 12, 3) - expected).abs() < 1e-9);
+}
@@ -1,53 +1,978 @@
-use crate::unicode_normalization_check::uniform_unicode_normalization;
+use crate::grapheme_length;
+use crate::messages::{text, Message};
+use crate::unicode_normalization_check::{
+    dedup_normalized, nfc_form, uniform_unicode_normalization,
+};
+use std::collections::HashSet;
 use std::fs::File;
 use std::io;
 use std::io::BufRead;
 use std::io::BufReader;
+use std::io::Read;
 use std::path::Path;
-use std::path::PathBuf;
-use std::str::FromStr;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Limits on how much of a word list file we'll read, so a file that isn't really a word list
+/// (like a binary file, which might not have a newline for hundreds of megabytes) gives an error
+/// instead of using up all of the computer's memory.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub struct ListLimits {
+    /// Longest a line can be, in bytes, not counting its line ending
+    pub max_line_bytes: usize,
+    /// Most lines a file can have, blank lines included
+    pub max_lines: usize,
+}
+
+impl Default for ListLimits {
+    /// 1 KB per line, which is far longer than any real word, and 10 million lines, which is
+    /// hundreds of times longer than Phraze's longest built-in list.
+    fn default() -> Self {
+        ListLimits {
+            max_line_bytes: 1024,
+            max_lines: 10_000_000,
+        }
+    }
+}
+
+/// Formatting a custom word list asks for, with directives in comment lines at the very top of
+/// the file, before any words:
+///
+/// ```text
+/// # separator: -
+/// # case: title
+/// ```
+///
+/// The separator can be anything `--sep` accepts (including the special values like `_n`), and
+/// can have single quotes around it, to use a space. Case can be `title` or `lower`. Other lines
+/// starting with `#` aren't directives, so they're read as words, just as they always have been.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ListDirectives {
+    /// The separator the list asks for, if any
+    pub separator: Option<String>,
+    /// Whether the list asks for Title Case (`Some(true)`) or lowercase (`Some(false)`), if it
+    /// says either way
+    pub title_case: Option<bool>,
+}
 
 /// Read text file into a Vec<String>. Also trims whitespace, avoids adding blank strings,
-/// sorts, de-duplicates, and checks for uniform Unicode normalization.
+/// sorts, de-duplicates, and checks for uniform Unicode normalization. Files longer than the
+/// default `ListLimits` are an error. Any `ListDirectives` at the top of the file are left out
+/// of the list; use `read_in_custom_list_with_directives` to get them too.
 pub fn read_in_custom_list(file_path: &Path) -> Result<Vec<String>, String> {
-    let file_input: Vec<String> = match read_by_line(file_path.to_path_buf()) {
+    read_in_custom_list_with_limits(file_path, &ListLimits::default())
+}
+
+/// Same as `read_in_custom_list`, but also returns the directives at the top of the file.
+pub fn read_in_custom_list_with_directives(
+    file_path: &Path,
+) -> Result<(Vec<String>, ListDirectives), String> {
+    custom_list_and_directives_from_lines(lines_from_reader(
+        open_list_file(file_path)?,
+        &ListLimits::default(),
+    ))
+}
+
+/// Same as `read_in_custom_list`, with your own limits on how long the file's lines can be and
+/// how many of them there can be.
+pub fn read_in_custom_list_with_limits(
+    file_path: &Path,
+    limits: &ListLimits,
+) -> Result<Vec<String>, String> {
+    custom_list_and_directives_from_lines(lines_from_reader(open_list_file(file_path)?, limits))
+        .map(|(list, _)| list)
+}
+
+/// Same as `read_in_custom_list`, but reads the list from anything that can be read line by
+/// line, rather than a file. Any problem with the input (like invalid UTF-8) is returned as an
+/// error; this never panics, no matter what it's given.
+pub fn custom_list_from_reader(reader: impl BufRead) -> Result<Vec<String>, String> {
+    custom_list_and_directives_from_lines(lines_from_reader(reader, &ListLimits::default()))
+        .map(|(list, _)| list)
+}
+
+fn custom_list_and_directives_from_lines(
+    lines: io::Result<Vec<String>>,
+) -> Result<(Vec<String>, ListDirectives), String> {
+    let mut file_input: Vec<String> = match lines {
         Ok(r) => r,
-        Err(e) => return Err(format!("Error reading word list file: {}", e)),
+        Err(e) => return Err(text(Message::ListReadFailed, &[&e])),
+    };
+    strip_byte_order_mark(&mut file_input);
+    check_for_stray_carriage_returns(&file_input)?;
+    let directives = take_directives(&mut file_input)?;
+    Ok((finish_custom_list(file_input), directives))
+}
+
+/// Clean up a custom list's words with `clean_up_words`, warning about anything the user should
+/// know about.
+fn finish_custom_list(words: Vec<String>) -> Vec<String> {
+    let cleaned = clean_up_words(words);
+    report_normalized_duplicates(cleaned.normalized_duplicates);
+    if !uniform_unicode_normalization(&cleaned.words) {
+        eprintln!("{}", text(Message::MixedNormalization, &[]));
+    }
+    cleaned.words
+}
+
+/// Format a custom list file is in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ListFormat {
+    /// One word per line, with optional `ListDirectives` at the top
+    Text,
+    /// Either an array of words, like `["abacus", "abbey"]`, or an object with a `words` array
+    /// in it, and optionally `name` and `source` strings
+    Json,
+    /// A `words = [...]` array, and optionally `name` and `source` strings
+    Toml,
+}
+
+impl ListFormat {
+    /// Work out a list file's format from its extension: `.json` or `.toml`, and anything else is
+    /// text.
+    pub fn from_path(file_path: &Path) -> ListFormat {
+        match file_path
+            .extension()
+            .and_then(|extension| extension.to_str())
+        {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => ListFormat::Json,
+            Some(extension) if extension.eq_ignore_ascii_case("toml") => ListFormat::Toml,
+            _ => ListFormat::Text,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            ListFormat::Text => "text",
+            ListFormat::Json => "JSON",
+            ListFormat::Toml => "TOML",
+        }
+    }
+}
+
+/// Information about a list that a JSON or TOML list can give along with its words.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ListMetadata {
+    /// What the list is called
+    pub name: Option<String>,
+    /// Where the list came from, like a URL
+    pub source: Option<String>,
+}
+
+/// Read a JSON or TOML word list (see `ListFormat`), along with any metadata it has. The words
+/// get the same clean-up as a text list's (trimming, sorting and removing duplicates). Errors
+/// say which file had the problem and, for files that can't be parsed, where in it.
+///
+/// Needs the `structured-lists` feature. Text lists are read with `read_in_custom_list` as usual.
+pub fn read_in_structured_list(
+    file_path: &Path,
+    format: ListFormat,
+) -> Result<(Vec<String>, ListMetadata), String> {
+    let mut contents = String::new();
+    if let Err(e) = open_list_file(file_path)?.read_to_string(&mut contents) {
+        return Err(text(Message::ListReadFailed, &[&e]));
+    }
+    let contents = contents.strip_prefix('\u{feff}').unwrap_or(&contents);
+    let parsed = match format {
+        ListFormat::Json => words_from_json(contents),
+        ListFormat::Toml => words_from_toml(contents),
+        ListFormat::Text => Err("Text lists aren't structured lists".to_string()),
+    };
+    match parsed {
+        Ok((words, metadata)) => Ok((finish_custom_list(words), metadata)),
+        Err(e) => Err(format!(
+            "Error reading {} word list {}: {}",
+            format.name(),
+            file_path.display(),
+            e
+        )),
+    }
+}
+
+#[cfg(feature = "structured-lists")]
+fn words_from_json(contents: &str) -> Result<(Vec<String>, ListMetadata), String> {
+    use serde_json::Value;
+    let value: Value = serde_json::from_str(contents).map_err(|e| e.to_string())?;
+    let (words, metadata) = match value {
+        Value::Array(words) => (words, ListMetadata::default()),
+        Value::Object(mut object) => {
+            let metadata_string = |key: &str| match object.get(key) {
+                None => Ok(None),
+                Some(Value::String(value)) => Ok(Some(value.clone())),
+                Some(_) => Err(format!("\"{}\" should be a string", key)),
+            };
+            let metadata = ListMetadata {
+                name: metadata_string("name")?,
+                source: metadata_string("source")?,
+            };
+            match object.remove("words") {
+                Some(Value::Array(words)) => (words, metadata),
+                Some(_) => return Err("\"words\" should be an array of strings".to_string()),
+                None => return Err("Should have a \"words\" array".to_string()),
+            }
+        }
+        _ => {
+            return Err(
+                "Should be an array of words, or an object with a \"words\" array".to_string(),
+            )
+        }
     };
+    let words = words
+        .into_iter()
+        .enumerate()
+        .map(|(index, word)| match word {
+            Value::String(word) => Ok(word),
+            _ => Err(format!("Word {} of \"words\" isn't a string", index + 1)),
+        })
+        .collect::<Result<Vec<String>, String>>()?;
+    Ok((words, metadata))
+}
+
+#[cfg(feature = "structured-lists")]
+fn words_from_toml(contents: &str) -> Result<(Vec<String>, ListMetadata), String> {
+    use toml::Value;
+    let mut table: toml::Table = match contents.parse() {
+        Ok(table) => table,
+        // The error's own message shows the line it's on, over several lines, so put it on one
+        // line like a JSON error
+        Err(e) => {
+            let e: toml::de::Error = e;
+            return Err(match e.span() {
+                Some(span) => {
+                    let before = &contents[..span.start];
+                    let line = before.matches('\n').count() + 1;
+                    let column = before[before.rfind('\n').map_or(0, |i| i + 1)..]
+                        .chars()
+                        .count()
+                        + 1;
+                    format!("{} at line {} column {}", e.message(), line, column)
+                }
+                None => e.message().to_string(),
+            });
+        }
+    };
+    let metadata_string = |key: &str| match table.get(key) {
+        None => Ok(None),
+        Some(Value::String(value)) => Ok(Some(value.clone())),
+        Some(_) => Err(format!("\"{}\" should be a string", key)),
+    };
+    let metadata = ListMetadata {
+        name: metadata_string("name")?,
+        source: metadata_string("source")?,
+    };
+    let words = match table.remove("words") {
+        Some(Value::Array(words)) => words,
+        Some(_) => return Err("\"words\" should be an array of strings".to_string()),
+        None => return Err("Should have a \"words = [...]\" array".to_string()),
+    };
+    let words = words
+        .into_iter()
+        .enumerate()
+        .map(|(index, word)| match word {
+            Value::String(word) => Ok(word),
+            _ => Err(format!("Word {} of \"words\" isn't a string", index + 1)),
+        })
+        .collect::<Result<Vec<String>, String>>()?;
+    Ok((words, metadata))
+}
+
+#[cfg(not(feature = "structured-lists"))]
+fn words_from_json(_contents: &str) -> Result<(Vec<String>, ListMetadata), String> {
+    Err("This version of Phraze was built without support for JSON and TOML lists (the `structured-lists` feature)".to_string())
+}
+
+#[cfg(not(feature = "structured-lists"))]
+fn words_from_toml(contents: &str) -> Result<(Vec<String>, ListMetadata), String> {
+    words_from_json(contents)
+}
+
+/// A plain custom list's words after `clean_up_words`, along with how many lines each step
+/// changed.
+struct CleanedWords {
+    words: Vec<String>,
+    trimmed: usize,
+    blank_lines: usize,
+    duplicates: usize,
+    normalized_duplicates: usize,
+}
+
+/// The cleanup every plain custom list gets: trimming whitespace, dropping blank lines, sorting,
+/// and removing duplicates, including words that only differ in their Unicode normalization.
+fn clean_up_words(lines: Vec<String>) -> CleanedWords {
+    let mut trimmed = 0;
+    let mut blank_lines = 0;
     let mut word_list: Vec<String> = vec![];
-    for line in file_input {
+    for line in lines {
         // Don't add blank lines or lines made up purely of whitespace
         if line.trim() != "" {
             // Remove any starting or trailing whitespace before adding word to list
+            if line.trim() != line {
+                trimmed += 1;
+            }
             word_list.push(line.trim().to_string());
+        } else {
+            blank_lines += 1;
         }
     }
     // Remove any duplicate words, since duplicate words would undermine entropy estimates.
     word_list.sort();
+    let length_before_dedup = word_list.len();
     word_list.dedup();
-    if !uniform_unicode_normalization(&word_list) {
-        eprintln!("WARNING: Custom word list has multiple Unicode normalizations. Consider normalizing the Unicode of all words on the list before making a passphrase.");
+    let duplicates = length_before_dedup - word_list.len();
+    // Words that only differ in their Unicode normalization look identical, so they're
+    // duplicates too
+    let normalized_duplicates = dedup_normalized(&mut word_list);
+    CleanedWords {
+        words: word_list,
+        trimmed,
+        blank_lines,
+        duplicates,
+        normalized_duplicates,
     }
-    Ok(word_list)
 }
 
-/// Generatic function that reads a file in, line by line.
-/// Not sure if all of this is necessary, but it gets the job done.
-fn read_by_line<T: FromStr>(file_path: PathBuf) -> io::Result<Vec<T>>
-where
-    <T as std::str::FromStr>::Err: std::fmt::Debug,
-{
-    let mut vec = Vec::new();
-    let f = match File::open(file_path) {
-        Ok(res) => res,
-        Err(e) => return Err(e),
+/// Fewest words a list needs to be any use. A list with only one word adds no entropy at all.
+pub const MIN_USABLE_LIST_LENGTH: usize = 2;
+
+/// A custom list cleaned up by `clean_list`, ready to be written back out.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CleanedList {
+    /// The list's words, just as `read_in_custom_list` would read them
+    pub words: Vec<String>,
+    /// Any directives from the top of the list, which are kept
+    pub directives: ListDirectives,
+    /// How much each cleaning step changed
+    pub report: CleaningReport,
+}
+
+/// How many lines (or words) each of `clean_list`'s steps changed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CleaningReport {
+    /// Lines that had whitespace trimmed off
+    pub trimmed: usize,
+    /// Blank lines dropped
+    pub blank_lines: usize,
+    /// Lines that had diceware numbers taken off
+    pub diceware_numbers: usize,
+    /// Words rewritten in Unicode Normalization Form C
+    pub normalized: usize,
+    /// Exact duplicates removed (including words that were only different before they were
+    /// normalized)
+    pub duplicates: usize,
+    /// Words removed because they only differed from another word in case, with `case_fold`
+    pub case_duplicates: usize,
+}
+
+/// Clean up a custom list so it can be shared, with the same steps `read_in_custom_list` takes
+/// (trimming whitespace, dropping blank lines, sorting and removing duplicates), plus a few
+/// more, done first:
+///
+/// - taking off diceware numbers, if every line starts with one (like `11111 abacus`)
+/// - rewriting every word in Unicode Normalization Form C
+/// - with `case_fold`, removing words that only differ from another word in case (keeping the
+///   first one, in sorted order)
+///
+/// The result reads back in exactly the same, with `read_in_custom_list`, after it's written out
+/// with `format_cleaned_list`. Directives are kept, but any other line starting with `#` is a
+/// word to `read_in_custom_list`, so it's kept as one.
+pub fn clean_list(file_path: &Path, case_fold: bool) -> Result<CleanedList, String> {
+    let mut lines = match lines_from_reader(open_list_file(file_path)?, &ListLimits::default()) {
+        Ok(lines) => lines,
+        Err(e) => return Err(text(Message::ListReadFailed, &[&e])),
+    };
+    strip_byte_order_mark(&mut lines);
+    check_for_stray_carriage_returns(&lines)?;
+    let directives = take_directives(&mut lines)?;
+
+    let mut report = CleaningReport {
+        diceware_numbers: strip_diceware_numbers(&mut lines),
+        ..Default::default()
+    };
+    for line in lines.iter_mut() {
+        let normalized = nfc_form(line);
+        if normalized != *line {
+            *line = normalized;
+            report.normalized += 1;
+        }
+    }
+
+    // From here, this is just what reading the list in would do
+    let cleaned = clean_up_words(lines);
+    report.trimmed = cleaned.trimmed;
+    report.blank_lines = cleaned.blank_lines;
+    report.duplicates = cleaned.duplicates + cleaned.normalized_duplicates;
+    let mut words = cleaned.words;
+    if case_fold {
+        let length_before_case_fold = words.len();
+        let mut seen = HashSet::new();
+        words.retain(|word| seen.insert(word.to_lowercase()));
+        report.case_duplicates = length_before_case_fold - words.len();
+    }
+    Ok(CleanedList {
+        words,
+        directives,
+        report,
+    })
+}
+
+/// Read a custom list's words as they're written, for checking the list over: whitespace is
+/// trimmed and blank lines and directives are left out, but the words aren't sorted, and
+/// duplicates are kept.
+pub fn read_in_words_as_written(file_path: &Path) -> Result<Vec<String>, String> {
+    let mut lines = match lines_from_reader(open_list_file(file_path)?, &ListLimits::default()) {
+        Ok(lines) => lines,
+        Err(e) => return Err(text(Message::ListReadFailed, &[&e])),
+    };
+    strip_byte_order_mark(&mut lines);
+    check_for_stray_carriage_returns(&lines)?;
+    take_directives(&mut lines)?;
+    Ok(lines
+        .iter()
+        .map(|line| line.trim())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_string())
+        .collect())
+}
+
+/// Where most Unix-like systems keep their dictionary of words, one per line.
+pub const SYSTEM_DICTIONARY_PATH: &str = "/usr/share/dict/words";
+
+/// The words Phraze kept from a system dictionary, and how many entries it had to start with.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SystemDictionary {
+    pub words: Vec<String>,
+    pub entries: usize,
+}
+
+/// Read in a system dictionary, like `/usr/share/dict/words`, as a word list. These are made
+/// for spell checkers, not passphrases, so entries are filtered first: anything starting with an
+/// uppercase letter (proper nouns, like "Alabama"), possessives (like "aardvark's") and entries
+/// with fewer than `min_length` or more than `max_length` characters are left out. What's left
+/// is cleaned up like any other custom list.
+pub fn read_in_system_dictionary(
+    file_path: &Path,
+    min_length: usize,
+    max_length: usize,
+) -> Result<SystemDictionary, String> {
+    if !file_path.exists() {
+        return Err(format!(
+            "There's no dictionary at {}. Most Linux distributions have one in a package: wamerican (or another language's) on Debian and Ubuntu, words on Fedora and Arch. Or give --system-dict the path to a dictionary somewhere else.",
+            file_path.display()
+        ));
+    }
+    let mut lines = match lines_from_reader(open_list_file(file_path)?, &ListLimits::default()) {
+        Ok(lines) => lines,
+        Err(e) => return Err(text(Message::ListReadFailed, &[&e])),
+    };
+    strip_byte_order_mark(&mut lines);
+    let entries = lines.iter().filter(|line| !line.trim().is_empty()).count();
+    let kept = lines
+        .into_iter()
+        .filter(|line| is_passphrase_worthy_entry(line.trim(), min_length, max_length))
+        .collect();
+    Ok(SystemDictionary {
+        words: finish_custom_list(kept),
+        entries,
+    })
+}
+
+/// Most of a list command's error output to show when it fails, in bytes.
+const MAX_LIST_COMMAND_STDERR_BYTES: u64 = 4096;
+
+/// Run a command and read what it prints as a word list, one word per line, like
+/// `read_in_custom_list` reads a file. The command is run directly, not through a shell, so
+/// `arguments` are passed to `program` exactly as given. Its output has the same
+/// `ListLimits` as a file.
+///
+/// If the command fails, the error has its exit status and what it printed to standard error.
+/// If it hasn't finished after `timeout`, it's killed.
+pub fn read_in_list_from_command(
+    program: &str,
+    arguments: &[String],
+    timeout: Duration,
+) -> Result<Vec<String>, String> {
+    let mut child = match Command::new(program)
+        .args(arguments)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => return Err(format!("Couldn't run list command {}: {}", program, e)),
+    };
+    // Read both outputs as they come, so the command never blocks on a full pipe
+    let stdout = child.stdout.take().unwrap();
+    let stdout_reader =
+        thread::spawn(move || lines_from_reader(BufReader::new(stdout), &ListLimits::default()));
+    let stderr = child.stderr.take().unwrap();
+    let stderr_reader = thread::spawn(move || {
+        let mut error_output = vec![];
+        let mut stderr = stderr;
+        let _ = (&mut stderr)
+            .take(MAX_LIST_COMMAND_STDERR_BYTES)
+            .read_to_end(&mut error_output);
+        // Keep reading (and throwing away) the rest, so the command doesn't get stuck
+        let _ = io::copy(&mut stderr, &mut io::sink());
+        String::from_utf8_lossy(&error_output).trim().to_string()
+    });
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if started.elapsed() >= timeout => {
+                // Waiting after killing it means it doesn't linger as a zombie process
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "List command {} took longer than --list-cmd-timeout ({} seconds), so it was stopped",
+                    program,
+                    timeout.as_secs_f64()
+                ));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(10)),
+            Err(e) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("Couldn't wait for list command {}: {}", program, e));
+            }
+        }
     };
-    let file = BufReader::new(&f);
-    for line in file.lines() {
-        match line?.parse() {
-            Ok(l) => vec.push(l),
-            Err(e) => panic!("Error parsing line from file: {:?}", e),
+    // If the output went past the limits, we stopped reading it, so the command was likely
+    // stopped by the closed pipe. That's the error to report.
+    let lines = match stdout_reader.join().unwrap() {
+        Ok(lines) => lines,
+        Err(e) => {
+            return Err(format!(
+                "Error reading list command {}'s output: {}",
+                program, e
+            ))
+        }
+    };
+    let error_output = stderr_reader.join().unwrap();
+    if !status.success() {
+        return Err(match error_output.is_empty() {
+            true => format!("List command {} failed ({})", program, status),
+            false => format!(
+                "List command {} failed ({}): {}",
+                program, status, error_output
+            ),
+        });
+    }
+    if lines.iter().all(|line| line.trim().is_empty()) {
+        return Err(format!("List command {} didn't print any words", program));
+    }
+    custom_list_and_directives_from_lines(Ok(lines)).map(|(list, _)| list)
+}
+
+fn is_passphrase_worthy_entry(entry: &str, min_length: usize, max_length: usize) -> bool {
+    let starts_with_uppercase = entry.chars().next().is_some_and(char::is_uppercase);
+    let possessive = entry.ends_with("'s") || entry.ends_with("\u{2019}s");
+    let length = grapheme_length(entry);
+    !starts_with_uppercase && !possessive && (min_length..=max_length).contains(&length)
+}
+
+/// Write out a cleaned list, with its directives at the top and one word per line.
+pub fn format_cleaned_list(cleaned_list: &CleanedList) -> String {
+    let mut text = String::new();
+    if let Some(ref separator) = cleaned_list.directives.separator {
+        // Quote separators that wouldn't otherwise read back in the same, like a space
+        let needs_quotes = separator.trim() != separator
+            || separator.is_empty()
+            || (separator.len() > 1 && separator.starts_with('\'') && separator.ends_with('\''));
+        match needs_quotes {
+            true => text.push_str(&format!("# separator: '{}'\n", separator)),
+            false => text.push_str(&format!("# separator: {}\n", separator)),
+        }
+    }
+    match cleaned_list.directives.title_case {
+        Some(true) => text.push_str("# case: title\n"),
+        Some(false) => text.push_str("# case: lower\n"),
+        None => {}
+    }
+    for word in &cleaned_list.words {
+        text.push_str(word);
+        text.push('\n');
+    }
+    text
+}
+
+/// Diceware lists number each word with the dice rolls that pick it, like `11111 abacus` or
+/// `1-1-1-1-1<TAB>abacus`. If every line that isn't blank starts with a number like that, take
+/// the numbers off, returning how many lines had one. Otherwise, leave the lines alone, since
+/// some of the words might just start with numbers.
+fn strip_diceware_numbers(lines: &mut [String]) -> usize {
+    fn split_number(line: &str) -> Option<&str> {
+        let (number, word) = line.trim().split_once(char::is_whitespace)?;
+        let is_number = number.chars().any(|c| c.is_ascii_digit())
+            && number.chars().all(|c| c.is_ascii_digit() || c == '-');
+        match is_number && !word.trim().is_empty() {
+            true => Some(word.trim()),
+            false => None,
+        }
+    }
+    let mut non_blank_lines = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .peekable();
+    if non_blank_lines.peek().is_none() || !non_blank_lines.all(|line| split_number(line).is_some())
+    {
+        return 0;
+    }
+    let mut stripped = 0;
+    for line in lines.iter_mut() {
+        if let Some(word) = split_number(line) {
+            *line = word.to_string();
+            stripped += 1;
+        }
+    }
+    stripped
+}
+
+/// Take any `ListDirectives` off the top of a list's lines. Directives stop at the first line
+/// that isn't one.
+fn take_directives(lines: &mut Vec<String>) -> Result<ListDirectives, String> {
+    let mut directives = ListDirectives::default();
+    let mut directive_lines = 0;
+    for line in lines.iter() {
+        let (key, value) = match line
+            .strip_prefix('#')
+            .and_then(|directive| directive.split_once(':'))
+        {
+            Some(directive) => directive,
+            None => break,
+        };
+        // Only take off one space after the colon, since a separator could be a space
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match key.trim() {
+            "separator" => {
+                let unquoted = value
+                    .strip_prefix('\'')
+                    .and_then(|value| value.strip_suffix('\''));
+                directives.separator = Some(unquoted.unwrap_or(value).to_string());
+            }
+            "case" => {
+                directives.title_case = match value.trim() {
+                    "title" => Some(true),
+                    "lower" => Some(false),
+                    other => {
+                        return Err(format!(
+                        "Word list file asks for case '{}', but it can only be 'title' or 'lower'",
+                        other
+                    ))
+                    }
+                }
+            }
+            _ => break,
+        }
+        directive_lines += 1;
+    }
+    lines.drain(..directive_lines);
+    Ok(directives)
+}
+
+/// Read a two-column text file, with a word and a weight on each line separated by a tab
+/// (`word<TAB>weight`), into a Vec of words and their weights. Lines without a weight get a
+/// weight of 1. Weights must be positive numbers. Otherwise, this cleans up the list just like
+/// `read_in_custom_list` does. If a word appears more than once, its weights are added up.
+pub fn read_in_weighted_custom_list(file_path: &Path) -> Result<Vec<(String, f64)>, String> {
+    weighted_custom_list_from_reader(open_list_file(file_path)?)
+}
+
+/// Same as `read_in_weighted_custom_list`, but reads the list from anything that can be read
+/// line by line, rather than a file. Like `custom_list_from_reader`, this never panics.
+pub fn weighted_custom_list_from_reader(
+    reader: impl BufRead,
+) -> Result<Vec<(String, f64)>, String> {
+    let mut file_input: Vec<String> = match lines_from_reader(reader, &ListLimits::default()) {
+        Ok(r) => r,
+        Err(e) => return Err(text(Message::ListReadFailed, &[&e])),
+    };
+    strip_byte_order_mark(&mut file_input);
+    check_for_stray_carriage_returns(&file_input)?;
+    let mut weighted_list: Vec<(String, f64)> = vec![];
+    for (line_number, line) in file_input.iter().enumerate() {
+        let (word, weight) = match line.split_once('\t') {
+            Some((word, weight)) => (word.trim(), parse_weight(weight, line_number + 1)?),
+            None => (line.trim(), 1.0),
+        };
+        // Don't add blank lines or lines made up purely of whitespace
+        if !word.is_empty() {
+            weighted_list.push((word.to_string(), weight));
+        }
+    }
+    // Combine any duplicate words, since duplicate words would undermine entropy estimates.
+    // Words that only differ in their Unicode normalization look identical, so compare words
+    // in Normalization Form C
+    weighted_list.sort_by_cached_key(|(word, _)| nfc_form(word));
+    let length_before_dedup = weighted_list.len();
+    let mut exact_duplicates = 0;
+    weighted_list.dedup_by(|duplicate, kept| {
+        if duplicate.0 == kept.0 {
+            exact_duplicates += 1;
+        }
+        if nfc_form(&duplicate.0) == nfc_form(&kept.0) {
+            kept.1 += duplicate.1;
+            true
+        } else {
+            false
+        }
+    });
+    report_normalized_duplicates(length_before_dedup - weighted_list.len() - exact_duplicates);
+    // Adding up a duplicate word's weights could overflow
+    if let Some((word, _)) = weighted_list.iter().find(|(_, weight)| !weight.is_finite()) {
+        return Err(format!(
+            "Total weight of word '{}' in word list file is too large",
+            word
+        ));
+    }
+    let words: Vec<String> = weighted_list.iter().map(|(word, _)| word.clone()).collect();
+    if !uniform_unicode_normalization(&words) {
+        eprintln!("{}", text(Message::MixedNormalization, &[]));
+    }
+    Ok(weighted_list)
+}
+
+/// Read a word list annotated with parts of speech, as a two-column CSV file with a word and its
+/// part of speech on each line (`word,pos`, like `happy,adj`). Parts of speech can be any label
+/// you like; they're lowercased so that "Noun" and "noun" match. A word can appear more than once
+/// with different parts of speech. Otherwise, the list is cleaned up like `read_in_custom_list`
+/// does: blank lines are skipped, whitespace is trimmed, and exact duplicates are removed.
+pub fn read_in_part_of_speech_list(file_path: &Path) -> Result<Vec<(String, String)>, String> {
+    part_of_speech_list_from_reader(open_list_file(file_path)?)
+}
+
+/// Same as `read_in_part_of_speech_list`, but reads the list from anything that can be read
+/// line by line, rather than a file.
+pub fn part_of_speech_list_from_reader(
+    reader: impl BufRead,
+) -> Result<Vec<(String, String)>, String> {
+    let mut file_input: Vec<String> = match lines_from_reader(reader, &ListLimits::default()) {
+        Ok(r) => r,
+        Err(e) => return Err(text(Message::ListReadFailed, &[&e])),
+    };
+    strip_byte_order_mark(&mut file_input);
+    check_for_stray_carriage_returns(&file_input)?;
+    let mut annotated_list = vec![];
+    for (line_number, line) in file_input.iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match line.split_once(',') {
+            Some((word, part_of_speech))
+                if !word.trim().is_empty() && !part_of_speech.trim().is_empty() =>
+            {
+                annotated_list.push((
+                    word.trim().to_string(),
+                    part_of_speech.trim().to_lowercase(),
+                ))
+            }
+            _ => {
+                return Err(format!(
+                    "Line {} of word list file should be a word, a comma, and a part of speech",
+                    line_number + 1
+                ))
+            }
+        }
+    }
+    annotated_list.sort();
+    annotated_list.dedup();
+    Ok(annotated_list)
+}
+
+/// Read a word list with a definition (like a translation, for a bilingual list) for each word,
+/// from a CSV file. `word_column` and `definition_column` say which columns (counting from 1)
+/// hold the word and its definition, so an `english,spanish,notes` file can be read either way
+/// around. Columns are split on commas, without any quoting, so words and definitions can't
+/// have commas in them. Blank lines are skipped and whitespace is trimmed. If a word appears
+/// more than once, its different definitions are joined with "; ". Returns each word and its
+/// definition, sorted by word.
+pub fn read_in_definitions_list(
+    file_path: &Path,
+    word_column: usize,
+    definition_column: usize,
+) -> Result<Vec<(String, String)>, String> {
+    definitions_list_from_reader(open_list_file(file_path)?, word_column, definition_column)
+}
+
+/// Same as `read_in_definitions_list`, but reads the list from anything that can be read line
+/// by line, rather than a file.
+pub fn definitions_list_from_reader(
+    reader: impl BufRead,
+    word_column: usize,
+    definition_column: usize,
+) -> Result<Vec<(String, String)>, String> {
+    if word_column == 0 || definition_column == 0 {
+        return Err("Columns are counted from 1".to_string());
+    }
+    let mut file_input: Vec<String> = match lines_from_reader(reader, &ListLimits::default()) {
+        Ok(r) => r,
+        Err(e) => return Err(text(Message::ListReadFailed, &[&e])),
+    };
+    strip_byte_order_mark(&mut file_input);
+    check_for_stray_carriage_returns(&file_input)?;
+    let mut defined_list: Vec<(String, String)> = vec![];
+    for (line_number, line) in file_input.iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let columns: Vec<&str> = line.split(',').map(|column| column.trim()).collect();
+        match (columns.get(word_column - 1), columns.get(definition_column - 1)) {
+            (Some(word), Some(definition)) if !word.is_empty() => {
+                defined_list.push((word.to_string(), definition.to_string()))
+            }
+            _ => {
+                return Err(format!(
+                    "Line {} of word list file should have a word in column {} and a definition in column {}",
+                    line_number + 1,
+                    word_column,
+                    definition_column
+                ))
+            }
+        }
+    }
+    defined_list.sort();
+    defined_list.dedup();
+    // Combine the definitions of any word that's in the list more than once
+    defined_list.dedup_by(|duplicate, kept| {
+        if duplicate.0 == kept.0 {
+            kept.1 = format!("{}; {}", kept.1, duplicate.1);
+            true
+        } else {
+            false
+        }
+    });
+    Ok(defined_list)
+}
+
+/// Let the user know if we merged words that only differed in their Unicode normalization.
+fn report_normalized_duplicates(merged: usize) {
+    if merged > 0 {
+        eprintln!("Note: Merged {} words in the word list file that were identical apart from their Unicode normalization", merged);
+    }
+}
+
+/// Files saved by some Windows editors (like Notepad) start with an invisible byte-order mark
+/// (BOM). Trimming whitespace doesn't remove it, so if we left it there, it would quietly become
+/// part of the first word. Remove it, and let the user know we did.
+fn strip_byte_order_mark(lines: &mut [String]) {
+    if let Some(first_line) = lines.first_mut() {
+        if let Some(without_bom) = first_line.strip_prefix('\u{feff}') {
+            *first_line = without_bom.to_string();
+            eprintln!("Note: Removed a byte-order mark (BOM) from the start of the word list file");
+        }
+    }
+}
+
+/// Line endings ("\n" or Windows' "\r\n", even mixed in one file) are taken off as lines are
+/// read. But a file with old Mac-style line endings (just "\r") would come through as a single
+/// line, and a "\r" left in the middle of a word could make entries look unique when they're
+/// not, so that's an error.
+fn check_for_stray_carriage_returns(lines: &[String]) -> Result<(), String> {
+    match lines.iter().position(|line| line.trim_end().contains('\r')) {
+        Some(line_index) => Err(format!(
+            "Line {} of word list file has a carriage return (\\r) in it. If the file uses old Mac-style line endings, convert them to \\n or \\r\\n first.",
+            line_index + 1
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Parse the weight column of a weighted word list, making sure it's a positive number.
+fn parse_weight(weight: &str, line_number: usize) -> Result<f64, String> {
+    match weight.trim().parse::<f64>() {
+        Ok(weight) if weight > 0.0 && weight.is_finite() => Ok(weight),
+        _ => Err(format!(
+            "Weight on line {} of word list file ('{}') isn't a positive number",
+            line_number,
+            weight.trim()
+        )),
+    }
+}
+
+/// Open a word list file for reading line by line. The "file" can also be a named pipe (FIFO) or,
+/// on Unix, a Unix socket, for lists streamed from another program. Either way, we read until the
+/// other end closes it, so this blocks until the whole list has been sent (and, for a named pipe,
+/// until something opens it for writing).
+fn open_list_file(file_path: &Path) -> Result<Box<dyn BufRead>, String> {
+    // A Unix socket (like one a daemon serves lists from) has to be connected to, rather than
+    // opened like a file
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+        use std::os::unix::net::UnixStream;
+        if let Ok(metadata) = std::fs::metadata(file_path) {
+            if metadata.file_type().is_socket() {
+                return match UnixStream::connect(file_path) {
+                    Ok(stream) => Ok(Box::new(BufReader::new(stream))),
+                    Err(e) => Err(format!("Error connecting to word list socket: {}", e)),
+                };
+            }
+        }
+    }
+    // Anything else that can be opened and read, including named pipes, is read like a file
+    match File::open(file_path) {
+        Ok(f) => Ok(Box::new(BufReader::new(f))),
+        Err(e) => Err(text(Message::ListReadFailed, &[&e])),
+    }
+}
+
+/// Read a text file, line by line, without any of the cleanup the list readers do. Any problem
+/// opening or reading the file (like invalid UTF-8, or going past the default `ListLimits`) is
+/// returned as an error.
+pub fn read_lines(file_path: &Path) -> io::Result<Vec<String>> {
+    lines_from_reader(
+        BufReader::new(File::open(file_path)?),
+        &ListLimits::default(),
+    )
+}
+
+/// Read lines like `BufRead::lines` does (taking off "\n" or "\r\n" line endings), but never
+/// holding more than one line past the limits in memory.
+fn lines_from_reader(mut reader: impl BufRead, limits: &ListLimits) -> io::Result<Vec<String>> {
+    let mut lines = vec![];
+    let mut line = vec![];
+    loop {
+        line.clear();
+        // Leave room for a "\r\n" after a line that's exactly as long as it's allowed to be
+        let bytes_read = (&mut reader)
+            .take(limits.max_line_bytes as u64 + 2)
+            .read_until(b'\n', &mut line)?;
+        if bytes_read == 0 {
+            return Ok(lines);
+        }
+        if line.last() == Some(&b'\n') {
+            line.pop();
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+        }
+        if line.len() > limits.max_line_bytes {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Line {} is longer than {} bytes, so this doesn't look like a word list",
+                    lines.len() + 1,
+                    limits.max_line_bytes
+                ),
+            ));
+        }
+        if lines.len() == limits.max_lines {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("File has more than {} lines", limits.max_lines),
+            ));
+        }
+        match String::from_utf8(line.clone()) {
+            Ok(line) => lines.push(line),
+            Err(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Line {} isn't valid UTF-8", lines.len() + 1),
+                ))
+            }
         }
     }
-    Ok(vec)
 }
@@ -0,0 +1,53 @@
+//! Entry points for Phraze's fuzz targets (see the fuzz/ directory). Each one takes arbitrary
+//! bytes and feeds them to one of Phraze's parsers. None of them should ever panic: bad input
+//! must always come back as an error.
+use crate::assemble_passphrase;
+use crate::file_reader::{custom_list_from_reader, weighted_custom_list_from_reader};
+use crate::separators::{
+    check_min_distinct_separators, generate_distinct_separators, is_generated_separator,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// Feed arbitrary bytes to the custom word list reader, as if they were a word list file.
+pub fn fuzz_custom_list(data: &[u8]) {
+    if let Ok(list) = custom_list_from_reader(data) {
+        // Whatever made it through should be a clean list
+        assert!(list
+            .iter()
+            .all(|word| !word.is_empty() && word.trim() == word));
+        assert!(list.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
+
+/// Feed arbitrary bytes to the weighted word list reader, as if they were a word list file.
+pub fn fuzz_weighted_custom_list(data: &[u8]) {
+    if let Ok(list) = weighted_custom_list_from_reader(data) {
+        assert!(list
+            .iter()
+            .all(|(word, weight)| !word.is_empty() && *weight > 0.0 && weight.is_finite()));
+    }
+}
+
+/// Use arbitrary bytes as a separator, including the special values that generate random
+/// separators. The first byte picks how many words to join.
+pub fn fuzz_separator(data: &[u8]) {
+    let (number_of_words, separator) = match data.split_first() {
+        Some((first, rest)) => ((*first % 16) as usize, String::from_utf8_lossy(rest)),
+        None => return,
+    };
+    let words = vec!["word"; number_of_words];
+    let mut rng = StdRng::seed_from_u64(0);
+    let passphrase = assemble_passphrase(&mut rng, &words, &separator, false);
+    let gaps = number_of_words.saturating_sub(1);
+    if is_generated_separator(&separator) {
+        assert_eq!(passphrase.len(), number_of_words * 4 + gaps);
+    } else {
+        assert_eq!(passphrase, words.join(&separator));
+    }
+    for min_distinct in 0..3 {
+        if check_min_distinct_separators(&separator, number_of_words, min_distinct).is_ok() {
+            assert!(generate_distinct_separators(&mut rng, &separator, gaps, min_distinct).is_ok());
+        }
+    }
+}
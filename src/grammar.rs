@@ -0,0 +1,104 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Fewest words a part of speech can have for it to be used in a grammar. A slot with only one
+/// possible word adds no entropy at all.
+pub const MIN_WORDS_PER_PART_OF_SPEECH: usize = 2;
+
+/// Turn a grammar, like "adj noun verb noun", into the candidate words for each slot, using a
+/// list of words annotated with their parts of speech (see
+/// `file_reader::read_in_part_of_speech_list`). Returns an error if a slot's part of speech has
+/// fewer than `MIN_WORDS_PER_PART_OF_SPEECH` words in the list.
+pub fn parse_grammar<'a>(
+    grammar: &str,
+    annotated_list: &'a [(String, String)],
+) -> Result<Vec<Vec<&'a str>>, String> {
+    let slots: Vec<Vec<&str>> = grammar
+        .split_whitespace()
+        .map(|part_of_speech| {
+            let part_of_speech = part_of_speech.to_lowercase();
+            let candidates: Vec<&str> = annotated_list
+                .iter()
+                .filter(|(_, word_part_of_speech)| *word_part_of_speech == part_of_speech)
+                .map(|(word, _)| word.as_str())
+                .collect();
+            if candidates.len() < MIN_WORDS_PER_PART_OF_SPEECH {
+                Err(format!(
+                    "Not enough words marked '{}' in the word list: a grammar slot needs at least {}, but there are only {}",
+                    part_of_speech,
+                    MIN_WORDS_PER_PART_OF_SPEECH,
+                    candidates.len()
+                ))
+            } else {
+                Ok(candidates)
+            }
+        })
+        .collect::<Result<_, _>>()?;
+    if slots.is_empty() {
+        return Err("Grammar needs at least one part of speech".to_string());
+    }
+    Ok(slots)
+}
+
+/// Pick one word for each slot of a grammar.
+pub fn generate_grammatical_words<'a>(rng: &mut impl Rng, slots: &[Vec<&'a str>]) -> Vec<&'a str> {
+    slots
+        .iter()
+        .map(|candidates| match candidates.choose(rng) {
+            Some(word) => *word,
+            None => panic!("Couldn't pick a random word"),
+        })
+        .collect()
+}
+
+/// Slots don't have to come from a grammar: with --per-position, each position has its own
+/// list. Make `number_of_words` slots from a sequence of lists, cycling back to the first list if
+/// there are more slots than lists.
+pub fn cycle_slots<'a>(lists: &[Vec<&'a str>], number_of_words: usize) -> Vec<Vec<&'a str>> {
+    lists
+        .iter()
+        .cycle()
+        .take(number_of_words)
+        .cloned()
+        .collect()
+}
+
+/// Fewest positions a passphrase needs to reach a minimum entropy, when positions cycle through
+/// lists of these lengths (see `cycle_slots`): we add up log2 of each position's list length
+/// until it reaches the minimum. Every list needs at least `MIN_WORDS_PER_PART_OF_SPEECH` words,
+/// or this would never finish.
+pub fn positions_needed_for_entropy(minimum_entropy: usize, list_lengths: &[usize]) -> usize {
+    assert!(list_lengths
+        .iter()
+        .all(|length| *length >= MIN_WORDS_PER_PART_OF_SPEECH));
+    let mut entropy = 0.0;
+    let mut positions = 0;
+    for length in list_lengths.iter().cycle() {
+        if entropy >= minimum_entropy as f64 {
+            break;
+        }
+        entropy += (*length as f64).log2();
+        positions += 1;
+    }
+    positions
+}
+
+/// Describe how many words each slot of a grammar can pick from, like "5 × 5 × 4 × 5".
+pub fn describe_grammar_slots(slots: &[Vec<&str>]) -> String {
+    let counts: Vec<String> = slots
+        .iter()
+        .map(|candidates| candidates.len().to_string())
+        .collect();
+    counts.join(" × ")
+}
+
+/// Entropy, in bits, of a passphrase made from a grammar. Each slot's word is picked from that
+/// slot's candidates only, so the number of possible passphrases is the product of the number of
+/// candidates for each slot, and the entropy is the sum of log2 of each slot's count. This is
+/// less than the same number of words picked from the whole list.
+pub fn grammar_entropy(slots: &[Vec<&str>]) -> f64 {
+    slots
+        .iter()
+        .map(|candidates| (candidates.len() as f64).log2())
+        .sum()
+}
@@ -0,0 +1,52 @@
+use crate::grapheme_length;
+
+/// Describe the structure of a passphrase, without revealing its words, to help the user
+/// remember it: for example, "4 words: 7-5-6-4 letters, title case".
+///
+/// Note that this does leak some information. Knowing a word's length narrows down which word
+/// it could be, so anyone who sees the hint has less guessing to do. Keep it as private as you
+/// reasonably can.
+pub fn structural_hint(words: &[&str], title_case: bool) -> String {
+    let lengths: Vec<String> = words
+        .iter()
+        .map(|word| grapheme_length(word).to_string())
+        .collect();
+    let mut hint = format!(
+        "{} {}: {} letters",
+        words.len(),
+        if words.len() == 1 { "word" } else { "words" },
+        lengths.join("-")
+    );
+    if title_case {
+        hint += ", title case";
+    }
+    hint
+}
+
+#[test]
+fn can_describe_a_passphrases_structure() {
+    assert_eq!(
+        structural_hint(&["example", "words", "recall", "test"], true),
+        "4 words: 7-5-6-4 letters, title case"
+    );
+    assert_eq!(
+        structural_hint(&["e\u{301}lan"], false),
+        "1 word: 4 letters"
+    );
+}
+
+#[test]
+fn hint_matches_a_generated_passphrase() {
+    use crate::{assemble_passphrase, fetch_list, generate_words, ListChoice};
+    let mut rng = rand::thread_rng();
+    let words = generate_words(&mut rng, 6, fetch_list(ListChoice::Medium));
+    let passphrase = assemble_passphrase(&mut rng, &words, " ", true);
+    let lengths: Vec<String> = passphrase
+        .split(' ')
+        .map(|word| grapheme_length(word).to_string())
+        .collect();
+    assert_eq!(
+        structural_hint(&words, true),
+        format!("6 words: {} letters, title case", lengths.join("-"))
+    );
+}
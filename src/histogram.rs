@@ -0,0 +1,69 @@
+use std::collections::BTreeMap;
+
+/// The widest a histogram bar can be, in characters.
+const MAX_BAR_WIDTH: usize = 50;
+
+/// Count how many times each length appears.
+pub fn length_histogram(lengths: impl Iterator<Item = usize>) -> BTreeMap<usize, usize> {
+    let mut histogram = BTreeMap::new();
+    for length in lengths {
+        *histogram.entry(length).or_insert(0) += 1;
+    }
+    histogram
+}
+
+/// Draw a histogram as plain ASCII text, one line per length, shortest first. Every length
+/// between the shortest and longest gets a line, even if its count is 0, so histograms of the
+/// same settings always line up. Bars are scaled so the most common length gets the widest bar.
+pub fn format_histogram(histogram: &BTreeMap<usize, usize>) -> String {
+    let (Some(shortest), Some(longest)) = (histogram.keys().next(), histogram.keys().last()) else {
+        return String::new();
+    };
+    let max_count = histogram.values().max().copied().unwrap_or(0).max(1);
+    let mut lines = vec![];
+    for length in *shortest..=*longest {
+        let count = histogram.get(&length).copied().unwrap_or(0);
+        // Round up, so any length that appeared at all gets at least one #
+        let bar_width = (count * MAX_BAR_WIDTH).div_ceil(max_count);
+        lines.push(format!(
+            "{:>4} | {:<width$} {}",
+            length,
+            "#".repeat(bar_width),
+            count,
+            width = MAX_BAR_WIDTH
+        ));
+    }
+    lines.join("\n")
+}
+
+#[test]
+fn can_draw_a_histogram() {
+    let histogram = length_histogram([3, 5, 5, 5, 6].into_iter());
+    assert_eq!(histogram.values().sum::<usize>(), 5);
+    let drawn = format_histogram(&histogram);
+    let lines: Vec<&str> = drawn.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("   3 | #"));
+    assert!(lines[1].starts_with("   4 |  "));
+    assert!(lines[2].ends_with(&format!("{} 3", "#".repeat(50))));
+}
+
+#[test]
+fn histogram_of_generated_passphrases_counts_every_passphrase() {
+    use crate::{fetch_list, generate_passphrase, grapheme_length, ListChoice};
+    let lengths = (0..50).map(|_| {
+        grapheme_length(&generate_passphrase(
+            4,
+            "-",
+            false,
+            fetch_list(ListChoice::Eff),
+        ))
+    });
+    let histogram = length_histogram(lengths);
+    assert_eq!(histogram.values().sum::<usize>(), 50);
+    let counts_drawn: usize = format_histogram(&histogram)
+        .lines()
+        .map(|line| line.rsplit(' ').next().unwrap().parse::<usize>().unwrap())
+        .sum();
+    assert_eq!(counts_drawn, 50);
+}
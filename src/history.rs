@@ -0,0 +1,128 @@
+use crate::messages::{text, Message};
+use crate::sinks::write_private_file;
+use rand::{thread_rng, Rng};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// If fewer than this fraction of the original list's words are still unused, warn the user that
+/// their history file is close to using up the list.
+pub const HISTORY_WARNING_FRACTION: f64 = 0.1;
+
+/// A record of words that have already been used in passphrases, so that they can be excluded
+/// from future passphrases.
+///
+/// To keep the history file from being a plaintext copy of old passphrases' words, we only store
+/// a salted SHA-256 hash of each word. The salt is random, created along with the file, and
+/// stored on the file's first line. Note that this only stops the file from being read at a
+/// glance: word lists are small, so anyone with the file and the list can hash every word in the
+/// list and see which ones have been used. So the file is only readable by its owner (mode 0600
+/// on Unix).
+///
+/// History file format is a `salt <hex>` line followed by one hex-encoded hash per line.
+#[derive(Clone, Debug)]
+pub struct History {
+    salt: String,
+    hashes: HashSet<String>,
+}
+
+impl History {
+    /// Start a brand new, empty history with a random salt.
+    pub fn new() -> Self {
+        let salt_bytes: [u8; 16] = thread_rng().gen();
+        History {
+            salt: to_hex(&salt_bytes),
+            hashes: HashSet::new(),
+        }
+    }
+
+    /// Read in a history file. If the file doesn't exist yet, start a new history (the file will
+    /// be created when the history is saved).
+    pub fn load(file_path: &Path) -> Result<Self, String> {
+        let contents = match fs::read_to_string(file_path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(History::new()),
+            Err(e) => return Err(format!("Error reading history file: {}", e)),
+        };
+        let mut lines = contents.lines();
+        let salt = match lines.next().and_then(|line| line.strip_prefix("salt ")) {
+            Some(salt) => salt.trim().to_string(),
+            None => {
+                return Err(format!(
+                    "History file {} doesn't start with a salt line",
+                    file_path.display()
+                ))
+            }
+        };
+        let hashes = lines
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect();
+        Ok(History { salt, hashes })
+    }
+
+    /// Write this history out to a file, replacing whatever was there. The file is only readable
+    /// by its owner.
+    pub fn save(&self, file_path: &Path) -> Result<(), String> {
+        // Sort the hashes so the file doesn't reveal the order words were used in
+        let mut hashes: Vec<&String> = self.hashes.iter().collect();
+        hashes.sort();
+        let mut contents = format!("salt {}\n", self.salt);
+        for hash in hashes {
+            contents += hash;
+            contents.push('\n');
+        }
+        match write_private_file(file_path, &contents) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(format!("Error writing history file: {}", e)),
+        }
+    }
+
+    /// Check whether a word has been used before.
+    pub fn contains(&self, word: &str) -> bool {
+        self.hashes.contains(&self.hash_word(word))
+    }
+
+    /// Remember that a word has been used.
+    pub fn record(&mut self, word: &str) {
+        let hash = self.hash_word(word);
+        self.hashes.insert(hash);
+    }
+
+    /// Remove every previously used word from a list.
+    pub fn exclude_used_words(&self, list: &mut Vec<&str>) {
+        list.retain(|word| !self.contains(word));
+    }
+
+    fn hash_word(&self, word: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.salt.as_bytes());
+        hasher.update(word.as_bytes());
+        to_hex(&hasher.finalize())
+    }
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// If a history file has used up most of a list, return a warning to show the user.
+pub fn pool_exhaustion_warning(remaining_words: usize, original_words: usize) -> Option<String> {
+    if (remaining_words as f64) < original_words as f64 * HISTORY_WARNING_FRACTION {
+        Some(text(
+            Message::HistoryRunningLow,
+            &[&remaining_words, &original_words],
+        ))
+    } else {
+        None
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -0,0 +1,314 @@
+use crate::sinks::write_private_file;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Calculate a fingerprint (a SHA-256 hash, in hex) of a whole word list, so we can tell whether
+/// an index file was made with the same list it's being replayed with.
+pub fn list_fingerprint<T: AsRef<str>>(list: &[T]) -> String {
+    let mut hasher = Sha256::new();
+    for word in list {
+        hasher.update(word.as_ref().as_bytes());
+        hasher.update(b"\n");
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Find where each of the given words is in a word list.
+pub fn word_indices<T: AsRef<str>>(words: &[&str], list: &[T]) -> Result<Vec<usize>, String> {
+    let positions: HashMap<&str, usize> = list
+        .iter()
+        .enumerate()
+        .map(|(i, word)| (word.as_ref(), i))
+        .collect();
+    words
+        .iter()
+        .map(|word| match positions.get(word) {
+            Some(i) => Ok(*i),
+            None => Err(format!("Word '{}' isn't in the word list", word)),
+        })
+        .collect()
+}
+
+/// Look up the words at the given positions of a word list.
+pub fn words_from_indices<'a, T: AsRef<str>>(
+    indices: &[usize],
+    list: &'a [T],
+) -> Result<Vec<&'a str>, String> {
+    indices
+        .iter()
+        .map(|i| match list.get(*i) {
+            Some(word) => Ok(word.as_ref()),
+            None => Err(format!(
+                "Index {} is out of range for a list of {} words",
+                i,
+                list.len()
+            )),
+        })
+        .collect()
+}
+
+/// Write the indices of the words chosen for one or more passphrases to a file, along with the
+/// fingerprint of the list they came from. The file has one `indices:` line per passphrase.
+///
+/// Anyone with this file and the word list can rebuild the passphrases, so the file is exactly
+/// as sensitive as the passphrases themselves. It's written so only its owner can read it (mode
+/// 0600 on Unix).
+pub fn write_index_file(
+    file_path: &Path,
+    fingerprint: &str,
+    passphrase_indices: &[Vec<usize>],
+) -> Result<(), String> {
+    let mut contents = "# Phraze word indices. KEEP THIS FILE SECRET: it is as sensitive as the passphrases it records.\n".to_string();
+    contents += &format!("list: {}\n", fingerprint);
+    for indices in passphrase_indices {
+        let indices: Vec<String> = indices.iter().map(|i| i.to_string()).collect();
+        contents += &format!("indices: {}\n", indices.join(" "));
+    }
+    match write_private_file(file_path, &contents) {
+        Ok(()) => Ok(()),
+        Err(e) => Err(format!("Error writing index file: {}", e)),
+    }
+}
+
+/// Read an index file written by `write_index_file`, returning the list fingerprint and the
+/// indices for each passphrase.
+pub fn read_index_file(file_path: &Path) -> Result<(String, Vec<Vec<usize>>), String> {
+    let contents = match fs::read_to_string(file_path) {
+        Ok(contents) => contents,
+        Err(e) => return Err(format!("Error reading index file: {}", e)),
+    };
+    let mut fingerprint = None;
+    let mut passphrase_indices = vec![];
+    for (line_number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(list) = line.strip_prefix("list:") {
+            fingerprint = Some(list.trim().to_string());
+        } else if let Some(indices) = line.strip_prefix("indices:") {
+            let indices: Result<Vec<usize>, _> =
+                indices.split_whitespace().map(|i| i.parse()).collect();
+            match indices {
+                Ok(indices) => passphrase_indices.push(indices),
+                Err(_) => {
+                    return Err(format!(
+                        "Line {} of index file has an invalid index",
+                        line_number + 1
+                    ))
+                }
+            }
+        } else {
+            return Err(format!(
+                "Line {} of index file isn't understood",
+                line_number + 1
+            ));
+        }
+    }
+    match fingerprint {
+        Some(fingerprint) => Ok((fingerprint, passphrase_indices)),
+        None => Err("Index file doesn't say which list it was made with".to_string()),
+    }
+}
+
+/// Ways to encode word indices as a short, machine-friendly string.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum IndexEncoding {
+    /// RFC 4648 base32 (A-Z and 2-7), without padding
+    Base32,
+    /// Base58, using the Bitcoin alphabet (no 0, O, I or l)
+    Base58,
+}
+
+const BASE32_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// How many bits we need to store any index into a list of the given length.
+fn bits_per_index(list_length: usize) -> usize {
+    let mut bits = 1;
+    while bits < usize::BITS as usize && (1usize << bits) < list_length {
+        bits += 1;
+    }
+    bits
+}
+
+/// Pack word indices into a compact string. The first byte is the number of words; after that,
+/// each index takes up exactly as many bits as the list length needs (13 bits for the 8,192-word
+/// Medium list, for example), packed together with no gaps. The bytes are then encoded in base32
+/// or base58. Anyone with this string and the word list can rebuild the passphrase.
+pub fn encode_indices(
+    indices: &[usize],
+    list_length: usize,
+    encoding: IndexEncoding,
+) -> Result<String, String> {
+    if indices.len() > u8::MAX as usize {
+        return Err(format!(
+            "Can't encode the indices of more than {} words",
+            u8::MAX
+        ));
+    }
+    let bits = bits_per_index(list_length);
+    let mut bytes = vec![indices.len() as u8];
+    let mut buffer: u128 = 0;
+    let mut buffered_bits = 0;
+    for index in indices {
+        if *index >= list_length {
+            return Err(format!(
+                "Index {} is out of range for a list of {} words",
+                index, list_length
+            ));
+        }
+        buffer = (buffer << bits) | *index as u128;
+        buffered_bits += bits;
+        while buffered_bits >= 8 {
+            buffered_bits -= 8;
+            bytes.push((buffer >> buffered_bits) as u8);
+        }
+    }
+    // Pad out the last byte with zeros
+    if buffered_bits > 0 {
+        bytes.push((buffer << (8 - buffered_bits)) as u8);
+    }
+    Ok(match encoding {
+        IndexEncoding::Base32 => encode_base32(&bytes),
+        IndexEncoding::Base58 => encode_base58(&bytes),
+    })
+}
+
+/// Turn a string made by `encode_indices` back into word indices.
+pub fn decode_indices(
+    encoded: &str,
+    list_length: usize,
+    encoding: IndexEncoding,
+) -> Result<Vec<usize>, String> {
+    let bytes = match encoding {
+        IndexEncoding::Base32 => decode_base32(encoded)?,
+        IndexEncoding::Base58 => decode_base58(encoded)?,
+    };
+    let (number_of_words, packed) = match bytes.split_first() {
+        Some((number_of_words, packed)) => (*number_of_words as usize, packed),
+        None => return Err("Encoded indices are empty".to_string()),
+    };
+    let bits = bits_per_index(list_length);
+    if packed.len() * 8 < number_of_words * bits {
+        return Err(
+            "Encoded indices are too short for the number of words they claim to have".to_string(),
+        );
+    }
+    let mut indices = Vec::with_capacity(number_of_words);
+    let mut buffer: u128 = 0;
+    let mut buffered_bits = 0;
+    let mut packed = packed.iter();
+    while indices.len() < number_of_words {
+        while buffered_bits < bits {
+            // We checked the length above, so there's always another byte here
+            buffer = (buffer << 8) | *packed.next().unwrap() as u128;
+            buffered_bits += 8;
+        }
+        buffered_bits -= bits;
+        let index = ((buffer >> buffered_bits) & ((1u128 << bits) - 1)) as usize;
+        if index >= list_length {
+            return Err(format!(
+                "Encoded index {} is out of range for a list of {} words",
+                index, list_length
+            ));
+        }
+        indices.push(index);
+    }
+    Ok(indices)
+}
+
+/// Encode bytes as RFC 4648 base32 (A-Z and 2-7), without padding.
+pub(crate) fn encode_base32(bytes: &[u8]) -> String {
+    let mut encoded = String::new();
+    let mut buffer: u32 = 0;
+    let mut buffered_bits = 0;
+    for byte in bytes {
+        buffer = (buffer << 8) | *byte as u32;
+        buffered_bits += 8;
+        while buffered_bits >= 5 {
+            buffered_bits -= 5;
+            encoded.push(BASE32_ALPHABET[((buffer >> buffered_bits) & 31) as usize] as char);
+        }
+    }
+    if buffered_bits > 0 {
+        encoded.push(BASE32_ALPHABET[((buffer << (5 - buffered_bits)) & 31) as usize] as char);
+    }
+    encoded
+}
+
+/// Decode RFC 4648 base32, ignoring case and any padding.
+pub(crate) fn decode_base32(encoded: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = vec![];
+    let mut buffer: u32 = 0;
+    let mut buffered_bits = 0;
+    for c in encoded.trim().trim_end_matches('=').chars() {
+        let value = match BASE32_ALPHABET
+            .iter()
+            .position(|a| *a as char == c.to_ascii_uppercase())
+        {
+            Some(value) => value as u32,
+            None => return Err(format!("'{}' isn't a valid base32 character", c)),
+        };
+        buffer = (buffer << 5) | value;
+        buffered_bits += 5;
+        if buffered_bits >= 8 {
+            buffered_bits -= 8;
+            bytes.push((buffer >> buffered_bits) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+fn encode_base58(bytes: &[u8]) -> String {
+    // Treat the bytes as one big number and repeatedly divide it by 58
+    let mut digits: Vec<u8> = vec![];
+    for byte in bytes {
+        let mut carry = *byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    // Each leading zero byte is written as a leading "1"
+    let leading_zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+    let mut encoded = "1".repeat(leading_zeros);
+    encoded.extend(
+        digits
+            .iter()
+            .rev()
+            .map(|digit| BASE58_ALPHABET[*digit as usize] as char),
+    );
+    encoded
+}
+
+fn decode_base58(encoded: &str) -> Result<Vec<u8>, String> {
+    let encoded = encoded.trim();
+    let mut bytes: Vec<u8> = vec![];
+    for c in encoded.chars() {
+        let mut carry = match BASE58_ALPHABET.iter().position(|a| *a as char == c) {
+            Some(value) => value as u32,
+            None => return Err(format!("'{}' isn't a valid base58 character", c)),
+        };
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    let leading_zeros = encoded.chars().take_while(|c| *c == '1').count();
+    let mut decoded = vec![0; leading_zeros];
+    decoded.extend(bytes.iter().rev());
+    Ok(decoded)
+}
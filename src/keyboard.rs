@@ -0,0 +1,302 @@
+use crate::choose_word;
+use rand::Rng;
+use std::collections::HashMap;
+
+/// Farthest apart (in key widths) that the last letter of one word and the first letter of the
+/// next word can be for the transition to count as "comfortable" when using --qwerty-flow.
+pub const QWERTY_FLOW_MAX_DISTANCE: f64 = 2.0;
+
+/// Maximum number of times we'll draw a word while looking for a comfortable transition. If none
+/// of these draws are comfortable, we keep the last one. This keeps --qwerty-flow from ever
+/// looping forever, even with lists where no comfortable transitions exist.
+pub const QWERTY_FLOW_MAX_TRIES: usize = 10;
+
+/// Longest run of neighboring keys (like "asdf" or "poiu") that a passphrase may contain when
+/// using --no-keyboard-runs.
+pub const KEYBOARD_RUN_MAX_LENGTH: usize = 3;
+
+/// Rows of a QWERTY keyboard, along with how far (in key widths) each row is shifted to the right
+/// of the top row.
+const QWERTY_ROWS: [(&str, f64); 3] = [("qwertyuiop", 0.0), ("asdfghjkl", 0.25), ("zxcvbnm", 0.75)];
+
+/// Find the (x, y) position of a letter on a QWERTY keyboard, measured in key widths. Returns
+/// None for characters that aren't one of the 26 letters.
+pub fn qwerty_position(c: char) -> Option<(f64, f64)> {
+    let c = c.to_ascii_lowercase();
+    for (row_number, (row, offset)) in QWERTY_ROWS.iter().enumerate() {
+        if let Some(column) = row.find(c) {
+            return Some((column as f64 + offset, row_number as f64));
+        }
+    }
+    None
+}
+
+/// Find the row and column of a letter's key on a QWERTY keyboard.
+fn qwerty_key(c: char) -> Option<(usize, usize)> {
+    let c = c.to_ascii_lowercase();
+    QWERTY_ROWS
+        .iter()
+        .enumerate()
+        .find_map(|(row_number, (row, _offset))| Some((row_number, row.find(c)?)))
+}
+
+/// Find the length of the longest "keyboard run" in a string: a sequence of letters whose keys
+/// are next to each other in the same row of a QWERTY keyboard, typed in order in either
+/// direction (like "asdf", "rty" or "lkj"). Case is ignored. Runs can span words, so this
+/// should be given the whole passphrase.
+pub fn longest_keyboard_run(s: &str) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    // Which way the current run is going: 1 for left to right, -1 for right to left
+    let mut direction = 0;
+    let mut previous_key: Option<(usize, usize)> = None;
+    for c in s.chars() {
+        let key = qwerty_key(c);
+        match (previous_key, key) {
+            (Some((previous_row, previous_column)), Some((row, column)))
+                if previous_row == row && previous_column.abs_diff(column) == 1 =>
+            {
+                let step = if column > previous_column { 1 } else { -1 };
+                if current >= 2 && step == direction {
+                    current += 1;
+                } else {
+                    current = 2;
+                    direction = step;
+                }
+            }
+            (_, Some(_)) => current = 1,
+            (_, None) => current = 0,
+        }
+        longest = longest.max(current);
+        previous_key = key;
+    }
+    longest
+}
+
+/// Straight-line distance between two letters on a QWERTY keyboard, in key widths.
+pub fn qwerty_distance(a: char, b: char) -> Option<f64> {
+    let (ax, ay) = qwerty_position(a)?;
+    let (bx, by) = qwerty_position(b)?;
+    Some((ax - bx).hypot(ay - by))
+}
+
+/// Keyboard layouts we can measure typing effort on.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum KeyboardLayout {
+    /// A full QWERTY keyboard, including the number row, punctuation and the space bar
+    Qwerty,
+    /// The alphabetical grid many TVs and game consoles use (the one the Alpha list is made
+    /// for), six letters to a row, with a row of digits underneath
+    Alphabetical,
+}
+
+/// Rows of a full QWERTY keyboard, as (unshifted, shifted) characters, with how far (in key
+/// widths) each row is shifted to the right of the top letter row. The number row is y = -1, so
+/// letters keep the same positions as in `qwerty_position`.
+const QWERTY_FULL_ROWS: [(&str, &str, f64); 4] = [
+    ("1234567890-=", "!@#$%^&*()_+", -0.5),
+    ("qwertyuiop[]\\", "QWERTYUIOP{}|", 0.0),
+    ("asdfghjkl;'", "ASDFGHJKL:\"", 0.25),
+    ("zxcvbnm,./", "ZXCVBNM<>?", 0.75),
+];
+
+/// Rows of the alphabetical layout, top to bottom.
+const ALPHABETICAL_ROWS: [&str; 6] = ["abcdef", "ghijkl", "mnopqr", "stuvwx", "yz", "1234567890"];
+
+/// Find the (x, y) position of any character's key on a keyboard layout, in key widths. Shifted
+/// characters (like uppercase letters, or "!") use the same key as their unshifted versions.
+/// Returns None for characters the layout doesn't have.
+pub fn key_position(c: char, layout: KeyboardLayout) -> Option<(f64, f64)> {
+    match layout {
+        KeyboardLayout::Qwerty => {
+            if c == ' ' {
+                // Roughly where a thumb hits the space bar, under "b"
+                return Some((4.75, 3.0));
+            }
+            QWERTY_FULL_ROWS.iter().enumerate().find_map(
+                |(row_number, (unshifted, shifted, offset))| {
+                    let column = unshifted
+                        .chars()
+                        .position(|key| key == c)
+                        .or_else(|| shifted.chars().position(|key| key == c))?;
+                    Some((column as f64 + offset, row_number as f64 - 1.0))
+                },
+            )
+        }
+        KeyboardLayout::Alphabetical => {
+            let c = c.to_ascii_lowercase();
+            ALPHABETICAL_ROWS
+                .iter()
+                .enumerate()
+                .find_map(|(row_number, row)| {
+                    let column = row.chars().position(|key| key == c)?;
+                    Some((column as f64, row_number as f64))
+                })
+        }
+    }
+}
+
+/// Score how much effort it takes to type a passphrase, as the total distance (in key widths)
+/// a single finger or cursor would travel from key to key, separators included. Characters the
+/// layout doesn't have are skipped over. Lower is easier.
+pub fn typing_effort(s: &str, layout: KeyboardLayout) -> f64 {
+    let mut effort = 0.0;
+    let mut previous: Option<(f64, f64)> = None;
+    for position in s.chars().filter_map(|c| key_position(c, layout)) {
+        if let Some((previous_x, previous_y)) = previous {
+            effort += (position.0 - previous_x).hypot(position.1 - previous_y);
+        }
+        previous = Some(position);
+    }
+    effort
+}
+
+/// Check whether going from the last letter of previous_word to the first letter of next_word
+/// is a short trip on a QWERTY keyboard. Characters that aren't on the keyboard (or empty words)
+/// can't be judged, so we count those transitions as comfortable.
+pub fn is_comfortable_transition(previous_word: &str, next_word: &str) -> bool {
+    is_comfortable_pair(previous_word.chars().last(), next_word.chars().next())
+}
+
+fn is_comfortable_pair(a: Option<char>, b: Option<char>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => match qwerty_distance(a, b) {
+            Some(distance) => distance <= QWERTY_FLOW_MAX_DISTANCE,
+            None => true,
+        },
+        _ => true,
+    }
+}
+
+/// Randomly pick words like `generate_words`, but prefer sequences that minimize the travel
+/// between words on a QWERTY keyboard. After the first word, each word is re-drawn (up to
+/// QWERTY_FLOW_MAX_TRIES draws in total) until its first letter is within
+/// QWERTY_FLOW_MAX_DISTANCE of the previous word's last letter.
+pub fn generate_words_with_qwerty_flow<'a, T: AsRef<str>>(
+    rng: &mut impl Rng,
+    number_of_words: usize,
+    list: &'a [T],
+) -> Vec<&'a str> {
+    let mut words: Vec<&'a str> = Vec::with_capacity(number_of_words);
+    for _ in 0..number_of_words {
+        let mut candidate = choose_word(rng, list);
+        if let Some(previous_word) = words.last() {
+            let mut tries = 1;
+            while tries < QWERTY_FLOW_MAX_TRIES
+                && !is_comfortable_transition(previous_word, candidate)
+            {
+                candidate = choose_word(rng, list);
+                tries += 1;
+            }
+        }
+        words.push(candidate);
+    }
+    words
+}
+
+/// Estimate the entropy of a passphrase generated with `generate_words_with_qwerty_flow`.
+///
+/// The first word is chosen uniformly, so it's worth the usual log2(list length) bits. Each word
+/// after that is no longer uniform: comfortable words are more likely than uncomfortable ones.
+/// Given a previous word where a fraction p of the list is comfortable, and a maximum of T draws,
+/// each comfortable word has a probability of (1 - (1-p)^T) / (N * p) and each uncomfortable word
+/// has a probability of (1-p)^(T-1) / N. We take the Shannon entropy of that distribution, and
+/// average it over every possible previous word.
+pub fn qwerty_flow_entropy<T: AsRef<str>>(number_of_words: usize, list: &[T]) -> f64 {
+    if number_of_words == 0 || list.is_empty() {
+        return 0.0;
+    }
+    (list.len() as f64).log2() + qwerty_flow_entropy_per_word(list) * (number_of_words - 1) as f64
+}
+
+/// Estimated entropy, in bits, of each word after the first in a passphrase generated with
+/// `generate_words_with_qwerty_flow`, averaged over every possible previous word. The first word
+/// is worth a little more, so counting every word at this is a safe way to work out how many
+/// words a passphrase needs.
+pub fn qwerty_flow_entropy_per_word<T: AsRef<str>>(list: &[T]) -> f64 {
+    let list_length = list.len() as f64;
+    if list.is_empty() {
+        return 0.0;
+    }
+    // We only care about first and last letters, so count those up rather than comparing every
+    // pair of words
+    let mut first_letter_counts: HashMap<Option<char>, usize> = HashMap::new();
+    let mut last_letter_counts: HashMap<Option<char>, usize> = HashMap::new();
+    for word in list {
+        *first_letter_counts
+            .entry(word.as_ref().chars().next())
+            .or_insert(0) += 1;
+        *last_letter_counts
+            .entry(word.as_ref().chars().last())
+            .or_insert(0) += 1;
+    }
+
+    let mut mean_transition_entropy = 0.0;
+    for (last_letter, previous_count) in &last_letter_counts {
+        let comfortable_count: usize = first_letter_counts
+            .iter()
+            .filter(|(first_letter, _)| is_comfortable_pair(*last_letter, **first_letter))
+            .map(|(_, count)| count)
+            .sum();
+        let transition_entropy =
+            transition_entropy(comfortable_count as f64 / list_length, list_length);
+        mean_transition_entropy += transition_entropy * (*previous_count as f64 / list_length);
+    }
+    mean_transition_entropy
+}
+
+/// Shannon entropy of a single word choice where a fraction p of the list is comfortable.
+fn transition_entropy(p: f64, list_length: f64) -> f64 {
+    // If every word is comfortable, or none are, every word is equally likely
+    if p <= 0.0 || p >= 1.0 {
+        return list_length.log2();
+    }
+    let q = 1.0 - p;
+    let tries = QWERTY_FLOW_MAX_TRIES as i32;
+    let comfortable_probability = (1.0 - q.powi(tries)) / (list_length * p);
+    let uncomfortable_probability = q.powi(tries - 1) / list_length;
+    -(p * list_length * comfortable_probability * comfortable_probability.log2()
+        + q * list_length * uncomfortable_probability * uncomfortable_probability.log2())
+}
+
+#[test]
+fn can_measure_qwerty_distances() {
+    assert_eq!(qwerty_distance('a', 'a'), Some(0.0));
+    assert_eq!(qwerty_distance('q', 'p'), Some(9.0));
+    assert_eq!(qwerty_distance('A', 's'), Some(1.0));
+    assert_eq!(qwerty_distance('a', 'é'), None);
+}
+
+#[test]
+fn can_find_keyboard_runs() {
+    assert_eq!(longest_keyboard_run("asdf"), 4);
+    assert_eq!(longest_keyboard_run("PoIu"), 4);
+    // Runs can cross words, but not rows, and have to keep going the same way
+    assert_eq!(longest_keyboard_run("toqwer-ty"), 4);
+    assert_eq!(longest_keyboard_run("qwerty"), 6);
+    assert_eq!(longest_keyboard_run("asa"), 2);
+    assert_eq!(longest_keyboard_run("aqz"), 1);
+    assert_eq!(longest_keyboard_run("12-!"), 0);
+}
+
+#[test]
+fn can_score_typing_effort() {
+    // Neighboring keys are cheap, opposite ends of the keyboard are expensive
+    assert_eq!(typing_effort("asdf", KeyboardLayout::Qwerty), 3.0);
+    assert_eq!(typing_effort("qp", KeyboardLayout::Qwerty), 9.0);
+    assert_eq!(typing_effort("qpqp", KeyboardLayout::Qwerty), 27.0);
+    assert_eq!(typing_effort("a", KeyboardLayout::Qwerty), 0.0);
+    // Shifted characters use the same keys
+    assert_eq!(typing_effort("ASdf", KeyboardLayout::Qwerty), 3.0);
+    assert_eq!(typing_effort("1!", KeyboardLayout::Qwerty), 0.0);
+    // Separators count too
+    assert!(
+        typing_effort("as-df", KeyboardLayout::Qwerty)
+            > typing_effort("asdf", KeyboardLayout::Qwerty)
+    );
+    // "g" is right under "a" in the alphabetical layout, but across the keyboard on QWERTY
+    assert_eq!(typing_effort("ag", KeyboardLayout::Alphabetical), 1.0);
+    assert_eq!(typing_effort("ag", KeyboardLayout::Qwerty), 4.0);
+    // Characters the layout doesn't have are skipped
+    assert_eq!(typing_effort("a-b", KeyboardLayout::Alphabetical), 1.0);
+}
@@ -1,8 +1,45 @@
+pub mod abbreviation;
+#[cfg(feature = "async")]
+pub mod asynchronous;
+pub mod batch;
+pub mod builder;
+pub mod compound;
+#[cfg(feature = "compressed-lists")]
+mod compressed_lists;
+pub mod confusables;
+pub mod dice;
+pub mod digits;
 pub mod file_reader;
+#[cfg(feature = "fuzzing")]
+pub mod fuzzing;
+pub mod grammar;
+pub mod hint;
+pub mod histogram;
+pub mod history;
+pub mod indices;
+pub mod keyboard;
+pub mod list_analysis;
+pub mod memorability;
+pub mod messages;
+pub mod output;
+pub mod policy;
+pub mod required;
+pub mod restrictions;
+pub mod rng;
+pub mod screen;
 pub mod separators;
+pub mod sinks;
+pub mod token;
+pub mod totp;
 pub mod unicode_normalization_check;
-use crate::separators::make_separator;
-use rand::{seq::SliceRandom, thread_rng, Rng};
+pub mod verification;
+pub mod weighted;
+pub mod word_bank;
+pub mod word_pattern;
+use crate::separators::push_separator;
+use rand::{thread_rng, Rng};
+use std::collections::HashSet;
+use unicode_segmentation::UnicodeSegmentation;
 
 // Pull in the wordlists as constants for us to use later.
 // This is thanks to the build.rs build script. Learn more:
@@ -19,44 +56,184 @@ pub enum ListChoice {
     Effshort,
     Qwerty,
     Alpha,
+    Emoji,
+}
+
+impl ListChoice {
+    /// Every built-in word list, in the order they're listed in Phraze's help text.
+    pub fn all() -> [ListChoice; 8] {
+        [
+            ListChoice::Medium,
+            ListChoice::Long,
+            ListChoice::Eff,
+            ListChoice::Mnemonicode,
+            ListChoice::Effshort,
+            ListChoice::Qwerty,
+            ListChoice::Alpha,
+            ListChoice::Emoji,
+        ]
+    }
+
+    /// The list's full name, like "Orchard Street Medium list".
+    pub fn name(&self) -> &'static str {
+        match self {
+            ListChoice::Long => "Orchard Street Long list",
+            ListChoice::Medium => "Orchard Street Medium list",
+            ListChoice::Eff => "EFF long list",
+            ListChoice::Mnemonicode => "Mnemonicode list",
+            ListChoice::Effshort => "EFF short list",
+            ListChoice::Qwerty => "Orchard Street QWERTY list",
+            ListChoice::Alpha => "Orchard Street Alpha list",
+            ListChoice::Emoji => "Emoji list",
+        }
+    }
+
+    /// Iterate over the words of this built-in list, in order. This is the preferred way to go
+    /// through a list's words, since it doesn't tie you to how the list is stored: today the
+    /// built-in lists are static slices, but word lists that are loaded lazily won't be.
+    pub fn words(&self) -> impl Iterator<Item = &'static str> {
+        fetch_list(*self).iter().copied()
+    }
+}
+
+/// The settings that shape a generated passphrase, apart from the word list and the RNG.
+#[derive(Clone, Debug)]
+pub struct PassphraseConfig {
+    pub number_of_words: usize,
+    pub separator: String,
+    pub title_case: bool,
 }
 
 /// Given user's inputs, figure out how many words the generated passphrase will need. If user
 /// specified an exact number_of_words, just return that number_of_words. If user is using a
 /// strength_count, do the necessary math. If user specified a minimum_entropy, we need to do
 /// some math to figure out how many words will clear that minimum.
+///
+/// Words are usually each worth log2(list_length) bits, which assumes every word is equally
+/// likely. If they aren't, like with a weighted list, give the real entropy per word as
+/// `entropy_per_word` (for a weighted list, its Shannon entropy, from
+/// `weighted::shannon_entropy_per_word`), and that's used instead.
+///
+/// # Panics
+///
+/// This doesn't panic itself, but for a list that can't make a passphrase it returns a number
+/// of words that can't be generated: 0 for an empty list, and `usize::MAX` for a list of 1 word
+/// (with no `number_of_words` or `entropy_per_word`), which panics when you try to generate
+/// that many. Use `try_calculate_number_words_needed` to get an error instead.
 pub fn calculate_number_words_needed(
     number_of_words: Option<usize>,
     minimum_entropy: Option<usize>,
     strength_count: u8,
     list_length: usize,
+    entropy_per_word: Option<f64>,
 ) -> usize {
     // If a number of words was requested exactly by the user, use that
     if let Some(number_of_words) = number_of_words {
         return number_of_words;
     }
 
+    let minimum_entropy = target_minimum_entropy(minimum_entropy, strength_count);
+    match entropy_per_word {
+        Some(entropy_per_word) => words_needed_for_entropy(minimum_entropy, entropy_per_word),
+        None => convert_minimum_entropy_to_number_of_words(minimum_entropy, list_length),
+    }
+}
+
+/// Same as `calculate_number_words_needed`, but returns an error rather than a meaningless
+/// number of words if the list can't make a passphrase: an empty list has no words to pick, and
+/// a list of 1 word gives no entropy, so no number of its words reaches a minimum.
+pub fn try_calculate_number_words_needed(
+    number_of_words: Option<usize>,
+    minimum_entropy: Option<usize>,
+    strength_count: u8,
+    list_length: usize,
+    entropy_per_word: Option<f64>,
+) -> Result<usize, String> {
+    if list_length == 0 {
+        return Err(empty_list_error());
+    }
+    if list_length == 1 && number_of_words.is_none() && entropy_per_word.is_none() {
+        return Err(
+            "List has only 1 word, which is worth 0 bits, so no number of them can reach a minimum entropy"
+                .to_string(),
+        );
+    }
+    Ok(calculate_number_words_needed(
+        number_of_words,
+        minimum_entropy,
+        strength_count,
+        list_length,
+        entropy_per_word,
+    ))
+}
+
+/// The error the `try_` functions give for an empty word list.
+fn empty_list_error() -> String {
+    "Word list is empty, so there are no words to pick from".to_string()
+}
+
+/// Work out the minimum entropy, in bits, the user is asking for. If they used the strength
+/// count option, that's 20 bits per S above the default. If they asked for a minimum entropy
+/// directly, it's that. Otherwise, it's the default of 80 bits.
+pub fn target_minimum_entropy(minimum_entropy: Option<usize>, strength_count: u8) -> usize {
     const DEFAULT_MINIMUM_ENTROPY: usize = 80;
-    // If they used the strength count option, do some math to calculate what minimum_entropy
-    // we should give them, then convert that into number of bits of entropy.
     if strength_count > 0 {
         // Use number of Ss to calculate minimum_entropy in bits
-        let minimum_entropy = DEFAULT_MINIMUM_ENTROPY + (strength_count as usize) * 20;
-        // convert this into number of words, using list length
-        return convert_minimum_entropy_to_number_of_words(minimum_entropy, list_length);
-    }
-    // If we made it here, that means either the user requested a specific minimum_entropy in bits,
-    // or they entered no relevant settings. Let's handle both cases with a match statement.
-    match minimum_entropy {
-        // If a minimum_entropy is set by user, use that.
-        Some(minimum_entropy) => {
-            convert_minimum_entropy_to_number_of_words(minimum_entropy, list_length)
+        return DEFAULT_MINIMUM_ENTROPY + (strength_count as usize) * 20;
+    }
+    minimum_entropy.unwrap_or(DEFAULT_MINIMUM_ENTROPY)
+}
+
+/// Named strength presets, as a friendlier alternative to choosing a minimum entropy directly.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum StrengthLevel {
+    /// 60 bits: fine for something rate-limited, like an online account with lockouts
+    Low,
+    /// 80 bits: Phraze's default, and a good general choice
+    Medium,
+    /// 100 bits: for things that could face offline guessing, like a password manager's
+    /// master password
+    High,
+    /// 128 bits: about as strong as the encryption keys a passphrase might protect
+    Paranoid,
+}
+
+impl StrengthLevel {
+    /// Every strength level, from weakest to strongest.
+    pub fn all() -> [StrengthLevel; 4] {
+        [
+            StrengthLevel::Low,
+            StrengthLevel::Medium,
+            StrengthLevel::High,
+            StrengthLevel::Paranoid,
+        ]
+    }
+
+    /// The minimum entropy, in bits, that this level asks for.
+    pub fn minimum_entropy(&self) -> usize {
+        match self {
+            StrengthLevel::Low => 60,
+            StrengthLevel::Medium => 80,
+            StrengthLevel::High => 100,
+            StrengthLevel::Paranoid => 128,
         }
-        // If none of these 3 settings were given, use the DEFAULT_MINIMUM_ENTROPY
-        None => convert_minimum_entropy_to_number_of_words(DEFAULT_MINIMUM_ENTROPY, list_length),
     }
 }
 
+/// How many words a passphrase from a built-in list needs to reach each `StrengthLevel`'s
+/// minimum entropy, as (bits, words) pairs from weakest to strongest, like `(80, 7)` for the
+/// Medium list. Handy for offering presets in a UI, without working out the numbers yourself.
+pub fn recommended_word_counts(list: ListChoice) -> [(usize, usize); 4] {
+    let list_length = fetch_list(list).len();
+    StrengthLevel::all().map(|level| {
+        let bits = level.minimum_entropy();
+        (
+            bits,
+            calculate_number_words_needed(None, Some(bits), 0, list_length, None),
+        )
+    })
+}
+
 /// A little helper function to actually calculate the number of words needed to meet a desired
 /// minimum entropy, given the length of the word list we're using.
 pub fn convert_minimum_entropy_to_number_of_words(
@@ -64,11 +241,75 @@ pub fn convert_minimum_entropy_to_number_of_words(
     list_length: usize,
 ) -> usize {
     let entropy_per_word_from_this_list = (list_length as f64).log2();
-    (minimum_entropy as f64 / entropy_per_word_from_this_list).ceil() as usize
+    words_needed_for_entropy(minimum_entropy, entropy_per_word_from_this_list)
+}
+
+/// The number of words, each worth `entropy_per_word` bits, needed to reach at least
+/// `minimum_entropy` bits: minimum_entropy / entropy_per_word, rounded up. `entropy_per_word`
+/// should be more than 0, since no number of words worth nothing reaches any entropy.
+pub fn words_needed_for_entropy(minimum_entropy: usize, entropy_per_word: f64) -> usize {
+    (minimum_entropy as f64 / entropy_per_word).ceil() as usize
+}
+
+/// The inverse of `convert_minimum_entropy_to_number_of_words`: the shortest list that gives a
+/// passphrase of `number_of_words` words at least `minimum_entropy` bits, which is
+/// 2^(minimum_entropy / number_of_words), rounded up to a whole number of words.
+pub fn minimum_list_size(minimum_entropy: usize, number_of_words: usize) -> Result<u128, String> {
+    if number_of_words == 0 {
+        return Err("Can't reach any entropy with 0 words".to_string());
+    }
+    let bits_per_word = minimum_entropy as f64 / number_of_words as f64;
+    if bits_per_word >= 127.0 {
+        return Err(format!(
+            "{} words would need more than 2^127 words in the list to reach {} bits",
+            number_of_words, minimum_entropy
+        ));
+    }
+    let reaches_minimum = |list_length: u128| {
+        number_of_words as f64 * (list_length as f64).log2() >= minimum_entropy as f64
+    };
+    // Start from the floating-point answer, then nudge it so rounding can't leave it off by one
+    let mut list_length = (2f64.powf(bits_per_word).ceil() as u128).max(1);
+    while list_length > 1 && reaches_minimum(list_length - 1) {
+        list_length -= 1;
+    }
+    while !reaches_minimum(list_length) {
+        list_length += 1;
+    }
+    Ok(list_length)
+}
+
+/// Show where a passphrase's entropy comes from: the number of words times log2 of the list
+/// length. For lists whose length is a power of two, every word is worth a whole number of
+/// bits, so the result is exact, like "7 × log2(8192) = 7 × 13 = 91 bits". Otherwise, it's
+/// rounded, like "7 × log2(7776) ≈ 7 × 12.92 ≈ 90.47 bits".
+pub fn describe_entropy_symbolically(number_of_words: usize, list_length: usize) -> String {
+    if list_length.is_power_of_two() {
+        let bits_per_word = list_length.trailing_zeros() as usize;
+        format!(
+            "{} × log2({}) = {} × {} = {} bits",
+            number_of_words,
+            list_length,
+            number_of_words,
+            bits_per_word,
+            number_of_words * bits_per_word
+        )
+    } else {
+        let bits_per_word = (list_length as f64).log2();
+        format!(
+            "{} × log2({}) ≈ {} × {:.2} ≈ {:.2} bits",
+            number_of_words,
+            list_length,
+            number_of_words,
+            bits_per_word,
+            bits_per_word * number_of_words as f64
+        )
+    }
 }
 
 /// Take enum of list_choice and find the constant that is the corresponding word list (with the
 /// actual words). These are defined in the build script (build.rs)
+#[cfg(not(feature = "compressed-lists"))]
 pub fn fetch_list(list_choice: ListChoice) -> &'static [&'static str] {
     match list_choice {
         ListChoice::Long => WL_LONG,
@@ -78,49 +319,449 @@ pub fn fetch_list(list_choice: ListChoice) -> &'static [&'static str] {
         ListChoice::Eff => WL_EFF,
         ListChoice::Effshort => WL_EFFSHORT,
         ListChoice::Mnemonicode => WL_MNEMONICODE,
+        ListChoice::Emoji => WL_EMOJI,
+    }
+}
+
+/// Take enum of list_choice and find the corresponding word list (with the actual words). With
+/// the `compressed-lists` feature, the lists are stored compressed, and all of them are
+/// decompressed the first time any list is fetched. After that, fetching a list is as cheap as
+/// usual.
+#[cfg(feature = "compressed-lists")]
+pub fn fetch_list(list_choice: ListChoice) -> &'static [&'static str] {
+    compressed_lists::decompressed_list(list_choice)
+}
+
+/// A list of words that passphrases can be made from. Built-in lists (from `fetch_list`) are
+/// `&'static [&'static str]`, while custom lists (like from `file_reader::read_in_custom_list`)
+/// are `Vec<String>`, but both are word lists, so code that picks words can take either one
+/// without caring which it is.
+pub trait WordList {
+    /// How many words are on the list.
+    fn len(&self) -> usize;
+
+    /// The word at the given position, counting from 0. Panics if the position is past the end
+    /// of the list, like indexing a slice does.
+    fn word(&self, index: usize) -> &str;
+
+    /// Whether the list has no words at all.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Go through the list's words in order, without copying them.
+    fn words(&self) -> Words<'_, Self> {
+        Words {
+            list: self,
+            index: 0,
+        }
+    }
+}
+
+impl<T: AsRef<str>> WordList for [T] {
+    fn len(&self) -> usize {
+        <[T]>::len(self)
+    }
+
+    fn word(&self, index: usize) -> &str {
+        self[index].as_ref()
+    }
+}
+
+impl<T: AsRef<str>> WordList for Vec<T> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn word(&self, index: usize) -> &str {
+        self[index].as_ref()
     }
 }
 
-/// Actually generate the passphrase, given a couple neccessary parameters.
-/// This function uses some Rust magic to be able to accept a word list as
-/// either a &[&str] (built-in word lists) or as a &[String] if user provides a file
-/// as word list.
-pub fn generate_passphrase<T: AsRef<str> + std::fmt::Display>(
+impl<T: AsRef<str>, const N: usize> WordList for [T; N] {
+    fn len(&self) -> usize {
+        N
+    }
+
+    fn word(&self, index: usize) -> &str {
+        self[index].as_ref()
+    }
+}
+
+// So a reference to a word list (like `&&[&str]`) can be passed wherever a word list can
+impl<L: WordList + ?Sized> WordList for &L {
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    fn word(&self, index: usize) -> &str {
+        (**self).word(index)
+    }
+}
+
+/// An iterator over a `WordList`'s words, from `WordList::words`.
+pub struct Words<'a, L: WordList + ?Sized> {
+    list: &'a L,
+    index: usize,
+}
+
+impl<'a, L: WordList + ?Sized> Iterator for Words<'a, L> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.index >= self.list.len() {
+            return None;
+        }
+        self.index += 1;
+        Some(self.list.word(self.index - 1))
+    }
+}
+
+/// Actually generate the passphrase, given a couple neccessary parameters. The word list can be
+/// any `WordList`, like a built-in list or a custom one.
+///
+/// # Panics
+///
+/// Panics if the list is empty (and more than 0 words are asked for). Use
+/// `try_generate_passphrase` to get an error instead.
+pub fn generate_passphrase<L: WordList + ?Sized>(
     number_of_words_to_put_in_passphrase: usize,
     separator: &str,
     title_case: bool,
-    list: &[T], // Either type!
+    list: &L,
 ) -> String {
     let mut rng = thread_rng();
-    // Create a blank String to put words into to create our passphrase
-    let mut passphrase = String::new();
-    for i in 0..number_of_words_to_put_in_passphrase {
-        // Check if we're doing title_case
-        let random_word = if title_case {
-            make_title_case(&get_random_element(&mut rng, list))
+    generate_passphrase_with_rng(
+        &mut rng,
+        number_of_words_to_put_in_passphrase,
+        separator,
+        title_case,
+        list,
+    )
+}
+
+/// Same as `generate_passphrase`, but uses a given RNG rather than creating a new one. Handy
+/// when generating many passphrases in a row, since we only need to set up one RNG for all of
+/// them.
+///
+/// # Panics
+///
+/// Panics if the list is empty (and more than 0 words are asked for). Use
+/// `try_generate_passphrase_with_rng` to get an error instead.
+pub fn generate_passphrase_with_rng<L: WordList + ?Sized>(
+    rng: &mut impl Rng,
+    number_of_words_to_put_in_passphrase: usize,
+    separator: &str,
+    title_case: bool,
+    list: &L,
+) -> String {
+    let words = generate_words(rng, number_of_words_to_put_in_passphrase, list);
+    assemble_passphrase(rng, &words, separator, title_case)
+}
+
+/// Same as `generate_passphrase`, but returns an error if the list is empty, rather than
+/// panicking.
+pub fn try_generate_passphrase<L: WordList + ?Sized>(
+    number_of_words_to_put_in_passphrase: usize,
+    separator: &str,
+    title_case: bool,
+    list: &L,
+) -> Result<String, String> {
+    let mut rng = thread_rng();
+    try_generate_passphrase_with_rng(
+        &mut rng,
+        number_of_words_to_put_in_passphrase,
+        separator,
+        title_case,
+        list,
+    )
+}
+
+/// Same as `generate_passphrase_with_rng`, but returns an error if the list is empty, rather
+/// than panicking.
+pub fn try_generate_passphrase_with_rng<L: WordList + ?Sized>(
+    rng: &mut impl Rng,
+    number_of_words_to_put_in_passphrase: usize,
+    separator: &str,
+    title_case: bool,
+    list: &L,
+) -> Result<String, String> {
+    if list.is_empty() {
+        return Err(empty_list_error());
+    }
+    Ok(generate_passphrase_with_rng(
+        rng,
+        number_of_words_to_put_in_passphrase,
+        separator,
+        title_case,
+        list,
+    ))
+}
+
+/// Randomly pick the given number of words from a word list. Words are borrowed from the list
+/// rather than copied, to save allocations.
+pub fn generate_words<'a, L: WordList + ?Sized>(
+    rng: &mut impl Rng,
+    number_of_words: usize,
+    list: &'a L,
+) -> Vec<&'a str> {
+    (0..number_of_words)
+        .map(|_| choose_word(rng, list))
+        .collect()
+}
+
+/// Randomly pick the given number of words from a list whose length is a power of two, using
+/// exactly log2(length) random bits for each word, like 13 bits for an 8,192-word list. Each
+/// word's position is just the next bits from the RNG, so no random numbers are ever thrown away
+/// (like `generate_words` sometimes does) and every word is equally likely. Any bits left over
+/// at the end aren't used.
+pub fn generate_words_from_bits<'a, L: WordList + ?Sized>(
+    rng: &mut impl Rng,
+    number_of_words: usize,
+    list: &'a L,
+) -> Result<Vec<&'a str>, String> {
+    if !list.len().is_power_of_two() || list.len() < 2 {
+        return Err(format!(
+            "List has {} words, which isn't a power of two",
+            list.len()
+        ));
+    }
+    let bits_per_word = list.len().trailing_zeros();
+    let mut bits = RandomBits::default();
+    Ok((0..number_of_words)
+        .map(|_| list.word(bits.take(rng, bits_per_word) as usize))
+        .collect())
+}
+
+/// Random bits from an RNG, handed out a few at a time, for `generate_words_from_bits`.
+#[derive(Default)]
+struct RandomBits {
+    /// Bits that haven't been used yet, at the top
+    buffer: u64,
+    /// How many bits of `buffer` haven't been used yet
+    available: u32,
+}
+
+impl RandomBits {
+    /// Take the next `count` bits (up to 64), as a number.
+    fn take(&mut self, rng: &mut impl Rng, count: u32) -> u64 {
+        let mut value: u64 = 0;
+        let mut needed = count;
+        while needed > 0 {
+            if self.available == 0 {
+                self.buffer = rng.next_u64();
+                self.available = 64;
+            }
+            let taken = needed.min(self.available);
+            value = value.checked_shl(taken).unwrap_or(0) | (self.buffer >> (64 - taken));
+            self.buffer = self.buffer.checked_shl(taken).unwrap_or(0);
+            self.available -= taken;
+            needed -= taken;
+        }
+        value
+    }
+}
+
+/// Join already-chosen words into a passphrase, making them Title Case if requested and putting
+/// a separator (which may be randomly generated) between each word.
+pub fn assemble_passphrase(
+    rng: &mut impl Rng,
+    words: &[&str],
+    separator: &str,
+    title_case: bool,
+) -> String {
+    // We know the words already, so we can work out (or at least closely estimate) how long
+    // the passphrase will be, and avoid re-allocating while building it. Generated separators
+    // are always one byte long.
+    let separator_length = match separator {
+        "_n" | "_s" | "_b" => 1,
+        _ => separator.len(),
+    };
+    let words_length: usize = words.iter().map(|word| word.len()).sum();
+    let mut passphrase =
+        String::with_capacity(words_length + separator_length * words.len().saturating_sub(1));
+    for (i, word) in words.iter().enumerate() {
+        // Add this word to our passphrase, checking if we're doing title_case
+        if title_case {
+            push_title_case(&mut passphrase, word);
         } else {
-            get_random_element(&mut rng, list)
-        };
-        // Add this word to our passphrase
-        passphrase += &random_word;
+            passphrase.push_str(word);
+        }
         // Add a separator
-        if i != number_of_words_to_put_in_passphrase - 1 {
-            passphrase += &make_separator(&mut rng, separator);
+        if i != words.len() - 1 {
+            push_separator(rng, &mut passphrase, separator);
+        }
+    }
+    passphrase
+}
+
+/// Same as `assemble_passphrase`, but with the separators already chosen. There should be one
+/// separator for each gap between words.
+pub fn assemble_passphrase_with_separators(
+    words: &[&str],
+    separators: &[char],
+    title_case: bool,
+) -> String {
+    let words_length: usize = words.iter().map(|word| word.len()).sum();
+    let mut passphrase = String::with_capacity(words_length + separators.len());
+    for (i, word) in words.iter().enumerate() {
+        if title_case {
+            push_title_case(&mut passphrase, word);
+        } else {
+            passphrase.push_str(word);
+        }
+        if let Some(separator) = separators.get(i) {
+            passphrase.push(*separator);
+        }
+    }
+    passphrase
+}
+
+/// Make the words at the given positions (counting from 1) Title Case, and leave the rest as
+/// they are. Positions past the end of the passphrase are ignored.
+pub fn capitalize_words_at(words: &[&str], positions: &[usize]) -> Vec<String> {
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| match positions.contains(&(i + 1)) {
+            true => make_title_case(word),
+            false => word.to_string(),
+        })
+        .collect()
+}
+
+/// Pick a random word from a word list, borrowing it from the list.
+///
+/// # Panics
+///
+/// Panics if the list is empty, since there's no word to pick.
+pub fn choose_word<'a, L: WordList + ?Sized>(rng: &mut impl Rng, word_list: &'a L) -> &'a str {
+    if word_list.is_empty() {
+        panic!("Couldn't pick a random word");
+    }
+    // Pick the position the same way `SliceRandom::choose` does, so seeded passphrases come out
+    // the same as they always have
+    let index = match u32::try_from(word_list.len()) {
+        Ok(length) => rng.gen_range(0..length) as usize,
+        Err(_) => rng.gen_range(0..word_list.len()),
+    };
+    word_list.word(index)
+}
+
+/// Add a word to the end of a passphrase in Title Case, writing it straight into the passphrase
+/// rather than allocating a new String for the word. ASCII words (all of the built-in lists) get
+/// a simple byte-by-byte fast path.
+///
+/// A few Unicode characters can't be cased one at a time: a capital sigma (Σ) lowercases
+/// differently at the end of a word, and a first character like İ lowercases to more than one
+/// character. Words with those fall back to `make_title_case`, so the result is always the same
+/// as it would be from that function.
+fn push_title_case(passphrase: &mut String, word: &str) {
+    if word.is_ascii() {
+        let mut bytes = word.bytes();
+        if let Some(first) = bytes.next() {
+            passphrase.push(first.to_ascii_uppercase() as char);
+        }
+        passphrase.extend(bytes.map(|b| b.to_ascii_lowercase() as char));
+        return;
+    }
+    if word.contains('Σ') {
+        passphrase.push_str(&make_title_case(word));
+        return;
+    }
+    let mut chars = word.chars();
+    if let Some(first) = chars.next() {
+        let mut lowercase_first = first.to_lowercase();
+        if lowercase_first.len() != 1 {
+            passphrase.push_str(&make_title_case(word));
+            return;
+        }
+        // Just checked there's exactly one character here
+        passphrase.extend(lowercase_first.next().unwrap().to_uppercase());
+    }
+    for c in chars {
+        if c.is_ascii() {
+            passphrase.push(c.to_ascii_lowercase());
+        } else {
+            passphrase.extend(c.to_lowercase());
         }
     }
-    passphrase.to_string()
 }
 
-/// Give an array of words, pick a random element and make it a String for
-/// simplicity's sake.
-fn get_random_element<T: AsRef<str>>(rng: &mut impl Rng, word_list: &[T]) -> String
-where
-    T: std::fmt::Display,
-{
-    match word_list.choose(rng) {
-        Some(word) => word.to_string(),
-        None => panic!("Couldn't pick a random word"),
+/// Check whether making words Title Case would change any word in a list. For lists in scripts
+/// without letter case, like Japanese, it won't change anything.
+pub fn title_case_changes_any_word<T: AsRef<str>>(list: &[T]) -> bool {
+    let mut title_cased = String::new();
+    list.iter().any(|word| {
+        title_cased.clear();
+        push_title_case(&mut title_cased, word.as_ref());
+        title_cased != word.as_ref()
+    })
+}
+
+/// Entropy, in bits, that making each word Title Case or not at random (like --random-case
+/// does) adds to each word picked from a list. A coin flip is worth 1 bit, but only for words
+/// that Title Case changes, so each word adds the share of the list's words that it changes.
+pub fn random_case_entropy_per_word<T: AsRef<str>>(list: &[T]) -> f64 {
+    if list.is_empty() {
+        return 0.0;
+    }
+    let mut title_cased = String::new();
+    let changed = list
+        .iter()
+        .filter(|word| {
+            title_cased.clear();
+            push_title_case(&mut title_cased, word.as_ref());
+            title_cased != word.as_ref()
+        })
+        .count();
+    changed as f64 / list.len() as f64
+}
+
+/// Check whether any word in a list starts with a letter that has an uppercase and a lowercase
+/// form. If none do, Title Case can't show where one word ends and the next begins.
+pub fn any_word_starts_with_cased_letter<T: AsRef<str>>(list: &[T]) -> bool {
+    list.iter().any(|word| match word.as_ref().chars().next() {
+        Some(first) => !first.to_lowercase().eq(first.to_uppercase()),
+        None => false,
+    })
+}
+
+/// Check whether two word lists have no words in common. Before combining two lists into one,
+/// any words they share have to be taken out of one of them, or those words will be twice as
+/// likely to be picked, and the combined list will be worth less than its length suggests.
+pub fn lists_disjoint(a: &[&str], b: &[&str]) -> bool {
+    overlapping_words(a, b).is_empty()
+}
+
+/// Find the words that are in both of two word lists, in the order they appear in the first
+/// list, with each word listed once.
+pub fn overlapping_words<'a>(a: &[&'a str], b: &[&str]) -> Vec<&'a str> {
+    let in_b: HashSet<&str> = b.iter().copied().collect();
+    let mut seen = HashSet::new();
+    a.iter()
+        .copied()
+        .filter(|word| in_b.contains(word) && seen.insert(*word))
+        .collect()
+}
+
+/// Count the characters in a word or passphrase the way a person would. This counts extended
+/// grapheme clusters, rather than bytes (`str::len`) or Unicode scalar values
+/// (`chars().count()`), so an "é" written as an "e" plus a combining accent is one character, and
+/// so is an emoji with a skin tone modifier. Anything in Phraze that measures length in
+/// characters should use this.
+pub fn grapheme_length(s: &str) -> usize {
+    s.graphemes(true).count()
+}
+
+/// The average length of a list's words, in characters (as counted by `grapheme_length`). A
+/// passphrase's words are picked at random, so on average, each of them is this long.
+pub fn mean_word_length<T: AsRef<str>>(list: &[T]) -> f64 {
+    if list.is_empty() {
+        return 0.0;
     }
+    let total: usize = list.iter().map(|word| grapheme_length(word.as_ref())).sum();
+    total as f64 / list.len() as f64
 }
 
 /// Make first character of a given &str uppercase
@@ -134,6 +775,16 @@ fn make_title_case(s: &str) -> String {
     }
 }
 
+#[test]
+fn random_case_only_counts_words_it_changes() {
+    assert_eq!(random_case_entropy_per_word(&["alpha", "beta"]), 1.0);
+    assert_eq!(
+        random_case_entropy_per_word(&["alpha", "Beta", "42"]),
+        1.0 / 3.0
+    );
+    assert_eq!(random_case_entropy_per_word(&["犬", "猫"]), 0.0);
+}
+
 #[test]
 fn can_make_title_case() {
     let test_word = "alpha";
@@ -141,3 +792,122 @@ fn can_make_title_case() {
     let test_word = "ALPHA";
     assert_eq!(make_title_case(test_word), "Alpha".to_string());
 }
+
+#[test]
+fn can_capitalize_words_at_given_positions() {
+    let words = ["alpha", "beta", "gamma", "delta"];
+    assert_eq!(
+        capitalize_words_at(&words, &[3]),
+        vec!["alpha", "beta", "Gamma", "delta"]
+    );
+    assert_eq!(
+        capitalize_words_at(&words, &[1, 4, 4]),
+        vec!["Alpha", "beta", "gamma", "Delta"]
+    );
+    assert_eq!(capitalize_words_at(&words, &[]), words);
+}
+
+#[test]
+fn strength_levels_map_to_entropy_floors() {
+    assert_eq!(StrengthLevel::Medium.minimum_entropy(), 80);
+    assert_eq!(
+        calculate_number_words_needed(
+            None,
+            Some(StrengthLevel::Paranoid.minimum_entropy()),
+            0,
+            8192,
+            None
+        ),
+        10
+    );
+}
+
+#[test]
+fn can_describe_entropy_symbolically() {
+    assert_eq!(
+        describe_entropy_symbolically(7, 8192),
+        "7 × log2(8192) = 7 × 13 = 91 bits"
+    );
+    assert_eq!(
+        describe_entropy_symbolically(7, 7776),
+        "7 × log2(7776) ≈ 7 × 12.92 ≈ 90.47 bits"
+    );
+}
+
+#[test]
+fn can_tell_when_title_case_has_no_effect() {
+    let japanese = ["さくら", "やま", "かわ"];
+    assert!(!title_case_changes_any_word(&japanese));
+    assert!(!any_word_starts_with_cased_letter(&japanese));
+
+    let already_title_case = ["Alpha", "Beta"];
+    assert!(!title_case_changes_any_word(&already_title_case));
+    assert!(any_word_starts_with_cased_letter(&already_title_case));
+
+    assert!(title_case_changes_any_word(&["alpha", "さくら"]));
+}
+
+#[test]
+fn can_find_words_two_lists_share() {
+    let birds = ["robin", "swift", "crane", "swift"];
+    let machines = ["crane", "drill", "swift"];
+    assert!(!lists_disjoint(&birds, &machines));
+    assert_eq!(overlapping_words(&birds, &machines), vec!["swift", "crane"]);
+    assert!(lists_disjoint(&birds, &["drill", "lathe"]));
+    assert!(lists_disjoint(&[], &machines));
+}
+
+#[test]
+fn can_work_out_mean_word_length() {
+    assert_eq!(mean_word_length(&["ox", "cat", "bird"]), 3.0);
+    assert_eq!(mean_word_length(&["e\u{301}lan", "café"]), 4.0);
+    let empty: [&str; 0] = [];
+    assert_eq!(mean_word_length(&empty), 0.0);
+    assert_eq!(ListChoice::Effshort.name(), "EFF short list");
+}
+
+#[test]
+fn can_count_characters_like_a_person_would() {
+    // "élan" with a combining acute accent is 5 chars, but 4 characters to a reader
+    let word = "e\u{301}lan";
+    assert_eq!(word.chars().count(), 5);
+    assert_eq!(grapheme_length(word), 4);
+    // A thumbs up with a skin tone modifier is 2 chars, but 1 character
+    let passphrase = "alpha\u{1F44D}\u{1F3FD}beta";
+    assert_eq!(passphrase.chars().count(), 11);
+    assert_eq!(grapheme_length(passphrase), 10);
+    assert_eq!(grapheme_length(""), 0);
+}
+
+#[test]
+fn can_push_title_case_words() {
+    let mut passphrase = String::new();
+    push_title_case(&mut passphrase, "alpha");
+    push_title_case(&mut passphrase, "BETA");
+    push_title_case(&mut passphrase, "élan");
+    assert_eq!(passphrase, "AlphaBetaÉlan");
+}
+
+#[test]
+fn pushing_title_case_matches_making_title_case() {
+    // Words with characters whose case mappings are unusual: ß uppercases to two characters,
+    // ǆ has a separate title case form, Σ depends on where it is in the word, and İ lowercases
+    // to two characters
+    let words = [
+        "straße",
+        "ßa",
+        "ǆungla",
+        "ǅungla",
+        "ΟΔΟΣ",
+        "ΣΟΦΙΑ",
+        "İstanbul",
+        "ÉLAN",
+        "ﬁre",
+        "Ωmega",
+    ];
+    for word in words {
+        let mut passphrase = String::new();
+        push_title_case(&mut passphrase, word);
+        assert_eq!(passphrase, make_title_case(word), "Word was {}", word);
+    }
+}
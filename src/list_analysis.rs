@@ -0,0 +1,370 @@
+use crate::grapheme_length;
+use crate::histogram::length_histogram;
+use crate::separators::is_separator_character;
+use crate::unicode_normalization_check::{nfc_form, uniform_unicode_normalization};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Fewest words a list should have by default to pass an audit. Phraze's shortest built-in lists
+/// have 1,296 words (so they work with four six-sided dice).
+pub const AUDIT_MIN_WORDS: usize = 1000;
+
+/// Shortest and longest words, in characters, that count as a reasonable length. Very short words
+/// are easy to guess on their own, and very long ones are a chore to type.
+pub const AUDIT_SHORTEST_WORD: usize = 3;
+pub const AUDIT_LONGEST_WORD: usize = 15;
+
+/// Most example words to show in a check's details.
+const MAX_EXAMPLES: usize = 5;
+
+/// What we know about a word list, for judging whether it's a good list to make passphrases from.
+/// Build one with `ListAnalysis::new`, then check it over with `audit`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ListAnalysis {
+    /// How many words the list has as written, counting every duplicate
+    pub words_read: usize,
+    /// How many different words Phraze would actually use, once duplicates are removed
+    pub distinct_words: usize,
+    /// Words that appear more than once, exactly the same each time
+    pub exact_duplicates: Vec<String>,
+    /// Words that are spelled differently from an earlier word, but only in their Unicode
+    /// normalization, so they look the same
+    pub normalized_duplicates: Vec<String>,
+    /// Whether every word uses the same Unicode normalization
+    pub uniform_normalization: bool,
+    /// Pairs of words where the first is the start of the second, like "sun" and "sunflower"
+    pub prefix_pairs: Vec<(String, String)>,
+    /// Words with whitespace or a character that could be a separator in them
+    pub words_with_separators: Vec<String>,
+    /// How many distinct words there are of each length, in characters
+    pub length_histogram: BTreeMap<usize, usize>,
+}
+
+impl ListAnalysis {
+    /// Analyze a list's words, as written (like from `read_in_words_as_written`).
+    pub fn new(words: &[String]) -> Self {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for word in words {
+            *counts.entry(word).or_insert(0) += 1;
+        }
+        let mut exact_duplicates: Vec<String> = counts
+            .iter()
+            .filter(|(_, count)| **count > 1)
+            .map(|(word, _)| word.to_string())
+            .collect();
+        exact_duplicates.sort();
+
+        // Now work with each word once, in sorted order, like Phraze would read the list
+        let mut distinct: Vec<&str> = counts.keys().copied().collect();
+        distinct.sort();
+        let mut seen = HashSet::new();
+        let mut normalized_duplicates = vec![];
+        distinct.retain(|word| match seen.insert(nfc_form(word)) {
+            true => true,
+            false => {
+                normalized_duplicates.push(word.to_string());
+                false
+            }
+        });
+
+        // In sorted order, a word that starts another word is always right before a word it
+        // starts
+        let prefix_pairs = distinct
+            .windows(2)
+            .filter(|pair| pair[1].starts_with(pair[0]))
+            .map(|pair| (pair[0].to_string(), pair[1].to_string()))
+            .collect();
+        let words_with_separators = distinct
+            .iter()
+            .filter(|word| word.chars().any(is_separator_character))
+            .map(|word| word.to_string())
+            .collect();
+        let distinct_words: Vec<String> = distinct.iter().map(|word| word.to_string()).collect();
+
+        ListAnalysis {
+            words_read: words.len(),
+            distinct_words: distinct.len(),
+            exact_duplicates,
+            normalized_duplicates,
+            uniform_normalization: uniform_unicode_normalization(&distinct_words),
+            prefix_pairs,
+            words_with_separators,
+            length_histogram: length_histogram(distinct.iter().map(|word| grapheme_length(word))),
+        }
+    }
+
+    /// Run every `AuditCheck` on the list, in the order they're listed in `AuditCheck::ALL`.
+    /// `min_words` is the fewest distinct words the list needs for the minimum size check.
+    pub fn audit(&self, min_words: usize) -> Vec<AuditResult> {
+        AuditCheck::ALL
+            .iter()
+            .map(|check| {
+                let (passed, details) = self.run_check(*check, min_words);
+                AuditResult {
+                    check: *check,
+                    passed,
+                    details,
+                }
+            })
+            .collect()
+    }
+
+    fn run_check(&self, check: AuditCheck, min_words: usize) -> (bool, String) {
+        match check {
+            AuditCheck::MinSize => (
+                self.distinct_words >= min_words,
+                format!(
+                    "{} distinct words (at least {} needed)",
+                    self.distinct_words, min_words
+                ),
+            ),
+            AuditCheck::Duplicates => match self.exact_duplicates.len() {
+                0 => (true, "no word appears twice".to_string()),
+                count => (
+                    false,
+                    format!(
+                        "duplicated words ({}): {}",
+                        count,
+                        examples(self.exact_duplicates.iter())
+                    ),
+                ),
+            },
+            AuditCheck::NormalizedDuplicates => match self.normalized_duplicates.len() {
+                0 => (true, "no words only differ in Unicode normalization".to_string()),
+                count => (
+                    false,
+                    format!(
+                        "words that only differ from another word in Unicode normalization ({}): {}",
+                        count,
+                        examples(self.normalized_duplicates.iter())
+                    ),
+                ),
+            },
+            AuditCheck::UniformNormalization => match self.uniform_normalization {
+                true => (true, "every word uses the same Unicode normalization".to_string()),
+                false => (
+                    false,
+                    "words use more than one Unicode normalization (phraze clean-list can fix this)"
+                        .to_string(),
+                ),
+            },
+            AuditCheck::PrefixFree => match self.prefix_pairs.len() {
+                0 => (true, "no word is the start of another word".to_string()),
+                count => (
+                    false,
+                    format!(
+                        "words that are the start of another word ({}), like {}",
+                        count,
+                        examples(
+                            self.prefix_pairs
+                                .iter()
+                                .map(|(prefix, word)| format!("{} and {}", prefix, word))
+                        )
+                    ),
+                ),
+            },
+            AuditCheck::NoSeparators => match self.words_with_separators.len() {
+                0 => (
+                    true,
+                    "no words have whitespace, numbers or separator symbols in them".to_string(),
+                ),
+                count => {
+                    // Quote the words, so any whitespace in them shows up
+                    let quoted = self
+                        .words_with_separators
+                        .iter()
+                        .map(|word| format!("'{}'", word));
+                    (
+                        false,
+                        format!(
+                            "words with whitespace, numbers or separator symbols in them ({}): {}",
+                            count,
+                            examples(quoted)
+                        ),
+                    )
+                }
+            },
+            AuditCheck::LengthDistribution => self.check_lengths(),
+        }
+    }
+
+    fn check_lengths(&self) -> (bool, String) {
+        let (shortest, longest) = match (
+            self.length_histogram.keys().next(),
+            self.length_histogram.keys().last(),
+        ) {
+            (Some(shortest), Some(longest)) => (*shortest, *longest),
+            _ => return (false, "list has no words".to_string()),
+        };
+        let too_short: usize = self
+            .length_histogram
+            .range(..AUDIT_SHORTEST_WORD)
+            .map(|(_, count)| count)
+            .sum();
+        let too_long: usize = self
+            .length_histogram
+            .range(AUDIT_LONGEST_WORD + 1..)
+            .map(|(_, count)| count)
+            .sum();
+        let total_length: usize = self
+            .length_histogram
+            .iter()
+            .map(|(length, count)| length * count)
+            .sum();
+        let summary = format!(
+            "words are {} to {} characters long, {:.1} on average",
+            shortest,
+            longest,
+            total_length as f64 / self.distinct_words as f64
+        );
+        match (too_short, too_long) {
+            (0, 0) => (true, summary),
+            _ => (
+                false,
+                format!(
+                    "{}; {} shorter than {} and {} longer than {}",
+                    summary, too_short, AUDIT_SHORTEST_WORD, too_long, AUDIT_LONGEST_WORD
+                ),
+            ),
+        }
+    }
+}
+
+/// One of the things `ListAnalysis::audit` checks a list for.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Hash)]
+pub enum AuditCheck {
+    /// The list has at least a minimum number of distinct words
+    MinSize,
+    /// No word appears twice
+    Duplicates,
+    /// No two words only differ in their Unicode normalization
+    NormalizedDuplicates,
+    /// Every word uses the same Unicode normalization
+    UniformNormalization,
+    /// No word is the start of another, so passphrases without separators can't be ambiguous
+    PrefixFree,
+    /// No word has whitespace or a character that could be a separator in it
+    NoSeparators,
+    /// Every word is between `AUDIT_SHORTEST_WORD` and `AUDIT_LONGEST_WORD` characters long
+    LengthDistribution,
+}
+
+impl AuditCheck {
+    /// Every check, in the order they're run.
+    pub const ALL: [AuditCheck; 7] = [
+        AuditCheck::MinSize,
+        AuditCheck::Duplicates,
+        AuditCheck::NormalizedDuplicates,
+        AuditCheck::UniformNormalization,
+        AuditCheck::PrefixFree,
+        AuditCheck::NoSeparators,
+        AuditCheck::LengthDistribution,
+    ];
+
+    /// The check's short name, as given on the command line, like "prefix-free".
+    pub fn name(&self) -> &'static str {
+        match self {
+            AuditCheck::MinSize => "min-size",
+            AuditCheck::Duplicates => "duplicates",
+            AuditCheck::NormalizedDuplicates => "normalized-duplicates",
+            AuditCheck::UniformNormalization => "uniform-normalization",
+            AuditCheck::PrefixFree => "prefix-free",
+            AuditCheck::NoSeparators => "separators",
+            AuditCheck::LengthDistribution => "length-distribution",
+        }
+    }
+
+    /// Find a check by its short name.
+    pub fn from_name(name: &str) -> Option<AuditCheck> {
+        AuditCheck::ALL
+            .into_iter()
+            .find(|check| check.name() == name.to_lowercase())
+    }
+
+    /// Whether a list has to pass this check to pass an audit, unless the user says otherwise.
+    /// Plenty of good lists aren't prefix-free (most of Phraze's built-in lists aren't, since a
+    /// separator keeps words apart), or have a few long words, so those checks only warn.
+    pub fn required_by_default(&self) -> bool {
+        !matches!(
+            self,
+            AuditCheck::PrefixFree | AuditCheck::LengthDistribution
+        )
+    }
+}
+
+/// How a list did on one check.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuditResult {
+    pub check: AuditCheck,
+    pub passed: bool,
+    /// What the check found, like how many words failed it, with a few examples
+    pub details: String,
+}
+
+/// List the first few of many examples, noting how many were left out.
+fn examples(items: impl Iterator<Item = impl ToString>) -> String {
+    let items: Vec<String> = items.map(|item| item.to_string()).collect();
+    let mut listed = items[..items.len().min(MAX_EXAMPLES)].join(", ");
+    if items.len() > MAX_EXAMPLES {
+        listed.push_str(&format!(" (and {} more)", items.len() - MAX_EXAMPLES));
+    }
+    listed
+}
+
+#[test]
+fn can_analyze_a_list() {
+    let words: Vec<String> = [
+        "sun",
+        "sunflower",
+        "tea",
+        "tea",
+        "caf\u{e9}",
+        "cafe\u{301}",
+        "ice cream",
+        "ox",
+    ]
+    .iter()
+    .map(|word| word.to_string())
+    .collect();
+    let analysis = ListAnalysis::new(&words);
+    assert_eq!(analysis.words_read, 8);
+    assert_eq!(analysis.distinct_words, 6);
+    assert_eq!(analysis.exact_duplicates, vec!["tea"]);
+    assert_eq!(analysis.normalized_duplicates, vec!["caf\u{e9}"]);
+    assert!(!analysis.uniform_normalization);
+    assert_eq!(
+        analysis.prefix_pairs,
+        vec![("sun".to_string(), "sunflower".to_string())]
+    );
+    assert_eq!(analysis.words_with_separators, vec!["ice cream"]);
+    assert_eq!(analysis.length_histogram.get(&2), Some(&1));
+
+    let results = analysis.audit(3);
+    assert_eq!(results.len(), AuditCheck::ALL.len());
+    // Only the minimum size check passes
+    for result in results {
+        assert_eq!(result.passed, result.check == AuditCheck::MinSize);
+    }
+}
+
+#[test]
+fn can_find_checks_by_name() {
+    for check in AuditCheck::ALL {
+        assert_eq!(AuditCheck::from_name(check.name()), Some(check));
+    }
+    assert_eq!(
+        AuditCheck::from_name("Prefix-Free"),
+        Some(AuditCheck::PrefixFree)
+    );
+    assert_eq!(AuditCheck::from_name("spelling"), None);
+    assert!(!AuditCheck::PrefixFree.required_by_default());
+    assert!(AuditCheck::Duplicates.required_by_default());
+}
+
+#[test]
+fn examples_are_cut_short() {
+    assert_eq!(examples(["a", "b"].iter()), "a, b");
+    assert_eq!(
+        examples(["a", "b", "c", "d", "e", "f", "g"].iter()),
+        "a, b, c, d, e (and 2 more)"
+    );
+}
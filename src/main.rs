@@ -1,12 +1,95 @@
-use crate::file_reader::read_in_custom_list;
-use clap::Parser;
+use crate::abbreviation::{abbreviate, abbreviation_length};
+use crate::batch::{longest_possible_extra_word, longest_possible_passphrase, PassphraseBatch};
+use crate::compound::{
+    compound_collisions, compound_slot_entropy, join_compounds, WORDS_PER_COMPOUND,
+};
+use crate::confusables::visually_confusable_words;
+use crate::dice::{
+    dice_compatible_length, dice_worksheet, format_rolls, index_to_rolls, parse_rolls,
+    rolls_per_word, rolls_to_index,
+};
+use crate::digits::{distinct_letters, fewest_letters, inject_digits, injected_digits_entropy};
+use crate::file_reader::{
+    clean_list, custom_list_from_reader, format_cleaned_list, read_in_custom_list,
+    read_in_custom_list_with_directives, read_in_definitions_list, read_in_list_from_command,
+    read_in_part_of_speech_list, read_in_structured_list, read_in_system_dictionary,
+    read_in_weighted_custom_list, read_in_words_as_written, ListDirectives, ListFormat,
+    ListMetadata, MIN_USABLE_LIST_LENGTH, SYSTEM_DICTIONARY_PATH,
+};
+use crate::grammar::{
+    cycle_slots, describe_grammar_slots, generate_grammatical_words, grammar_entropy,
+    parse_grammar, positions_needed_for_entropy, MIN_WORDS_PER_PART_OF_SPEECH,
+};
+use crate::hint::structural_hint;
+use crate::histogram::{format_histogram, length_histogram};
+use crate::history::{pool_exhaustion_warning, History};
+use crate::indices::{
+    decode_indices, encode_indices, list_fingerprint, read_index_file, word_indices,
+    words_from_indices, write_index_file, IndexEncoding,
+};
+use crate::keyboard::{
+    generate_words_with_qwerty_flow, longest_keyboard_run, qwerty_flow_entropy,
+    qwerty_flow_entropy_per_word, typing_effort, KeyboardLayout, KEYBOARD_RUN_MAX_LENGTH,
+};
+use crate::list_analysis::{AuditCheck, ListAnalysis, AUDIT_MIN_WORDS};
+use crate::memorability::memorability_score;
+use crate::messages::{set_language, text, Language, Message};
+use crate::output::{
+    annotate_entropy, check_tty_print, format_entropy, format_passphrases, heatmap, pad_words,
+    passphrase_pieces, shell_quote, EntropyRounding, OutputFormat, OutputStream, HEATMAP_COLORS,
+    STREAM_FLUSH_INTERVAL,
+};
+use crate::policy::{load_policy, Policy};
+use crate::required::{
+    check_required_count, inject_word, injected_word_entropy, insert_required_words,
+    number_of_words_needed_with_required, required_words_entropy,
+};
+use crate::restrictions::{
+    check_distinct_first_words, find_common_substring, highest_vowel_ratio, longest_repeated_run,
+    longest_same_length_run, vowel_ratio, word_character_count, REJECTION_MAX_TRIES,
+};
+use crate::rng::{check_os_rng, salted_seeded_rng, seeded_rng, token_rng, RngSource};
+use crate::screen::{reveal_words, show_then_clear, ClearReason};
+use crate::separators::{
+    check_min_distinct_separators, generate_distinct_separators, generate_pool_separators,
+    generated_separator_entropy, is_generated_separator, parse_separator_pool,
+    separator_pool_entropy,
+};
+use crate::sinks::{send_to_sinks, Sink};
+use crate::token::{read_token_response, TokenResponseSource};
+use crate::totp::generate_totp_secret;
+use crate::unicode_normalization_check::{canonical_form, normalize, NormalizationForm};
+use crate::verification::check_could_be_generated;
+use crate::weighted::{
+    generate_weighted_words, make_weighted_distribution, shannon_entropy_per_word,
+    word_self_information,
+};
+use crate::word_bank::{
+    bank_selections, make_word_bank, read_word_bank, words_from_selections, write_word_bank,
+    DEFAULT_WORD_BANK_SIZE,
+};
+use crate::word_pattern::words_matching_pattern;
+use clap::parser::ValueSource;
+use clap::{ArgAction, ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand};
 use phraze::*;
-use std::path::PathBuf;
+use rand::distributions::WeightedIndex;
+use rand::{thread_rng, Rng};
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::hint::black_box;
+use std::io::{stdout, BufWriter, ErrorKind, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Generate random passphrases
 #[derive(Parser, Debug)]
-#[clap(version, name = "phraze")]
+#[clap(version, name = "phraze", args_conflicts_with_subcommands = true)]
 struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
     /// Strengthen your passphrase the easy way: Each flag increases minimum entropy by 20 bits (above the default of
     /// 80 bits).
     #[clap(short = 'S', long = "strength", conflicts_with = "number_of_words", conflicts_with = "minimum_entropy", action = clap::ArgAction::Count)]
@@ -17,25 +100,107 @@ struct Args {
     #[clap(
         short = 'e',
         long = "minimum-entropy",
-        conflicts_with = "number_of_words",
         conflicts_with = "strength_count"
     )]
     minimum_entropy: Option<usize>,
 
     /// Set exactly how many words to use in generated passphrase. If neither number_of_words or
     /// minimum_entropy is specified, Phraze will default to an 80-bit minimum.
+    #[clap(short = 'w', long = "words", conflicts_with = "strength_count")]
+    number_of_words: Option<usize>,
+
+    /// Set minimum entropy with a named strength level, rather than a number of bits.
+    ///
+    /// Options:
+    ///
+    /// low: 60 bits, for accounts that lock out repeated guesses
+    ///
+    /// medium: 80 bits, same as the default
+    ///
+    /// high: 100 bits, for things that could face offline guessing, like a password manager
+    ///
+    /// paranoid: 128 bits
     #[clap(
-        short = 'w',
-        long = "words",
+        long = "level",
+        value_parser=parse_strength_level,
+        conflicts_with = "number_of_words",
         conflicts_with = "minimum_entropy",
-        conflicts_with = "strength_count"
+        conflicts_with = "strength_count",
+        conflicts_with = "grammar"
     )]
-    number_of_words: Option<usize>,
+    level: Option<StrengthLevel>,
 
     /// Number of passphrases to generate
     #[clap(short = 'n', long = "passphrases", default_value = "1")]
     n_passphrases: usize,
 
+    /// Keep generating passphrases, one per line, until whatever's reading them stops (or
+    /// Ctrl-C is pressed), like for load testing something that takes in passphrases. Phraze
+    /// then exits without an error. Options that look at a whole batch of passphrases, or print
+    /// something extra with each one, can't be used with this.
+    #[clap(
+        long = "stream",
+        conflicts_with = "n_passphrases",
+        conflicts_with = "output_format",
+        conflicts_with = "sort_by_score",
+        conflicts_with = "distinct_first_words",
+        conflicts_with = "max_word_frequency",
+        conflicts_with = "history_file_path",
+        conflicts_with = "record_indices_path",
+        conflicts_with = "encode_indices",
+        conflicts_with = "word_bank_path",
+        conflicts_with = "security_answers",
+        conflicts_with = "output_path",
+        conflicts_with = "copy",
+        conflicts_with = "store_keyring",
+        conflicts_with = "clear_after",
+        conflicts_with = "stats_only",
+        conflicts_with = "entropy_histogram",
+        conflicts_with = "could_be_generated",
+        conflicts_with = "worksheet",
+        conflicts_with = "seed",
+        conflicts_with = "hint",
+        conflicts_with = "abbreviate",
+        conflicts_with = "show_definitions",
+        conflicts_with = "show_rolls",
+        conflicts_with = "score",
+        conflicts_with = "annotate_entropy",
+        conflicts_with = "pad_words",
+        conflicts_with = "heatmap",
+        conflicts_with = "with_totp_secret"
+    )]
+    stream: bool,
+
+    /// With --stream, generate at most this many passphrases per second, like 0.5 for one every
+    /// 2 seconds
+    #[clap(long = "rate", value_name = "PER_SECOND", requires = "stream")]
+    rate: Option<f64>,
+
+    /// Generate this many random answers for security questions (like "mother's maiden name"),
+    /// to store in a password manager, one per line with a number in front of it. Answers are
+    /// shorter than passphrases: 2 words with a space between them, unless --words (or a minimum
+    /// entropy) and --sep say otherwise. Each answer's estimated entropy is printed to standard
+    /// error.
+    #[clap(
+        long = "security-answers",
+        conflicts_with = "n_passphrases",
+        conflicts_with = "output_format",
+        conflicts_with = "encode_indices",
+        conflicts_with = "word_bank_path",
+        conflicts_with = "sort_by_score",
+        conflicts_with = "score",
+        conflicts_with = "pad_words",
+        conflicts_with = "annotate_entropy",
+        conflicts_with = "entropy_histogram",
+        conflicts_with = "could_be_generated",
+        conflicts_with = "from_word_bank_path",
+        conflicts_with = "replay_indices_path",
+        conflicts_with = "encoded_indices",
+        conflicts_with = "dice_input",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    security_answers: Option<usize>,
+
     /// Word separator. Can accept single quotes around the separator.
     ///
     /// There are special values that will trigger generated separators:
@@ -48,6 +213,19 @@ struct Args {
     #[clap(short = 's', long = "sep", default_value = "-")]
     separator: String,
 
+    /// Draw each separator independently from a pool of characters, made from a comma-separated
+    /// list of: digits, symbols (the same ones _s uses), unused-letters (lowercase letters that
+    /// aren't in any word in the list) and custom:<characters>. For example,
+    /// `--sep-pool 'digits,symbols,custom:£€'`. Since custom: takes every character after it
+    /// (commas included), it has to come last. Every character in the pool is equally likely,
+    /// so, unlike --sep, the separators count toward the entropy estimate.
+    #[clap(
+        long = "sep-pool",
+        conflicts_with = "separator",
+        conflicts_with = "min_distinct_separators"
+    )]
+    separator_pool: Option<String>,
+
     /// Choose a word list to use.
     ///
     /// Options:
@@ -68,126 +246,3939 @@ struct Args {
     ///
     /// a: Orchard Street Alpha list (1,296 words). Optimized to minimize travel distance on an
     /// alphabetical keyboard layout
-    #[clap(short = 'l', long = "list", value_parser=parse_list_choice, default_value="m")]
-    list_choice: ListChoice,
+    ///
+    /// j: Emoji list (975 emoji). For systems that accept emoji in passwords. Each emoji is
+    /// worth about 9.93 bits.
+    ///
+    /// With --per-position, this can be given more than once.
+    #[clap(
+        short = 'l',
+        long = "list",
+        id = "list_choice",
+        value_name = "LIST_CHOICE",
+        value_parser=parse_list_choice,
+        default_value="m",
+        action = ArgAction::Append
+    )]
+    list_choices: Vec<ListChoice>,
 
     /// Provide a text file with a list of words to randomly generate passphrase from.
     ///
-    /// Should be a text file with one word per line.
-    #[clap(short = 'c', long = "custom-list", conflicts_with = "list_choice")]
-    custom_list_file_path: Option<PathBuf>,
+    /// Should be a text file with one word per line. With --per-position, this can be given more
+    /// than once.
+    ///
+    /// Files ending in .json can be an array of words, or an object with a "words" array (and
+    /// optionally "name" and "source", which --stats-only shows). Files ending in .toml need a
+    /// `words = [...]` array, and can have name and source too.
+    #[clap(
+        short = 'c',
+        long = "custom-list",
+        id = "custom_list_file_path",
+        value_name = "CUSTOM_LIST_FILE_PATH",
+        action = ArgAction::Append
+    )]
+    custom_list_file_paths: Vec<PathBuf>,
+
+    /// Read --custom-list files as this format, whatever their extension: text, json or toml
+    #[clap(
+        long = "custom-list-format",
+        value_parser=parse_list_format,
+        requires = "custom_list_file_path"
+    )]
+    custom_list_format: Option<ListFormat>,
+
+    /// Use the system's dictionary as the word list, for computers without any other list
+    /// handy: /usr/share/dict/words, or the dictionary at this path. Proper nouns (entries that
+    /// start with an uppercase letter), possessives like "aardvark's", and words shorter than
+    /// --system-dict-min-length or longer than --system-dict-max-length are left out.
+    #[clap(
+        long = "system-dict",
+        value_name = "PATH",
+        num_args = 0..=1,
+        default_missing_value = SYSTEM_DICTIONARY_PATH,
+        conflicts_with = "custom_list_file_path",
+        conflicts_with = "list_choice",
+        conflicts_with = "per_position",
+        conflicts_with = "weighted",
+        conflicts_with = "grammar",
+        conflicts_with = "show_definitions"
+    )]
+    system_dict_path: Option<PathBuf>,
+
+    /// Shortest word, in characters, to take from the system dictionary
+    #[clap(
+        long = "system-dict-min-length",
+        value_name = "CHARACTERS",
+        default_value = "4",
+        requires = "system_dict_path"
+    )]
+    system_dict_min_length: usize,
+
+    /// Longest word, in characters, to take from the system dictionary
+    #[clap(
+        long = "system-dict-max-length",
+        value_name = "CHARACTERS",
+        default_value = "8",
+        requires = "system_dict_path"
+    )]
+    system_dict_max_length: usize,
+
+    /// Run this program and use what it prints as the word list, one word per line, for lists
+    /// another tool makes on the fly. The program is run directly, not through a shell, so give
+    /// each of its arguments with --list-cmd-arg. Its words are cleaned up like any other
+    /// custom list's.
+    #[clap(
+        long = "list-cmd",
+        value_name = "PROGRAM",
+        conflicts_with = "custom_list_file_path",
+        conflicts_with = "system_dict_path",
+        conflicts_with = "list_choice",
+        conflicts_with = "per_position",
+        conflicts_with = "weighted",
+        conflicts_with = "grammar",
+        conflicts_with = "show_definitions"
+    )]
+    list_command: Option<String>,
+
+    /// An argument to give the --list-cmd program. Give it more than once for more arguments,
+    /// in order.
+    #[clap(
+        long = "list-cmd-arg",
+        value_name = "ARG",
+        allow_hyphen_values = true,
+        requires = "list_command",
+        action = ArgAction::Append
+    )]
+    list_command_arguments: Vec<String>,
+
+    /// Stop the --list-cmd program, and exit with an error, if it hasn't finished after this many
+    /// seconds
+    #[clap(
+        long = "list-cmd-timeout",
+        value_name = "SECONDS",
+        default_value = "10",
+        requires = "list_command"
+    )]
+    list_command_timeout: u64,
+
+    /// Give each position in the passphrase its own list, in the order --list and --custom-list
+    /// are given. For example, `--per-position -l q -l m -l m -l m -l m -l n` makes passphrases
+    /// of one QWERTY list word, four Medium list words and one Mnemonicode word. If passphrases
+    /// need more words than there are lists, positions cycle back through the lists from the
+    /// start. Entropy is the sum of log2 of each position's list length.
+    #[clap(
+        long = "per-position",
+        conflicts_with = "grammar",
+        conflicts_with = "weighted",
+        conflicts_with = "require_from_file_path",
+        conflicts_with = "qwerty_flow",
+        conflicts_with = "history_file_path",
+        conflicts_with = "record_indices_path",
+        conflicts_with = "replay_indices_path",
+        conflicts_with = "encode_indices",
+        conflicts_with = "encoded_indices",
+        conflicts_with = "show_rolls",
+        conflicts_with = "dice_input"
+    )]
+    per_position: bool,
 
     /// Use Title Case for words in generated usernames
     #[clap(short = 't', long = "title-case")]
     title_case: bool,
 
+    /// Use Title Case for only the word at this position (counting from 1), like 3 for the
+    /// third word. Give it more than once to capitalize more than one word. Since the positions
+    /// are fixed, this doesn't add any entropy.
+    #[clap(long = "capitalize-nth", conflicts_with = "title_case")]
+    capitalize_nth: Vec<usize>,
+
+    /// Flip a coin for each word to decide whether it's in Title Case. The main entropy
+    /// estimate doesn't count the coin flips (like generated separators, they're easy for an
+    /// attacker to try), but --verbose shows what they'd add.
+    #[clap(
+        long = "random-case",
+        conflicts_with = "title_case",
+        conflicts_with = "capitalize_nth",
+        conflicts_with = "canonicalize",
+        conflicts_with = "pad_words",
+        conflicts_with = "heatmap",
+        conflicts_with = "reveal_delay",
+        conflicts_with = "hint"
+    )]
+    random_case: bool,
+
     /// Print estimated entropy of generated passphrase, in bits, along with the passphrase itself
     #[clap(short = 'v', long = "verbose")]
     verbose: bool,
-}
 
-fn main() -> Result<(), String> {
-    let opt = Args::parse();
+    /// Along with --verbose, show how the entropy estimate is worked out, like
+    /// "7 × log2(8192) = 7 × 13 = 91 bits". Lists with a power-of-two number of words give
+    /// whole numbers of bits per word, so their estimates are exact.
+    #[clap(long = "symbolic-entropy", requires = "verbose")]
+    symbolic_entropy: bool,
 
-    // Check for a rare but potentially dangerous combination of settings
-    if opt.custom_list_file_path.is_some() && opt.separator.is_empty() && !opt.title_case {
-        let error_msg = "Must use a separator or Title Case when using a custom word list";
-        return Err(error_msg.to_string());
-    }
+    /// Where to print the entropy estimate when using --verbose.
+    ///
+    /// Options:
+    ///
+    /// stderr: print it to standard error, so it shows in the terminal but isn't piped or
+    /// redirected with the passphrases [DEFAULT]
+    ///
+    /// stdout: print it to standard output, along with the passphrases
+    #[clap(long = "entropy-to", value_parser=parse_output_stream, default_value="stderr")]
+    entropy_to: OutputStream,
 
-    // We need two different variables here, one for a user-inputted list and another for
-    // the built-in list (whether chosen or the default). This is because we use different
-    // variable types for each case.
-    let (custom_list, built_in_list) = match opt.custom_list_file_path {
-        Some(custom_list_file_path) => (Some(read_in_custom_list(&custom_list_file_path)?), None),
-        None => (None, Some(fetch_list(opt.list_choice))),
-    };
+    /// How many decimal places to print entropy estimates with
+    #[clap(long = "entropy-precision", value_name = "N", default_value = "2")]
+    entropy_precision: usize,
 
-    // If a "custom_list" was given by the user, we're going to use that list.
-    // Otherwise we use the built-in list (a default list if the user didn't choose one).
+    /// How to round entropy estimates when printing them.
+    ///
+    /// Options:
+    ///
+    /// nearest: round to the nearest value [DEFAULT]
+    ///
+    /// floor: round down, so the printed estimate is never more than the real one, for
+    /// conservative reporting (like a compliance claim)
+    #[clap(long = "entropy-round", value_parser=parse_entropy_rounding, default_value="nearest")]
+    entropy_round: EntropyRounding,
 
-    // To get the length of the list we're going to use, we need to check if a
-    // custom_list was given.
-    let list_length = match custom_list {
-        Some(ref custom_list) => custom_list.len(),
-        None => built_in_list.unwrap().len(), // pretty sure we're safe to unwrap here...
-    };
+    /// Language to print Phraze's warnings, entropy estimates and errors in. Defaults to the
+    /// language of your locale (LC_ALL, LC_MESSAGES or LANG), or English if Phraze doesn't
+    /// have that one. The help text is always in English.
+    ///
+    /// Options:
+    ///
+    /// en: English
+    ///
+    /// es: Spanish
+    #[clap(long = "lang", value_name = "CODE", value_parser=parse_language)]
+    lang: Option<Language>,
 
-    // Since user can define a minimum entropy, we might have to do a little math to
-    // figure out how many words we need to include in this passphrase.
-    let number_of_words_to_put_in_passphrase = calculate_number_words_needed(
-        opt.number_of_words,
-        opt.minimum_entropy,
-        opt.strength_count,
-        list_length,
-    );
+    /// Same as --entropy-to stdout. Each line of the report starts with "# entropy: ", and
+    /// the whole report comes before any passphrases.
+    #[clap(long = "entropy-to-stdout", conflicts_with = "entropy_to")]
+    entropy_to_stdout: bool,
 
-    // If user enabled verbose option
-    if opt.verbose {
-        // print entropy information, but use eprint to only print it
-        // to the terminal
-        print_entropy(
-            number_of_words_to_put_in_passphrase,
-            list_length,
-            opt.n_passphrases,
-        );
-    }
+    /// Abort with an error, rather than generate anything, if the operating system's secure
+    /// random number source is unavailable. Only the OS's native source (e.g. the getrandom
+    /// syscall on Linux, getentropy on macOS, BCryptGenRandom on Windows) is accepted.
+    #[clap(long = "require-os-rng")]
+    require_os_rng: bool,
 
-    // Now we can (finally) generate and print some number of passphrases
-    for _ in 0..opt.n_passphrases {
-        // Again, we have more code than we should because of this pesky list type situation...
-        let passphrase = match (&custom_list, built_in_list) {
-            (Some(ref custom_list), _) => generate_passphrase(
-                number_of_words_to_put_in_passphrase,
-                &opt.separator,
-                opt.title_case,
-                custom_list,
-            ),
-            (None, Some(built_in_list)) => generate_passphrase(
-                number_of_words_to_put_in_passphrase,
-                &opt.separator,
-                opt.title_case,
-                built_in_list,
-            ),
-            (None, None) => return Err("List selection error!".to_string()),
-        };
-        println!("{}", passphrase);
-    }
+    /// Abort with an error, rather than print passphrases, if standard output is an interactive
+    /// terminal, where passphrases could be left in the terminal's scrollback. Pipe or redirect
+    /// Phraze's output instead (e.g. to a clipboard tool or a file).
+    #[clap(long = "no-tty-print")]
+    no_tty_print: bool,
 
-    Ok(())
-}
+    /// Write the passphrases to this file (only readable by you, on Unix), instead of printing
+    /// them. Can be used along with --copy: if one of them fails, the passphrases still go to
+    /// the other one, then Phraze exits with an error.
+    #[clap(
+        long = "output",
+        value_name = "FILE",
+        conflicts_with = "encode_indices",
+        conflicts_with = "word_bank_path",
+        conflicts_with = "security_answers",
+        conflicts_with = "stats_only",
+        conflicts_with = "entropy_histogram",
+        conflicts_with = "could_be_generated",
+        conflicts_with = "from_word_bank_path",
+        conflicts_with = "replay_indices_path",
+        conflicts_with = "encoded_indices",
+        conflicts_with = "dice_input"
+    )]
+    output_path: Option<PathBuf>,
 
-/// Print the calculated (estimated) entropy of a passphrase, based on three variables
-fn print_entropy(number_of_words: usize, list_length: usize, n_passphrases: usize) {
-    let passphrase_entropy = (list_length as f64).log2() * number_of_words as f64;
-    // Depending on how many different passphrases the user wants printed, change the printed text
-    // accordingly
-    if n_passphrases == 1 {
-        eprintln!(
-            "Passphrase has an estimated {:.2} bits of entropy ({} words from a list of {} words)",
-            passphrase_entropy, number_of_words, list_length,
-        );
-    } else {
-        eprintln!(
-            "Each passphrase has an estimated {:.2} bits of entropy ({} words from a list of {} words)",
-            passphrase_entropy, number_of_words, list_length
-        );
-    }
-}
+    /// Copy the passphrases to the clipboard, instead of printing them, with the first of
+    /// wl-copy, xclip or xsel that's installed (pbcopy on macOS, clip on Windows), or the
+    /// command in the PHRAZE_CLIPBOARD_COMMAND environment variable. With --output too, the
+    /// file is written first.
+    #[clap(
+        long = "copy",
+        conflicts_with = "clear_after",
+        conflicts_with = "encode_indices",
+        conflicts_with = "word_bank_path",
+        conflicts_with = "security_answers",
+        conflicts_with = "stats_only",
+        conflicts_with = "entropy_histogram",
+        conflicts_with = "could_be_generated",
+        conflicts_with = "from_word_bank_path",
+        conflicts_with = "replay_indices_path",
+        conflicts_with = "encoded_indices",
+        conflicts_with = "dice_input"
+    )]
+    copy: bool,
 
-/// Convert list_choice string slice into a ListChoice enum. Clap calls this function.
-fn parse_list_choice(list_choice: &str) -> Result<ListChoice, String> {
-    match list_choice.to_lowercase().as_ref() {
-        "l" => Ok(ListChoice::Long),
-        "m" => Ok(ListChoice::Medium),
-        "e" => Ok(ListChoice::Eff),
-        "n" => Ok(ListChoice::Mnemonicode),
-        "s" => Ok(ListChoice::Effshort),
-        "q" => Ok(ListChoice::Qwerty),
-        "a" => Ok(ListChoice::Alpha),
-        _ => Err(format!(
-            "Inputted list choice '{}' doesn't correspond to an available word list",
-            list_choice
+    /// Save the passphrase in the operating system's keyring, as the password for this service
+    /// and account (like `--store-keyring mail.example.com sam`), instead of printing it. Only
+    /// works with one passphrase. Can be used along with --output and --copy.
+    #[clap(
+        long = "store-keyring",
+        num_args = 2,
+        value_names = ["SERVICE", "ACCOUNT"],
+        conflicts_with = "clear_after",
+        conflicts_with = "output_format",
+        conflicts_with = "encode_indices",
+        conflicts_with = "word_bank_path",
+        conflicts_with = "security_answers",
+        conflicts_with = "stats_only",
+        conflicts_with = "entropy_histogram",
+        conflicts_with = "could_be_generated",
+        conflicts_with = "from_word_bank_path",
+        conflicts_with = "replay_indices_path",
+        conflicts_with = "encoded_indices",
+        conflicts_with = "dice_input"
+    )]
+    store_keyring: Option<Vec<String>>,
+
+    /// Print the passphrases, then clear them off the screen after this many seconds, or as
+    /// soon as Enter (or Ctrl-C) is pressed. Only works when printing to a terminal. This only
+    /// clears what's on the screen: passphrases can still be in the terminal's scrollback.
+    #[clap(
+        long = "clear-after",
+        value_name = "SECONDS",
+        conflicts_with = "output_path",
+        conflicts_with = "encode_indices",
+        conflicts_with = "word_bank_path",
+        conflicts_with = "security_answers",
+        conflicts_with = "stats_only",
+        conflicts_with = "entropy_histogram",
+        conflicts_with = "could_be_generated",
+        conflicts_with = "from_word_bank_path",
+        conflicts_with = "replay_indices_path",
+        conflicts_with = "encoded_indices",
+        conflicts_with = "dice_input",
+        conflicts_with = "with_totp_secret",
+        conflicts_with = "abbreviate",
+        conflicts_with = "show_definitions",
+        conflicts_with = "show_rolls"
+    )]
+    clear_after: Option<u64>,
+
+    /// Print each passphrase one word at a time, waiting this many milliseconds after each
+    /// word, for reading a passphrase where someone could be looking over your shoulder. Only
+    /// does anything when printing to a terminal; otherwise passphrases are printed as usual.
+    #[clap(
+        long = "reveal-delay",
+        value_name = "MS",
+        conflicts_with = "output_path",
+        conflicts_with = "copy",
+        conflicts_with = "clear_after",
+        conflicts_with = "output_format",
+        conflicts_with = "encode_indices",
+        conflicts_with = "word_bank_path",
+        conflicts_with = "security_answers",
+        conflicts_with = "sort_by_score",
+        conflicts_with = "score",
+        conflicts_with = "annotate_entropy",
+        conflicts_with = "pad_words",
+        conflicts_with = "heatmap",
+        conflicts_with = "abbreviate",
+        conflicts_with = "inject_digit",
+        conflicts_with = "show_definitions",
+        conflicts_with = "stream"
+    )]
+    reveal_delay: Option<u64>,
+
+    /// With --reveal-delay, erase each word before showing the next, so the whole passphrase is
+    /// never on the screen at once.
+    #[clap(long = "reveal-clear", requires = "reveal_delay")]
+    reveal_clear: bool,
+
+    /// Prefer word sequences that are easy to type on a QWERTY keyboard, by re-drawing (a
+    /// limited number of times) any word whose first letter is far from the previous word's
+    /// last letter. Designed for use with the QWERTY list. This makes some words more likely than
+    /// others, so it slightly reduces entropy; use --verbose to see the reduced estimate.
+    #[clap(long = "qwerty-flow")]
+    qwerty_flow: bool,
+
+    /// Leave out words that could be misread as another word in some common fonts, like
+    /// "modern" and "modem" ("rn" looks like "m"), for passphrases that will be read off a
+    /// screen or printout and typed back in. Uses a built-in list of these words, and also
+    /// finds any pairs like them in the list itself. Entropy is worked out from the words that
+    /// are left.
+    #[clap(
+        long = "avoid-visual-confusables",
+        conflicts_with = "per_position",
+        conflicts_with = "grammar"
+    )]
+    avoid_visual_confusables: bool,
+
+    /// Only use words that match this regular expression, like "^[a-m]" for words starting with
+    /// a to m, or "^.{4,6}$" for words of 4 to 6 characters. The pattern can match anywhere in a
+    /// word unless it's anchored with ^ or $. Entropy is worked out from the words that match.
+    /// Needs the `word-pattern` feature.
+    #[clap(
+        long = "word-pattern",
+        value_name = "REGEX",
+        conflicts_with = "per_position",
+        conflicts_with = "grammar"
+    )]
+    word_pattern: Option<String>,
+
+    /// Reject (and re-generate) any passphrase containing a run of more than 3 neighboring keys
+    /// on a QWERTY keyboard, like "asdf" or "poiu", including runs across words. Especially
+    /// useful with the QWERTY list and no separator. This rules out some passphrases, which
+    /// very slightly reduces entropy.
+    #[clap(long = "no-keyboard-runs")]
+    no_keyboard_runs: bool,
+
+    /// Format to print passphrases in.
+    ///
+    /// Options:
+    ///
+    /// plain: one passphrase per line [DEFAULT]
+    ///
+    /// yaml: a YAML list of passphrases, handy for pasting into config files like Kubernetes
+    /// Secrets manifests, or a map of them with --label. Passphrases are quoted whenever YAML
+    /// requires it. Requires Phraze to be built with the `yaml` feature.
+    #[clap(long = "format", value_parser=parse_output_format, default_value="plain")]
+    output_format: OutputFormat,
+
+    /// With --format yaml, print a map rather than a list, with each passphrase under its own
+    /// label, like `db-password: ...`. Give --label once for each passphrase you want, and
+    /// they're labeled in the order they're printed. Labels must all be different.
+    #[clap(
+        long = "label",
+        value_name = "NAME",
+        conflicts_with = "n_passphrases",
+        conflicts_with = "security_answers"
+    )]
+    labels: Vec<String>,
+
+    /// Put every passphrase in this Unicode normalization form before printing it, so the same
+    /// passphrase is always the same bytes, no matter how the word list was written. Handy when
+    /// a passphrase with accented letters has to match exactly in another system. Words that
+    /// only differ in normalization are already counted once, so this doesn't change entropy.
+    /// (nfkc and nfkd also rewrite "compatibility" characters, like the "ﬁ" ligature, which
+    /// could make two different words look the same. Built-in lists don't have any.)
+    ///
+    /// Options: nfc (the default if no form is given), nfd, nfkc, nfkd
+    #[clap(
+        long = "output-normalization",
+        num_args = 0..=1,
+        default_missing_value = "nfc",
+        value_parser=parse_normalization_form
+    )]
+    output_normalization: Option<NormalizationForm>,
+
+    /// Print every passphrase in lowercase and Unicode Normalization Form C, for storing
+    /// (hashed) passphrases that have to match whatever keyboard or input method they're typed
+    /// in with. This overrides any case options, like --title-case. Words on the list that are
+    /// the same once lowercased (like "Mark" and "mark") are only counted once, so the list can
+    /// get shorter and passphrases weaker. With lists that are all lowercase ASCII, like the
+    /// built-in lists, this changes nothing.
+    #[clap(
+        long = "canonicalize",
+        conflicts_with = "output_normalization",
+        conflicts_with = "per_position",
+        conflicts_with = "grammar"
+    )]
+    canonicalize: bool,
+
+    /// Generate passphrases deterministically from this seed, so the same seed and settings
+    /// always give the same passphrases. Useful for testing or for splitting a reproducible
+    /// batch across machines. WARNING: Seeded passphrases have at most 64 bits of entropy, and
+    /// anyone who knows the seed can reproduce them.
+    #[clap(long = "seed")]
+    seed: Option<u64>,
+
+    /// When using --seed, start at this position in the seeded batch of passphrases. For
+    /// example, `--seed 7 --index 5 -n 1` prints exactly the 6th passphrase that
+    /// `--seed 7 -n 6` would print, without generating the first 5.
+    #[clap(long = "index", requires = "seed", default_value = "0")]
+    index: u64,
+
+    /// When using --seed, mix this salt into the seed, so the same seed gives unrelated
+    /// passphrases for each different salt. Use a fixed salt for each application (like its
+    /// name) to safely share one seed between applications. Salting doesn't make seeded
+    /// passphrases any stronger.
+    #[clap(long = "salt", requires = "seed")]
+    salt: Option<String>,
+
+    /// Generate passphrases deterministically from a hardware token's HMAC response to a
+    /// challenge, read in hex from standard input, like
+    /// `ykchalresp -2 -x mail | phraze --token-stdin`. Only someone with the token can make the
+    /// same passphrases again (with the same challenge and settings). Phraze never talks to the
+    /// token itself: it only reads the response. Requires the `token` feature.
+    #[clap(
+        long = "token-stdin",
+        conflicts_with = "seed",
+        conflicts_with = "token_helper",
+        conflicts_with = "stream"
+    )]
+    token_stdin: bool,
+
+    /// Like --token-stdin, but run this command to get the token's response, like
+    /// "ykchalresp -2 -x mail". The command is split on whitespace.
+    #[clap(
+        long = "token-helper",
+        value_name = "COMMAND",
+        conflicts_with = "seed",
+        conflicts_with = "stream"
+    )]
+    token_helper: Option<String>,
+
+    /// Keep a history of used words in this file, and never use a word that's in it again. Words
+    /// are stored as salted hashes rather than plain text, but since lists are small, anyone with
+    /// the file and the word list can still work out which words were used, so only you can read
+    /// it (mode 0600 on Unix). As words get used up, the list shrinks, and passphrases get longer
+    /// (or weaker, if using --words).
+    #[clap(long = "history-file")]
+    history_file_path: Option<PathBuf>,
+
+    /// Treat the custom list as a weighted list. Each line should be a word, a tab, and then a
+    /// positive number weight (`word<TAB>weight`), and words are picked in proportion to their
+    /// weights. Lines without a weight get a weight of 1. Since uneven weights make some words
+    /// more predictable, each word is worth fewer bits of entropy than an unweighted word;
+    /// --verbose reports the Shannon entropy of the weighted list.
+    #[clap(
+        long = "weighted",
+        requires = "custom_list_file_path",
+        conflicts_with = "qwerty_flow"
+    )]
+    weighted: bool,
+
+    /// Write the position of each chosen word in the word list to this file, so the passphrases
+    /// can be rebuilt later with --replay-indices (using the same list). WARNING: This file is
+    /// exactly as sensitive as the passphrases themselves, so only you can read it (mode 0600 on
+    /// Unix).
+    #[clap(long = "record-indices", conflicts_with = "replay_indices_path")]
+    record_indices_path: Option<PathBuf>,
+
+    /// Rebuild passphrases from a file written by --record-indices, rather than generating new
+    /// ones. Must use the same word list the file was made with. Separator and case options apply
+    /// as usual (randomly generated separators will be freshly generated).
+    #[clap(long = "replay-indices")]
+    replay_indices_path: Option<PathBuf>,
+
+    /// Print a compact encoding of the chosen words' positions in the word list after each
+    /// passphrase, separated from it by a tab. The passphrase can be rebuilt later by passing
+    /// this string to --indices (using the same list). WARNING: Anyone with the string and the
+    /// word list can rebuild the passphrase.
+    ///
+    /// Options:
+    ///
+    /// base32: RFC 4648 base32, without padding
+    ///
+    /// base58: Bitcoin-style base58, which leaves out easily confused characters
+    ///
+    /// --token is another name for this, which uses base58 if no encoding is given.
+    #[clap(
+        long = "encode-indices",
+        visible_alias = "token",
+        num_args = 0..=1,
+        default_missing_value = "base58",
+        value_parser=parse_index_encoding,
+        conflicts_with = "replay_indices_path",
+        conflicts_with = "output_format"
+    )]
+    encode_indices: Option<IndexEncoding>,
+
+    /// Rebuild a passphrase from a string printed by --encode-indices, rather than generating a
+    /// new one. Must use the same word list the string was made with. Give the same
+    /// --encode-indices encoding as when the string was made (base58 if not given).
+    /// --from-token is another name for this.
+    #[clap(
+        long = "indices",
+        visible_alias = "from-token",
+        conflicts_with = "replay_indices_path",
+        conflicts_with = "record_indices_path"
+    )]
+    encoded_indices: Option<String>,
+
+    /// Write a numbered "word bank" to this file: every word the passphrases use, mixed in with
+    /// other words picked at random from the list. Instead of the passphrases, print the bank
+    /// numbers that make up each one. Send the bank and the numbers over different channels
+    /// (like a letter and a phone call): only someone with both can rebuild a passphrase, with
+    /// --from-word-bank and --selections. WARNING: This is NOT a substitute for real secret
+    /// sharing. Anyone who gets the bank only has to guess which of its words were picked, which
+    /// is far easier than guessing from the whole list (--verbose says how much easier).
+    #[clap(
+        long = "word-bank",
+        conflicts_with = "output_format",
+        conflicts_with = "encode_indices",
+        conflicts_with = "sort_by_score",
+        conflicts_with = "entropy_histogram",
+        conflicts_with = "replay_indices_path",
+        conflicts_with = "encoded_indices",
+        conflicts_with = "dice_input"
+    )]
+    word_bank_path: Option<PathBuf>,
+
+    /// How many words to put in the --word-bank. Bigger banks are harder to guess from.
+    #[clap(
+        long = "word-bank-size",
+        requires = "word_bank_path",
+        default_value_t = DEFAULT_WORD_BANK_SIZE
+    )]
+    word_bank_size: usize,
+
+    /// Rebuild a passphrase from a word bank written by --word-bank and the bank numbers given
+    /// with --selections, rather than generating a new one. The word list isn't needed.
+    /// Separator and case options apply as usual.
+    #[clap(
+        long = "from-word-bank",
+        requires = "selections",
+        conflicts_with = "word_bank_path",
+        conflicts_with = "replay_indices_path",
+        conflicts_with = "encoded_indices"
+    )]
+    from_word_bank_path: Option<PathBuf>,
+
+    /// The bank numbers printed by --word-bank, like "12 5 88 40", for --from-word-bank
+    #[clap(long = "selections", requires = "from_word_bank_path")]
+    selections: Option<String>,
+
+    /// Check whether this passphrase could have been generated with the chosen list, separator
+    /// and case options, rather than generating a new one: whether it splits into words on the
+    /// separator and every word is on the list. Prints Yes or No, and which words aren't on the
+    /// list. This only checks the words, so it can't tell a generated passphrase from one made
+    /// up out of list words.
+    #[clap(
+        long = "could-be-generated",
+        conflicts_with = "word_bank_path",
+        conflicts_with = "from_word_bank_path",
+        conflicts_with = "replay_indices_path",
+        conflicts_with = "encoded_indices",
+        conflicts_with = "dice_input"
+    )]
+    could_be_generated: Option<String>,
+
+    /// When using generated separators (_n, _s or _b), re-generate a passphrase's separators
+    /// until at least this many of them are different from each other, so a passphrase isn't
+    /// stuck with the same separator everywhere by chance. This rejects some separator
+    /// combinations, so it makes separators very slightly more predictable.
+    #[clap(long = "min-distinct-separators")]
+    min_distinct_separators: Option<usize>,
+
+    /// Provide a text file with a themed list of words (one word per line). Each passphrase will
+    /// include --require-count words from this list, at random positions, with the rest of its
+    /// words from the main list. Themed lists are often short, which makes their words worth
+    /// fewer bits each; use --verbose to check the estimate.
+    #[clap(
+        long = "require-from",
+        conflicts_with = "record_indices_path",
+        conflicts_with = "encode_indices"
+    )]
+    require_from_file_path: Option<PathBuf>,
+
+    /// How many words from the --require-from list to put in each passphrase
+    #[clap(
+        long = "require-count",
+        requires = "require_from_file_path",
+        default_value = "1"
+    )]
+    require_count: usize,
+
+    /// After each passphrase, print a hint about its structure (like "4 words: 7-5-6-4 letters,
+    /// title case") to standard error, to help you remember it. WARNING: The hint gives away
+    /// each word's length, which makes the passphrase easier to guess for anyone who sees it.
+    #[clap(long = "hint")]
+    hint: bool,
+
+    /// Print each passphrase with every word cut down to its first few characters, like
+    /// "cor-hor-bat-sta". Phraze works out the fewest characters that tell every word on the list
+    /// apart, so the shorter passphrase has just as much entropy. The full words are printed to
+    /// standard error, to help you remember it. Lists where that takes as many characters as the
+    /// longest word are an error.
+    #[clap(
+        long = "abbreviate",
+        conflicts_with = "require_from_file_path",
+        conflicts_with = "grammar",
+        conflicts_with = "pad_words",
+        conflicts_with = "replay_indices_path",
+        conflicts_with = "encoded_indices",
+        conflicts_with = "dice_input"
+    )]
+    abbreviate: bool,
+
+    /// After each passphrase, print (to standard error) the dice rolls that would pick each of
+    /// its words, for checking a passphrase against a printed dice list. The list's length must
+    /// be a power of the number of sides of the dice (see --dice-base), like the EFF long list
+    /// with 6-sided dice.
+    #[clap(
+        long = "show-rolls",
+        conflicts_with = "require_from_file_path",
+        conflicts_with = "dice_input"
+    )]
+    show_rolls: bool,
+
+    /// Rather than generate a passphrase randomly, make one from the given dice rolls, like
+    /// "16345 22166 ..." for 6-sided dice. Rolls can be separated by spaces, commas or dashes.
+    /// Dice faces are numbered from 1 (so count the 0 on a 10-sided die as 10).
+    #[clap(
+        long = "dice-input",
+        conflicts_with = "replay_indices_path",
+        conflicts_with = "encoded_indices"
+    )]
+    dice_input: Option<String>,
+
+    /// Rather than generate a passphrase, print a worksheet for making passphrases with real
+    /// dice away from a computer: every word on the list, next to the dice rolls that pick it.
+    /// Print it, or save it with --output. The list's length must be a power of the number of
+    /// sides of the dice (see --dice-base).
+    #[clap(
+        long = "worksheet",
+        conflicts_with = "dice_input",
+        conflicts_with = "show_rolls",
+        conflicts_with = "per_position",
+        conflicts_with = "grammar",
+        conflicts_with = "stats_only",
+        conflicts_with = "entropy_histogram",
+        conflicts_with = "could_be_generated",
+        conflicts_with = "encode_indices",
+        conflicts_with = "encoded_indices",
+        conflicts_with = "replay_indices_path",
+        conflicts_with = "word_bank_path",
+        conflicts_with = "from_word_bank_path",
+        conflicts_with = "security_answers",
+        conflicts_with = "clear_after",
+        conflicts_with = "heatmap"
+    )]
+    worksheet: bool,
+
+    /// Cut the list down to the largest power of the number of sides of the dice (see
+    /// --dice-base) that fits, so it can be used with dice, like 7,776 of the Medium list's
+    /// 8,192 words for 6-sided dice. The words kept are the first ones once the list is sorted,
+    /// so anyone with the same list gets the same words, numbered the same way. Works with
+    /// --worksheet, --dice-input and --show-rolls, or on its own.
+    #[clap(
+        long = "dice-compatible",
+        conflicts_with = "weighted",
+        conflicts_with = "per_position",
+        conflicts_with = "grammar",
+        conflicts_with = "history_file_path",
+        conflicts_with = "avoid_visual_confusables",
+        conflicts_with = "canonicalize"
+    )]
+    dice_compatible: bool,
+
+    /// Cut the list down to the largest power of two that fits, like 4,096 of the EFF long
+    /// list's 7,776 words, so each word is worth a whole number of bits, and pick each word
+    /// with exactly that many random bits. Like --dice-compatible, the words kept are the first
+    /// ones once the list is sorted. Lists that are already a power of two (like the Medium
+    /// list's 8,192 words) aren't cut.
+    #[clap(
+        long = "pow2",
+        conflicts_with = "dice_compatible",
+        conflicts_with = "weighted",
+        conflicts_with = "per_position",
+        conflicts_with = "grammar",
+        conflicts_with = "history_file_path",
+        conflicts_with = "avoid_visual_confusables",
+        conflicts_with = "canonicalize",
+        conflicts_with = "qwerty_flow",
+        conflicts_with = "require_from_file_path"
+    )]
+    pow2: bool,
+
+    /// Number of sides on the dice used with --show-rolls, --dice-input, --worksheet and
+    /// --dice-compatible, like 10 or 20 for lists sized to those dice.
+    #[clap(long = "dice-base", default_value = "6")]
+    dice_base: usize,
+
+    /// Rather than print passphrases, generate --passphrases of them (e.g. -n 100000) and print a
+    /// histogram of how many characters long they are. Useful for studying a list or settings.
+    /// The passphrases themselves are never printed or saved anywhere.
+    #[clap(
+        long = "entropy-histogram",
+        conflicts_with = "history_file_path",
+        conflicts_with = "record_indices_path",
+        conflicts_with = "encode_indices",
+        conflicts_with = "hint",
+        conflicts_with = "output_format"
+    )]
+    entropy_histogram: bool,
+
+    /// Make passphrases that follow a grammar, like "adj noun verb noun", with each word picked
+    /// from the words with that part of speech. The custom list must be a CSV file with a word
+    /// and its part of speech on each line (like `happy,adj`). The number of words is set by the
+    /// grammar. Since each word is picked from only some of the list, passphrases have less
+    /// entropy than the same number of words picked from the whole list; use --verbose to check.
+    #[clap(
+        long = "grammar",
+        requires = "custom_list_file_path",
+        conflicts_with = "number_of_words",
+        conflicts_with = "minimum_entropy",
+        conflicts_with = "strength_count",
+        conflicts_with = "weighted",
+        conflicts_with = "qwerty_flow",
+        conflicts_with = "require_from_file_path",
+        conflicts_with = "history_file_path"
+    )]
+    grammar: Option<String>,
+
+    /// Replace this many randomly chosen letters of each passphrase with random digits, for
+    /// sites that want a digit in the middle of a password. This throws away the replaced
+    /// letters, so it adds less entropy than it might seem (and with short passphrases, can
+    /// even take some away); use --verbose to check.
+    #[clap(
+        long = "inject-digit",
+        conflicts_with = "replay_indices_path",
+        conflicts_with = "encoded_indices",
+        conflicts_with = "dice_input"
+    )]
+    inject_digit: Option<usize>,
+
+    /// Put this word of your own, like the name of your hometown, into each passphrase at a
+    /// random position, to make it more personal. The word itself adds no entropy, since you
+    /// know it (and so might people who know you); only its position is counted.
+    #[clap(
+        long = "inject-word",
+        value_name = "WORD",
+        conflicts_with = "distinct_first_words",
+        conflicts_with = "max_word_frequency",
+        conflicts_with = "history_file_path",
+        conflicts_with = "record_indices_path",
+        conflicts_with = "encode_indices",
+        conflicts_with = "show_rolls",
+        conflicts_with = "word_bank_path",
+        conflicts_with = "show_definitions",
+        conflicts_with = "replay_indices_path",
+        conflicts_with = "encoded_indices",
+        conflicts_with = "dice_input"
+    )]
+    inject_word: Option<String>,
+
+    /// Fill each word position with two words from the list, picked independently and joined
+    /// together, like "mapleotter". Each of these slots is worth twice as many bits as a word, so
+    /// passphrases need half as many of them. --words then counts slots, not words.
+    #[clap(
+        long = "compound",
+        conflicts_with = "grammar",
+        conflicts_with = "per_position",
+        conflicts_with = "require_from_file_path",
+        conflicts_with = "inject_word",
+        conflicts_with = "qwerty_flow",
+        conflicts_with = "pad_words",
+        conflicts_with = "heatmap",
+        conflicts_with = "reveal_delay",
+        conflicts_with = "hint",
+        conflicts_with = "could_be_generated",
+        conflicts_with = "record_indices_path",
+        conflicts_with = "encode_indices",
+        conflicts_with = "show_rolls",
+        conflicts_with = "word_bank_path",
+        conflicts_with = "from_word_bank_path",
+        conflicts_with = "replay_indices_path",
+        conflicts_with = "encoded_indices",
+        conflicts_with = "dice_input"
+    )]
+    compound: bool,
+
+    /// What to put between the two words of each --compound slot, like an apostrophe. By
+    /// default they're joined with nothing, and Phraze warns if that lets two different pairs of
+    /// words make the same compound.
+    #[clap(
+        long = "compound-joiner",
+        value_name = "JOINER",
+        default_value = "",
+        requires = "compound"
+    )]
+    compound_joiner: String,
+
+    /// Rather than generate a passphrase, print how many words a list needs for --words words to
+    /// reach --minimum-entropy bits (e.g. `--min-list-size --minimum-entropy 128 --words 6`).
+    /// Handy for sizing a list of your own.
+    #[clap(
+        long = "min-list-size",
+        requires = "minimum_entropy",
+        requires = "number_of_words"
+    )]
+    min_list_size: bool,
+
+    /// Throw out and re-generate any passphrase with more than this many identical characters
+    /// in a row (like "eee"), for password policies that forbid them. Counts the whole
+    /// passphrase, including separators and runs that cross from one word to the next. This
+    /// rules out some passphrases, which very slightly reduces entropy.
+    #[clap(long = "max-repeated-chars")]
+    max_repeated_chars: Option<usize>,
+
+    /// Throw out and re-generate any passphrase with more than this many words of the same
+    /// length in a row (like "oak-elm-ash"), which can look monotonous. Like
+    /// --max-repeated-chars, this rules out some passphrases, which very slightly reduces
+    /// entropy.
+    #[clap(long = "max-same-length-run")]
+    max_same_length_run: Option<usize>,
+
+    /// Throw out and re-generate any passphrase whose words have fewer than this many letters
+    /// in total, not counting separators (or digits and punctuation), for systems that strip
+    /// separators out before checking a passphrase's length. Like --max-repeated-chars, this
+    /// rules out some passphrases, which very slightly reduces entropy.
+    #[clap(
+        long = "min-word-chars",
+        value_name = "N",
+        conflicts_with = "abbreviate"
+    )]
+    min_word_chars: Option<usize>,
+
+    /// Throw out and re-generate any passphrase whose words have fewer vowels than this share
+    /// of their letters, like 0.4 for at least 40% vowels, since passphrases heavy with
+    /// consonants ("strengths-rhythm") are hard to say out loud. Vowels are a, e, i, o and u.
+    /// Like --max-repeated-chars, this rules out some passphrases, which reduces entropy, and
+    /// by more than most restrictions: see the readme.
+    #[clap(
+        long = "min-vowel-ratio",
+        value_name = "RATIO",
+        conflicts_with = "abbreviate"
+    )]
+    min_vowel_ratio: Option<f64>,
+
+    /// Print which random number generator Phraze is using to standard error: the default,
+    /// cryptographically secure thread_rng, or the deterministic RNG used with --seed (which
+    /// is reported without revealing the seed).
+    #[clap(long = "rng-info")]
+    rng_info: bool,
+
+    /// Print a rough memorability score (0 to 100, higher is easier to remember) after each
+    /// passphrase, separated from it by a tab. The score looks at how long and how common the
+    /// words are, how many syllables they have, and whether neighboring words start with the
+    /// same letter. It never affects which words are picked.
+    #[clap(
+        long = "score",
+        conflicts_with = "encode_indices",
+        conflicts_with = "output_format"
+    )]
+    score: bool,
+
+    /// Print the passphrases in order of their memorability score (see --score), most
+    /// memorable first. Only the order changes, so this doesn't affect entropy, but keep in
+    /// mind that always picking the top passphrase would.
+    #[clap(long = "sort-by-score", conflicts_with = "encode_indices")]
+    sort_by_score: bool,
+
+    /// Print (to standard error) a phraze command with every option given for this run,
+    /// quoted for the shell, so the same settings can be saved in a script. Options left at
+    /// their defaults are left out.
+    #[clap(long = "print-command")]
+    print_command: bool,
+
+    /// Throw out and re-generate any passphrase that contains one of a small, built-in list of
+    /// very common passwords and sequences, like "password" or "1234". Mostly useful with no
+    /// separator, where words can run together to spell one (like "compass" and "words").
+    #[clap(long = "no-common-substrings")]
+    no_common_substrings: bool,
+
+    /// When generating more than one passphrase, make sure no two of them start with the same
+    /// word, so they're easy to tell apart at a glance. Passphrases are re-generated (a limited
+    /// number of times) until their first word is new. The list needs at least as many words
+    /// that can start a passphrase as --passphrases, and close to that many may still run out
+    /// of tries. Each passphrase's first word can't be one that's already been used, so later
+    /// passphrases in the batch have very slightly less entropy.
+    #[clap(long = "distinct-first-words", conflicts_with = "entropy_histogram")]
+    distinct_first_words: bool,
+
+    /// When generating more than one passphrase, keep any one word from showing up in more than
+    /// this fraction of them (between 0 and 1), since a word in, say, half of a batch looks odd,
+    /// even though it's just chance. Passphrases with a word that's already used up its share are
+    /// re-generated, a limited number of times, so a threshold that's too strict for the list and
+    /// batch size is an error rather than running forever. Every word can always be in at least
+    /// one passphrase. This only rules out passphrases based on what's already in the batch, so
+    /// each passphrase is still picked at random from the ones that are left: like
+    /// --distinct-first-words, later passphrases have very slightly less entropy.
+    #[clap(long = "max-word-frequency", conflicts_with = "entropy_histogram")]
+    max_word_frequency: Option<f64>,
+
+    /// Along with the passphrases, print (to standard error) a random 160-bit secret in base32,
+    /// labeled "TOTP secret", for setting up two-factor authentication in an authenticator app
+    /// at the same time as a new password. This is only the shared secret: Phraze doesn't
+    /// compute TOTP codes. The secret always comes from the secure random number generator,
+    /// so it can't be used with --seed.
+    #[clap(long = "with-totp-secret", conflicts_with = "seed")]
+    with_totp_secret: bool,
+
+    /// After each passphrase, print its estimated entropy as a comment, like
+    /// "  # 91.0 bits". The estimate is worked out for each passphrase on its own: with a
+    /// weighted list, a passphrase of rarer words is harder to guess, so it's worth more bits.
+    #[clap(
+        long = "annotate-entropy",
+        conflicts_with = "output_format",
+        conflicts_with = "encode_indices"
+    )]
+    annotate_entropy: bool,
+
+    /// What to start the --annotate-entropy comment with
+    #[clap(
+        long = "annotate-prefix",
+        requires = "annotate_entropy",
+        default_value = "#"
+    )]
+    annotate_prefix: String,
+
+    /// Pad each word with spaces to the length of the longest word in its passphrase, so words
+    /// line up in columns when printing many passphrases. Only the printed passphrase is
+    /// padded: the spaces are NOT part of the passphrase, so don't type them in.
+    #[clap(
+        long = "pad-words",
+        conflicts_with = "output_format",
+        conflicts_with = "encode_indices"
+    )]
+    pad_words: bool,
+
+    /// Color each word by how much of the passphrase's length it makes up, from blue (shorter
+    /// than the passphrase's average word) to red (much longer), to show where a passphrase's
+    /// length comes from. Every word adds the same entropy, whatever its length. Only works
+    /// when printing to a terminal that shows color.
+    #[clap(
+        long = "heatmap",
+        conflicts_with = "output_format",
+        conflicts_with = "encode_indices",
+        conflicts_with = "pad_words",
+        conflicts_with = "abbreviate",
+        conflicts_with = "inject_digit",
+        conflicts_with = "output_path",
+        conflicts_with = "copy",
+        conflicts_with = "word_bank_path",
+        conflicts_with = "security_answers"
+    )]
+    heatmap: bool,
+
+    /// Rather than generate any passphrases, print the plan for them: which list would be used,
+    /// how long it is and what each word is worth, how many words passphrases would have, and
+    /// their entropy and expected length. Nothing secret is ever generated. Handy for checking a
+    /// custom list, or settings, before trusting them.
+    #[clap(
+        long = "stats-only",
+        conflicts_with = "replay_indices_path",
+        conflicts_with = "encoded_indices",
+        conflicts_with = "dice_input",
+        conflicts_with = "entropy_histogram"
+    )]
+    stats_only: bool,
+
+    /// Print each word of each passphrase along with its definition (like its translation, from a
+    /// bilingual list) to standard error, so the passphrase doubles as a vocabulary drill. The
+    /// custom list has to be a CSV file, with the words in --word-column and their definitions in
+    /// --definition-column. The passphrases themselves are still printed to standard output.
+    #[clap(
+        long = "show-definitions",
+        requires = "custom_list_file_path",
+        conflicts_with = "weighted",
+        conflicts_with = "grammar",
+        conflicts_with = "per_position"
+    )]
+    show_definitions: bool,
+
+    /// With --show-definitions, which column of the custom list (counting from 1) has the words.
+    #[clap(
+        long = "word-column",
+        default_value = "1",
+        requires = "show_definitions"
+    )]
+    word_column: usize,
+
+    /// With --show-definitions, which column of the custom list (counting from 1) has each
+    /// word's definition.
+    #[clap(
+        long = "definition-column",
+        default_value = "2",
+        requires = "show_definitions"
+    )]
+    definition_column: usize,
+
+    /// Fetch a password policy from this URL and follow it. The policy is JSON, like
+    /// `{"min_entropy": 100, "allowed_lists": ["l", "e", "custom"]}`: passphrases get at least
+    /// min_entropy bits, and it's an error to use a list that isn't allowed (by the name --list
+    /// takes, or "custom"). A fetched policy is reused for 5 minutes. If the policy can't be
+    /// fetched, Phraze refuses to generate anything, unless --offline-ok is given. Only plain
+    /// http:// URLs are supported. Requires the `network` feature.
+    #[clap(long = "policy-url")]
+    policy_url: Option<String>,
+
+    /// If the policy from --policy-url can't be fetched, warn and carry on without it, rather
+    /// than refusing to generate passphrases.
+    #[clap(long = "offline-ok", requires = "policy_url")]
+    offline_ok: bool,
+}
+
+/// Things Phraze can do besides generating passphrases.
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Time how fast Phraze generates passphrases on this computer, with the given settings, and
+    /// print how many it makes per second. Handy for sizing a bulk-generation job, or comparing
+    /// lists and options. The passphrases are thrown away without being printed.
+    Benchmark(BenchmarkArgs),
+
+    /// Clean up a custom word list before sharing it, the same way Phraze cleans a list when it
+    /// reads one in (trimming whitespace, dropping blank lines, sorting and removing duplicates),
+    /// plus dropping comment lines (starting with #), taking off diceware numbers and putting
+    /// words in Unicode Normalization Form C. The cleaned list reads back in exactly as it's
+    /// written. A summary of what changed is printed to standard error.
+    CleanList(CleanListArgs),
+
+    /// Check a custom word list over and print a pass/fail report: whether it's big enough, has
+    /// no duplicates (exact, or only different in Unicode normalization), uses one Unicode
+    /// normalization, is prefix-free, has no words with whitespace or separator characters in
+    /// them, and has words of reasonable lengths. Exits with an error if any required check
+    /// fails, so it works as a CI check for a curated list. By default, only prefix-free and
+    /// length-distribution are warnings.
+    AuditList(AuditListArgs),
+}
+
+/// Settings for `phraze audit-list`.
+#[derive(clap::Args, Debug)]
+struct AuditListArgs {
+    /// Word list to check
+    input: PathBuf,
+
+    /// Fewest distinct words the list needs to have
+    #[clap(long = "min-words", default_value_t = AUDIT_MIN_WORDS)]
+    min_words: usize,
+
+    /// Only warn if this check fails, rather than failing the audit. Can be given more than once.
+    /// Checks: min-size, duplicates, normalized-duplicates, uniform-normalization, prefix-free,
+    /// separators, length-distribution
+    #[clap(long = "warn", value_name = "CHECK", value_parser=parse_audit_check)]
+    warn: Vec<AuditCheck>,
+
+    /// Fail the audit if this check fails, even if it would only warn by default (like
+    /// prefix-free, for a list meant to be used without separators). Can be given more than once.
+    #[clap(long = "require", value_name = "CHECK", value_parser=parse_audit_check)]
+    require: Vec<AuditCheck>,
+}
+
+/// Settings for `phraze clean-list`.
+#[derive(clap::Args, Debug)]
+struct CleanListArgs {
+    /// Word list to clean up
+    input: PathBuf,
+
+    /// File to write the cleaned list to. Without this, it's printed to standard output.
+    #[clap(short = 'o', long = "output")]
+    output: Option<PathBuf>,
+
+    /// Also remove words that only differ from another word in case (like "Paris" and "paris"),
+    /// keeping the first in sorted order
+    #[clap(long = "case-fold")]
+    case_fold: bool,
+
+    /// Fewest words the cleaned list can have. If it has fewer, it isn't written, and Phraze
+    /// exits with an error.
+    #[clap(long = "min-words", default_value_t = MIN_USABLE_LIST_LENGTH)]
+    min_words: usize,
+}
+
+/// Settings for `phraze benchmark`.
+#[derive(clap::Args, Debug)]
+struct BenchmarkArgs {
+    /// Built-in list to generate from (same choices as phraze --list)
+    #[clap(short = 'l', long = "list", value_parser=parse_list_choice, default_value="m")]
+    list_choice: ListChoice,
+
+    /// Generate from a custom list instead
+    #[clap(short = 'c', long = "custom-list", conflicts_with = "list_choice")]
+    custom_list_file_path: Option<PathBuf>,
+
+    /// Number of words in each passphrase
+    #[clap(short = 'w', long = "words", default_value = "7")]
+    number_of_words: usize,
+
+    /// Word separator, including the special values phraze --sep takes
+    #[clap(short = 's', long = "sep", default_value = "-")]
+    separator: String,
+
+    /// Use Title Case
+    #[clap(short = 't', long = "title-case")]
+    title_case: bool,
+
+    /// How many passphrases to generate
+    #[clap(long = "iterations", default_value = "1000000")]
+    iterations: usize,
+}
+
+impl Args {
+    /// The built-in list to use. Without --per-position, there's only ever one.
+    fn list_choice(&self) -> ListChoice {
+        self.list_choices[0]
+    }
+
+    /// The custom list to use, if any. Without --per-position, there's at most one.
+    fn custom_list_file_path(&self) -> Option<&PathBuf> {
+        self.custom_list_file_paths.first()
+    }
+}
+
+/// Extra information about a generated passphrase that's printed along with it, for options
+/// like --score.
+struct PassphraseDetails {
+    /// Memorability score, from `memorability_score`
+    score: f64,
+    /// Estimated entropy, with --annotate-entropy
+    entropy: Option<f64>,
+    /// How many characters each word takes up in the passphrase, with --pad-words or --heatmap
+    word_lengths: Vec<usize>,
+}
+
+/// Words used by the passphrases generated so far in a batch, for options that look at the whole
+/// batch.
+struct BatchWords<'a> {
+    /// First words used so far, with --distinct-first-words
+    first_words: HashSet<&'a str>,
+    /// How many passphrases each word has been in so far, with --max-word-frequency
+    passphrases_with_word: HashMap<&'a str, usize>,
+    /// Most passphrases any one word can be in, with --max-word-frequency
+    max_passphrases_with_word: Option<usize>,
+}
+
+impl<'a> BatchWords<'a> {
+    /// Check a new passphrase's words against the batch so far, returning the option for the
+    /// first check it fails, if any.
+    fn first_failed_check(&self, words: &[&'a str]) -> Option<&'static str> {
+        if let Some(first_word) = words.first() {
+            if self.first_words.contains(first_word) {
+                return Some("--distinct-first-words");
+            }
+        }
+        if let Some(max_passphrases_with_word) = self.max_passphrases_with_word {
+            let word_used_up = words.iter().any(|word| {
+                self.passphrases_with_word.get(word).copied().unwrap_or(0)
+                    >= max_passphrases_with_word
+            });
+            if word_used_up {
+                return Some("--max-word-frequency");
+            }
+        }
+        None
+    }
+
+    /// Add a passphrase's words to the batch.
+    fn record(&mut self, words: &[&'a str], opt: &Args) {
+        if opt.distinct_first_words {
+            if let Some(first_word) = words.first() {
+                self.first_words.insert(*first_word);
+            }
+        }
+        if self.max_passphrases_with_word.is_some() {
+            // A word used twice in one passphrase is still only in one passphrase
+            for word in words.iter().collect::<HashSet<_>>() {
+                *self.passphrases_with_word.entry(*word).or_insert(0) += 1;
+            }
+        }
+    }
+}
+
+/// Everything that decides which words (and separators) can go into a passphrase, worked out
+/// once before any passphrases are generated.
+struct WordSources<'a> {
+    list: &'a [&'a str],
+    separator_pool: Option<&'a [char]>,
+    weighted_distribution: Option<WeightedIndex<f64>>,
+    required_list: Option<&'a [String]>,
+    grammar_slots: Option<Vec<Vec<&'a str>>>,
+    /// How many characters to cut words down to, with --abbreviate
+    abbreviation_length: Option<usize>,
+    /// The user's own word to put in each passphrase, with --inject-word
+    injected_word: Option<&'a str>,
+    /// How many passphrases have been thrown out so far for not passing the user's
+    /// restrictions, for --verbose
+    rejected: Cell<usize>,
+}
+
+fn main() -> Result<(), String> {
+    let matches = Args::command().get_matches();
+    let mut opt = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    if let Some(lang) = opt.lang {
+        set_language(lang);
+    }
+    match opt.command {
+        Some(Command::Benchmark(ref benchmark_args)) => return run_benchmark(benchmark_args),
+        Some(Command::CleanList(ref clean_list_args)) => return run_clean_list(clean_list_args),
+        Some(Command::AuditList(ref audit_list_args)) => return run_audit_list(audit_list_args),
+        None => {}
+    }
+    if opt.print_command {
+        eprintln!("{}", reproducing_command(&matches));
+    }
+    if opt.entropy_to_stdout {
+        opt.entropy_to = OutputStream::Stdout;
+    }
+    if let Some(security_answers) = opt.security_answers {
+        apply_security_answer_defaults(security_answers, &mut opt, &matches);
+    }
+    // Each label gets a passphrase of its own
+    if !opt.labels.is_empty() {
+        check_labels(&opt.labels, opt.output_format)?;
+        opt.n_passphrases = opt.labels.len();
+    }
+
+    // If user just wants to know how long a list needs to be, tell them and stop
+    if opt.min_list_size {
+        // Clap makes sure both of these are given
+        let (minimum_entropy, number_of_words) =
+            (opt.minimum_entropy.unwrap(), opt.number_of_words.unwrap());
+        println!(
+            "To get at least {} bits of entropy with {} words, a list needs at least {} words",
+            minimum_entropy,
+            number_of_words,
+            minimum_list_size(minimum_entropy, number_of_words)?
+        );
+        return Ok(());
+    }
+    let sinks = chosen_sinks(&opt);
+    check_options(&opt, &matches, &sinks)?;
+
+    // Revealing passphrases word by word is only for someone reading them on a screen
+    let revealing = opt.reveal_delay.is_some() && stdout().is_terminal();
+    // Either way, passphrases are held back until they've all been generated, rather than
+    // printed as they're made
+    let print_as_generated = sinks.is_empty() && opt.clear_after.is_none();
+
+    // If the user gave us a custom list, read it in
+    let LoadedLists {
+        custom_list,
+        mut weights,
+        part_of_speech_list,
+        definitions_list,
+        metadata: list_metadata,
+        other_custom_lists,
+    } = read_in_lists(&mut opt, &matches)?;
+    let definitions: HashMap<&str, &str> = definitions_list
+        .iter()
+        .flatten()
+        .map(|(word, definition)| (word.as_str(), definition.as_str()))
+        .collect();
+
+    // If there's a central password policy, get it now, so we don't generate anything that
+    // doesn't follow it
+    let policy = match opt.policy_url {
+        Some(ref policy_url) => fetch_policy(policy_url, opt.offline_ok)?,
+        None => None,
+    };
+    if let Some(ref policy) = policy {
+        check_lists_allowed(policy, &opt, &matches, custom_list.is_some())?;
+    }
+
+    // Check for a rare but potentially dangerous combination of settings
+    if custom_list.is_some() && opt.separator.is_empty() && !opt.title_case {
+        return Err(text(Message::SeparatorRequired, &[]));
+    }
+    // And --canonicalize lowercases everything, so Title Case can't show where words begin
+    if opt.canonicalize && opt.separator.is_empty() && opt.title_case {
+        return Err("--canonicalize lowercases passphrases, so Title Case can't mark where words begin. Use a separator instead.".to_string());
+    }
+
+    // Whether we're using the custom list or a built-in list (a default list if the user didn't
+    // choose one), we work with a Vec of &strs from here on, so we can remove words if needed.
+    let mut list: Vec<&str> = match custom_list {
+        Some(ref custom_list) => custom_list.iter().map(|word| word.as_str()).collect(),
+        None => fetch_list(opt.list_choice()).to_vec(),
+    };
+    narrow_list(&mut list, &mut weights, &opt)?;
+    // With --per-position, each position has its own list. Everything that looks at the list as
+    // a whole (like which letters it uses) looks at every word from every list.
+    let position_sources = if opt.per_position {
+        let mut custom_lists: Vec<&[String]> =
+            custom_list.iter().map(|list| list.as_slice()).collect();
+        custom_lists.extend(other_custom_lists.iter().map(|list| list.as_slice()));
+        let position_sources = checked_per_position_sources(&matches, &opt, &custom_lists)?;
+        list = position_sources
+            .iter()
+            .flat_map(|(_, words)| words.iter().copied())
+            .collect();
+        list.sort_unstable();
+        list.dedup();
+        Some(position_sources)
+    } else {
+        None
+    };
+
+    // Title Case doesn't do anything for scripts without letter case (like Japanese), so let
+    // the user know. And if they're counting on it to show where words begin, that's an error.
+    if opt.title_case && !title_case_changes_any_word(&list) {
+        if opt.separator.is_empty() && !any_word_starts_with_cased_letter(&list) {
+            return Err(text(Message::TitleCaseCantMarkWords, &[]));
+        }
+        eprintln!("{}", text(Message::TitleCaseHasNoEffect, &[]));
+    }
+
+    // If user gave a separator pool, work out which characters are in it
+    let separator_pool = match opt.separator_pool {
+        Some(ref separator_pool) => {
+            Some(parse_separator_pool(separator_pool, list.iter().copied())?)
+        }
+        None => None,
+    };
+
+    // If user gave a grammar, find the words that can go in each of its slots
+    let grammar_slots = match (&opt.grammar, &part_of_speech_list) {
+        (Some(grammar), Some(part_of_speech_list)) => {
+            Some(parse_grammar(grammar, part_of_speech_list)?)
+        }
+        _ => None,
+    };
+
+    // Some options use the list for something other than generating passphrases, then stop
+    if run_list_only_modes(&list, separator_pool.as_deref(), &opt, &sinks)? {
+        return Ok(());
+    }
+
+    // Indices refer to positions in the whole list, before any words are taken out of it, so
+    // hold on to a copy of the whole list if we're going to record or encode indices
+    let full_list =
+        if opt.record_indices_path.is_some() || opt.encode_indices.is_some() || opt.show_rolls {
+            list.clone()
+        } else {
+            vec![]
+        };
+    let mut recorded_indices = opt.record_indices_path.as_ref().map(|_| vec![]);
+    // Check the list works with the user's dice before generating anything
+    let rolls_per_word = match opt.show_rolls {
+        true => Some(rolls_per_word(full_list.len(), opt.dice_base)?),
+        false => None,
+    };
+
+    // If user gave a history file, take any words that have been used before out of the list
+    let mut history = match &opt.history_file_path {
+        Some(history_file_path) => Some(History::load(history_file_path)?),
+        None => None,
+    };
+    remove_unwanted_words(&mut list, &mut weights, history.as_ref(), &opt)?;
+
+    // Without a joiner, two different pairs of words could make the same compound, which would
+    // make compound slots worth less than we'd say
+    if opt.compound && opt.compound_joiner.is_empty() {
+        let collisions = compound_collisions(&list);
+        if let Some([first, second, other_first, other_second]) = collisions.first() {
+            eprintln!(
+                "{}",
+                text(
+                    Message::CompoundCollisions,
+                    &[first, second, other_first, other_second, &collisions.len()]
+                )
+            );
+        }
+    }
+
+    // Work out how short words can be cut once, up front, since it means checking the whole list
+    let abbreviation_length = match opt.abbreviate {
+        true => Some(abbreviation_length(&list)?),
+        false => None,
+    };
+
+    // If the list is weighted, set up a distribution to pick words with
+    let weighted_distribution = match weights {
+        Some(ref weights) => Some(make_weighted_distribution(weights)?),
+        None => None,
+    };
+
+    // If user wants some words from a themed list, read that list in too
+    let required_list = match &opt.require_from_file_path {
+        Some(require_from_file_path) => {
+            let required_list = read_in_custom_list(require_from_file_path)?;
+            if required_list.is_empty() {
+                return Err("List given to --require-from has no words in it".to_string());
+            }
+            Some(required_list)
+        }
+        None => None,
+    };
+    let plan = plan_word_count(
+        &opt,
+        &list,
+        &weights,
+        policy.as_ref(),
+        required_list.as_deref(),
+        grammar_slots.as_deref(),
+        position_sources.as_deref(),
+    )?;
+    let grammar_slots = match position_sources {
+        Some(ref position_sources) => {
+            let lists: Vec<Vec<&str>> = position_sources
+                .iter()
+                .map(|(_, words)| words.clone())
+                .collect();
+            Some(cycle_slots(&lists, plan.number_of_words))
+        }
+        None => grammar_slots,
+    };
+
+    check_restrictions(
+        &opt,
+        &list,
+        plan.number_of_words,
+        grammar_slots.as_deref(),
+        required_list.as_deref(),
+    )?;
+    let injected_digit_entropy = plan_injected_digits(&opt, &list, plan, required_list.as_deref())?;
+
+    let word_sources = WordSources {
+        list: &list,
+        separator_pool: separator_pool.as_deref(),
+        weighted_distribution,
+        required_list: required_list.as_deref(),
+        grammar_slots,
+        abbreviation_length,
+        injected_word: opt.inject_word.as_deref(),
+        rejected: Cell::new(0),
+    };
+
+    print_targets(&opt, plan, abbreviation_length);
+    let entropy_estimates =
+        estimate_passphrase_entropy(&word_sources, &weights, plan, injected_digit_entropy, &opt);
+    let passphrase_entropy = entropy_estimates.map(|(_, passphrase_entropy)| passphrase_entropy);
+    // Words given with --words (or a grammar) might not reach the policy's minimum
+    if let (Some(policy_minimum_entropy), Some(passphrase_entropy)) =
+        (plan.policy_minimum_entropy, passphrase_entropy)
+    {
+        if passphrase_entropy < policy_minimum_entropy as f64 {
+            return Err(format!(
+                "Passphrases would have {:.2} bits of entropy, but the password policy requires at least {}",
+                passphrase_entropy, policy_minimum_entropy
+            ));
+        }
+    }
+    report_entropy(
+        entropy_estimates,
+        &word_sources,
+        &weights,
+        plan,
+        position_sources.as_deref(),
+        &opt,
+    );
+
+    // If user only wants the plan, print it and stop, before generating anything
+    if let (true, Some(passphrase_entropy)) = (opt.stats_only, passphrase_entropy) {
+        return print_plan(
+            &word_sources,
+            plan,
+            position_sources.as_deref(),
+            &list_metadata,
+            passphrase_entropy,
+            &opt,
+        );
+    }
+
+    // If user only wants a histogram, generate the passphrases, but only keep their lengths
+    if opt.entropy_histogram {
+        return print_length_histogram(&word_sources, plan, &opt);
+    }
+
+    // Set up one RNG to use for all of the passphrases we're about to generate, unless
+    // they're seeded, or come from a hardware token's response
+    let rng_source = choose_rng_source(&opt)?;
+    let mut rng = thread_rng();
+
+    // Let's see if the user wants passphrases forever
+    if opt.stream {
+        return stream_passphrases(&mut rng, plan.number_of_words, &word_sources, &opt);
+    }
+
+    // Keep the passphrases in one buffer until they've been printed (and, with the mlock
+    // feature, keep that buffer out of swap). Passphrases for formats other than plain are
+    // printed all at once at the end.
+    let mut batch = PassphraseBatch::with_capacity(
+        opt.n_passphrases * longest_possible_passphrase_for_plan(&word_sources, plan, &opt),
+    );
+    if cfg!(feature = "mlock") && !batch.is_locked() {
+        eprintln!("{}", text(Message::MemoryNotLocked, &[]));
+    }
+
+    // With --verbose, we report how much effort each passphrase takes to type, on the layout
+    // the user's list was made for
+    let typing_layout = match (opt.list_choice(), &opt.custom_list_file_path()) {
+        (ListChoice::Alpha, None) => KeyboardLayout::Alphabetical,
+        _ => KeyboardLayout::Qwerty,
+    };
+    let mut total_typing_effort = 0.0;
+    // Details to print with each passphrase, in the same order as the passphrases in the batch
+    let mut details = vec![];
+    // With --annotate-entropy, words from a weighted list are each worth their own number of
+    // bits, so work those out up front, to swap in for the list's average.
+    let word_bits = match (&weights, &required_list, opt.annotate_entropy) {
+        (Some(weights), None, true) => Some((
+            word_self_information(&list, weights),
+            shannon_entropy_per_word(weights),
+        )),
+        _ => None,
+    };
+    // Words used so far, for options that look at the whole batch
+    let mut batch_words = BatchWords {
+        first_words: HashSet::new(),
+        passphrases_with_word: HashMap::new(),
+        max_passphrases_with_word: opt
+            .max_word_frequency
+            .map(|frequency| ((frequency * opt.n_passphrases as f64) as usize).max(1)),
+    };
+
+    // With --word-bank, every passphrase's words, to make the bank from
+    let mut bank_passphrase_words = opt.word_bank_path.as_ref().map(|_| vec![]);
+
+    // Now we can (finally) generate and print some number of passphrases
+    for i in 0..opt.n_passphrases {
+        let (words, passphrase) = make_nth_passphrase_for_batch(
+            i,
+            &rng_source,
+            &mut rng,
+            plan.number_of_words,
+            &word_sources,
+            &opt,
+            &batch_words,
+        )?;
+        batch_words.record(&words, &opt);
+        let passphrase = batch.push(passphrase)?;
+        if opt.verbose {
+            let effort = typing_effort(passphrase, typing_layout);
+            eprintln!("Typing effort: {:.1} key widths", effort);
+            total_typing_effort += effort;
+        }
+        print_passphrase_notes(&words, &definitions, rolls_per_word, &full_list, &opt)?;
+        // If we're keeping a history of used words, record the words we picked
+        if let Some(ref mut history) = history {
+            for word in &words {
+                history.record(word);
+            }
+        }
+        if let Some(ref mut recorded_indices) = recorded_indices {
+            recorded_indices.push(word_indices(&words, &full_list)?);
+        }
+        let passphrase_details = PassphraseDetails {
+            score: memorability_score(&words),
+            entropy: annotated_entropy(&words, passphrase_entropy, word_bits.as_ref(), &opt),
+            word_lengths: match opt.pad_words || opt.heatmap || revealing {
+                true => displayed_word_lengths(&words, &opt),
+                false => vec![],
+            },
+        };
+        if let Some(encoding) = opt.encode_indices {
+            let indices = word_indices(&words, &full_list)?;
+            let encoded = encode_indices(&indices, full_list.len(), encoding)?;
+            if let Err(e) = writeln!(stdout(), "{}\t{}", passphrase, encoded) {
+                return print_error(e);
+            }
+        } else if let Some(ref mut bank_passphrase_words) = bank_passphrase_words {
+            // These get printed as word bank selections once the bank is made
+            bank_passphrase_words.push(words.clone());
+        } else if opt.security_answers.is_some() {
+            if let Err(e) = writeln!(stdout(), "{}. {}", i + 1, passphrase) {
+                return print_error(e);
+            }
+        } else if opt.output_format == OutputFormat::Plain
+            && !opt.sort_by_score
+            && print_as_generated
+        {
+            if let (true, Some(delay)) = (revealing, opt.reveal_delay) {
+                let pieces = passphrase_pieces(
+                    passphrase,
+                    &passphrase_details.word_lengths,
+                    separator_length(&opt),
+                );
+                reveal_words(&pieces, Duration::from_millis(delay), opt.reveal_clear)?;
+            } else if let Err(e) = print_passphrase(passphrase, &passphrase_details, &opt) {
+                return print_error(e);
+            }
+        }
+        details.push(passphrase_details);
+    }
+    if opt.verbose && opt.n_passphrases > 1 {
+        eprintln!(
+            "Average typing effort: {:.1} key widths",
+            total_typing_effort / opt.n_passphrases as f64
+        );
+    }
+    if opt.verbose && word_sources.rejected.get() > 0 {
+        opt.entropy_to.print_line(&format!(
+            "Threw out and re-generated {} passphrase{} that didn't pass your restrictions",
+            word_sources.rejected.get(),
+            if word_sources.rejected.get() == 1 {
+                ""
+            } else {
+                "s"
+            }
+        ));
+    }
+    // If sorting by score, we've held off printing until now
+    let mut passphrases: Vec<(&str, PassphraseDetails)> = batch.iter().zip(details).collect();
+    if opt.sort_by_score {
+        passphrases.sort_by(|(_, a), (_, b)| b.score.total_cmp(&a.score));
+        if opt.output_format == OutputFormat::Plain && print_as_generated {
+            for (passphrase, passphrase_details) in &passphrases {
+                if let Err(e) = print_passphrase(passphrase, passphrase_details, &opt) {
+                    return print_error(e);
+                }
+            }
+        }
+    }
+    if opt.output_format != OutputFormat::Plain && print_as_generated {
+        let passphrases: Vec<&str> = passphrases
+            .iter()
+            .map(|(passphrase, _)| *passphrase)
+            .collect();
+        let formatted = format_passphrases(&passphrases, &opt.labels, opt.output_format)?;
+        if let Err(e) = writeln!(stdout(), "{}", formatted) {
+            return print_error(e);
+        }
+    }
+    // Or hold them back instead
+    let held_back = match print_as_generated {
+        true => None,
+        false => Some(hold_back(&passphrases, &opt)?),
+    };
+    let held_back_text = held_back
+        .as_ref()
+        .and_then(|held_back| held_back.iter().next());
+    // Even if sending them fails, carry on with saving history and so on, since some of the
+    // sinks may have the passphrases now
+    let sent = match held_back_text {
+        Some(text) if !sinks.is_empty() => send_to_sinks(text, &sinks),
+        _ => Ok(()),
+    };
+
+    if let (Some(bank_passphrase_words), Some(word_bank_path)) =
+        (bank_passphrase_words, &opt.word_bank_path)
+    {
+        save_word_bank(
+            &mut rng,
+            &bank_passphrase_words,
+            &list,
+            word_bank_path,
+            plan.number_of_words,
+            &opt,
+        )?;
+    }
+
+    if opt.with_totp_secret {
+        eprintln!("TOTP secret: {}", generate_totp_secret(&mut rng));
+    }
+
+    if let (Some(recorded_indices), Some(record_indices_path)) =
+        (recorded_indices, &opt.record_indices_path)
+    {
+        write_index_file(
+            record_indices_path,
+            &list_fingerprint(&full_list),
+            &recorded_indices,
+        )?;
+    }
+
+    // Remember which words we used, for next time
+    if let (Some(history), Some(history_file_path)) = (history, &opt.history_file_path) {
+        history.save(history_file_path)?;
+    }
+
+    // Everything's saved, so now the passphrases can be shown, then cleared
+    if let (Some(seconds), Some(text)) = (opt.clear_after, held_back_text) {
+        if show_then_clear(text, seconds)? == ClearReason::Interrupted {
+            // Exiting skips destructors, so wipe (and unlock) the passphrases ourselves first
+            drop(held_back);
+            drop(batch);
+            // The same exit code as being stopped by Ctrl-C any other time
+            std::process::exit(130);
+        }
+    }
+
+    sent
+}
+
+/// Print any notes the user asked for about a passphrase's words, like a hint, their
+/// definitions, or the dice rolls that pick them.
+fn print_passphrase_notes(
+    words: &[&str],
+    definitions: &HashMap<&str, &str>,
+    rolls_per_word: Option<usize>,
+    full_list: &[&str],
+    opt: &Args,
+) -> Result<(), String> {
+    if opt.hint {
+        eprintln!("Hint: {}", structural_hint(words, opt.title_case));
+    }
+    if opt.abbreviate {
+        eprintln!("Full words: {}", words.join(" "));
+    }
+    if opt.show_definitions {
+        for word in words {
+            match definitions.get(word) {
+                Some(definition) => eprintln!("{}: {}", word, definition),
+                // Like a word from --require-from
+                None => eprintln!("{}: (no definition)", word),
+            }
+        }
+    }
+    if let Some(rolls_per_word) = rolls_per_word {
+        let rolls: Vec<String> = word_indices(words, full_list)?
+            .iter()
+            .map(|index| format_rolls(&index_to_rolls(*index, opt.dice_base, rolls_per_word)))
+            .collect();
+        eprintln!("Rolls: {}", rolls.join(" "));
+    }
+    Ok(())
+}
+
+/// Put the passphrases, in the format the user asked for, into a batch of their own, so they can
+/// be held back, to send wherever the user asked, or to show for a while with --clear-after. The
+/// text is wiped once we're done with it, just like the passphrases themselves.
+fn hold_back(
+    passphrases: &[(&str, PassphraseDetails)],
+    opt: &Args,
+) -> Result<PassphraseBatch, String> {
+    let text = match opt.output_format {
+        OutputFormat::Plain => passphrases
+            .iter()
+            .map(|(passphrase, passphrase_details)| {
+                passphrase_line(passphrase, passphrase_details, opt) + "\n"
+            })
+            .collect::<String>(),
+        _ => {
+            let passphrases: Vec<&str> = passphrases
+                .iter()
+                .map(|(passphrase, _)| *passphrase)
+                .collect();
+            format_passphrases(&passphrases, &opt.labels, opt.output_format)? + "\n"
+        }
+    };
+    let mut held_back = PassphraseBatch::with_capacity(text.len());
+    held_back.push(text)?;
+    Ok(held_back)
+}
+
+/// Like `per_position_sources`, but makes sure each position's list is long enough to pick from.
+fn checked_per_position_sources<'a>(
+    matches: &ArgMatches,
+    opt: &Args,
+    custom_lists: &[&'a [String]],
+) -> Result<Vec<(String, Vec<&'a str>)>, String> {
+    let position_sources = per_position_sources(matches, opt, custom_lists);
+    for (name, words) in &position_sources {
+        if words.len() < MIN_WORDS_PER_PART_OF_SPEECH {
+            return Err(format!(
+                "List {} has {} words, but each position's list needs at least {}",
+                name,
+                words.len(),
+                MIN_WORDS_PER_PART_OF_SPEECH
+            ));
+        }
+    }
+    Ok(position_sources)
+}
+
+/// With --annotate-entropy, how many bits of entropy to print next to a passphrase. That's the
+/// estimate for every passphrase, unless its words are each worth their own number of bits
+/// (`word_bits`, along with the list's average), in which case those are swapped in.
+fn annotated_entropy(
+    words: &[&str],
+    passphrase_entropy: Option<f64>,
+    word_bits: Option<&(HashMap<&str, f64>, f64)>,
+    opt: &Args,
+) -> Option<f64> {
+    match (opt.annotate_entropy, passphrase_entropy, word_bits) {
+        (true, Some(passphrase_entropy), Some((word_bits, average))) => {
+            let own: f64 = words.iter().filter_map(|word| word_bits.get(word)).sum();
+            Some(passphrase_entropy - average * words.len() as f64 + own)
+        }
+        (true, passphrase_entropy, None) => passphrase_entropy,
+        _ => None,
+    }
+}
+
+/// With --word-bank, make a bank with every passphrase's words hidden among others from the
+/// list, save it to `word_bank_path`, then print each passphrase as its selections from the bank.
+fn save_word_bank(
+    rng: &mut impl Rng,
+    bank_passphrase_words: &[Vec<&str>],
+    list: &[&str],
+    word_bank_path: &Path,
+    number_of_words: usize,
+    opt: &Args,
+) -> Result<(), String> {
+    let bank = make_word_bank(rng, bank_passphrase_words, list, opt.word_bank_size)?;
+    write_word_bank(word_bank_path, &bank)?;
+    for words in bank_passphrase_words {
+        println!("{}", bank_selections(words, &bank)?);
+    }
+    if opt.verbose {
+        opt.entropy_to.print_line(&format!(
+            "Anyone with only the word bank has to guess which of its {} words make up a passphrase, which is worth about {:.2} bits",
+            bank.len(),
+            (bank.len() as f64).log2() * number_of_words as f64
+        ));
+    }
+    Ok(())
+}
+
+/// Custom lists read in for this run, along with anything else their files had in them. We keep
+/// these around, since the list we actually generate from just borrows their words.
+struct LoadedLists {
+    /// The custom list, if the user gave us one (or a system dictionary, or a list command)
+    custom_list: Option<Vec<String>>,
+    /// A weight for each of the custom list's words, with --weighted
+    weights: Option<Vec<f64>>,
+    /// Each word and its part of speech, with --grammar
+    part_of_speech_list: Option<Vec<(String, String)>>,
+    /// Each word and its definition, with --show-definitions
+    definitions_list: Option<Vec<(String, String)>>,
+    /// A JSON or TOML list's name and source
+    metadata: ListMetadata,
+    /// With --per-position, the custom lists for other positions
+    other_custom_lists: Vec<Vec<String>>,
+}
+
+/// Read in whichever custom lists the user gave us. A text list's directives are applied to
+/// `opt` as it's read, unless the user's own options say otherwise.
+fn read_in_lists(opt: &mut Args, matches: &ArgMatches) -> Result<LoadedLists, String> {
+    // Only text lists can have columns for weights, parts of speech or definitions
+    let custom_list_format = opt
+        .custom_list_file_path()
+        .map(|custom_list_file_path| list_format_for(custom_list_file_path, opt));
+    if matches!(
+        custom_list_format,
+        Some(ListFormat::Json | ListFormat::Toml)
+    ) && (opt.weighted || opt.grammar.is_some() || opt.show_definitions)
+    {
+        return Err("JSON and TOML lists can only have words in them, so can't be used with --weighted, --grammar or --show-definitions".to_string());
+    }
+
+    // If the user gave a grammar, the list has a part of speech for each word
+    let part_of_speech_list = match (opt.custom_list_file_path(), &opt.grammar) {
+        (Some(custom_list_file_path), Some(_)) => {
+            Some(read_in_part_of_speech_list(custom_list_file_path)?)
+        }
+        _ => None,
+    };
+    // And if the user wants definitions, the list has a definition for each word
+    let definitions_list = match opt.custom_list_file_path() {
+        Some(custom_list_file_path) if opt.show_definitions => Some(read_in_definitions_list(
+            custom_list_file_path,
+            opt.word_column,
+            opt.definition_column,
+        )?),
+        _ => None,
+    };
+    // If the list is weighted, we'll also have a weight for each word
+    let mut list_metadata = ListMetadata::default();
+    let (custom_list, weights) = match opt.custom_list_file_path() {
+        Some(_) if definitions_list.is_some() => {
+            let words = definitions_list
+                .iter()
+                .flatten()
+                .map(|(word, _)| word.clone())
+                .collect();
+            (Some(words), None)
+        }
+        Some(_) if part_of_speech_list.is_some() => {
+            // A word can have more than one part of speech, but should only be in the list once
+            let mut words: Vec<String> = part_of_speech_list
+                .iter()
+                .flatten()
+                .map(|(word, _)| word.clone())
+                .collect();
+            words.dedup();
+            (Some(words), None)
+        }
+        Some(custom_list_file_path) if opt.weighted => {
+            let weighted_list = read_in_weighted_custom_list(custom_list_file_path)?;
+            let (words, weights): (Vec<String>, Vec<f64>) = weighted_list.into_iter().unzip();
+            (Some(words), Some(weights))
+        }
+        Some(custom_list_file_path) if custom_list_format != Some(ListFormat::Text) => {
+            // There's always a format when there's a custom list
+            let (words, metadata) =
+                read_in_structured_list(custom_list_file_path, custom_list_format.unwrap())?;
+            list_metadata = metadata;
+            (Some(words), None)
+        }
+        Some(custom_list_file_path) => {
+            let (words, directives) = read_in_custom_list_with_directives(custom_list_file_path)?;
+            apply_list_directives(directives, opt, matches);
+            (Some(words), None)
+        }
+        None => match (&opt.system_dict_path, &opt.list_command) {
+            (Some(system_dict_path), _) => {
+                (Some(system_dictionary_words(system_dict_path, opt)?), None)
+            }
+            (None, Some(list_command)) => (
+                Some(read_in_list_from_command(
+                    list_command,
+                    &opt.list_command_arguments,
+                    Duration::from_secs(opt.list_command_timeout),
+                )?),
+                None,
+            ),
+            (None, None) => (None, None),
+        },
+    };
+    // With --per-position, there can be more custom lists, for other positions
+    let other_custom_lists: Vec<Vec<String>> = opt
+        .custom_list_file_paths
+        .iter()
+        .skip(1)
+        .map(
+            |custom_list_file_path| match list_format_for(custom_list_file_path, opt) {
+                ListFormat::Text => read_in_custom_list(custom_list_file_path),
+                format => read_in_structured_list(custom_list_file_path, format)
+                    .map(|(words, _metadata)| words),
+            },
+        )
+        .collect::<Result<_, _>>()?;
+    Ok(LoadedLists {
+        custom_list,
+        weights,
+        part_of_speech_list,
+        definitions_list,
+        metadata: list_metadata,
+        other_custom_lists,
+    })
+}
+
+/// Check that the user's options make sense together, and that everything they need (like a
+/// terminal, for --clear-after) is there, before reading in any lists or generating anything.
+fn check_options(opt: &Args, matches: &ArgMatches, sinks: &[Sink]) -> Result<(), String> {
+    // Outside of --min-list-size, a minimum entropy and an exact number of words would
+    // contradict each other
+    if opt.minimum_entropy.is_some() && opt.number_of_words.is_some() {
+        return Err(
+            "Can't use --minimum-entropy and --words together, except with --min-list-size"
+                .to_string(),
+        );
+    }
+
+    // Lists can only be given more than once (or mixed) with --per-position
+    if !opt.per_position {
+        if opt.list_choices.len() > 1 || opt.custom_list_file_paths.len() > 1 {
+            return Err(
+                "Can only give --list or --custom-list more than once with --per-position"
+                    .to_string(),
+            );
+        }
+        if opt.custom_list_file_path().is_some()
+            && matches.value_source("list_choice") == Some(ValueSource::CommandLine)
+        {
+            return Err(
+                "Can't use --list and --custom-list together, except with --per-position"
+                    .to_string(),
+            );
+        }
+    }
+
+    // If user requires it, make sure the OS's secure random source is up before doing anything
+    if opt.require_os_rng {
+        check_os_rng()?;
+    }
+
+    // Likewise, if user doesn't want passphrases shown in the terminal, check that before
+    // doing anything. That's fine if they're going somewhere else instead.
+    if opt.store_keyring.is_some() && opt.n_passphrases > 1 {
+        return Err(
+            "--store-keyring saves a single passphrase, so it can't be used with -n more than 1"
+                .to_string(),
+        );
+    }
+    if opt
+        .inject_word
+        .as_deref()
+        .is_some_and(|word| word.trim().is_empty())
+    {
+        return Err("Word given to --inject-word is empty".to_string());
+    }
+    if opt.no_tty_print {
+        check_tty_print(stdout().is_terminal(), !sinks.is_empty())?;
+    }
+    // And --clear-after only makes sense in a terminal
+    if opt.clear_after.is_some() {
+        if !stdout().is_terminal() {
+            return Err(text(Message::ClearAfterNeedsTerminal, &[]));
+        }
+        eprintln!("{}", text(Message::ClearAfterScrollback, &[]));
+    }
+    // The heatmap is colors, so it needs a terminal that shows them
+    if opt.heatmap {
+        if !stdout().is_terminal() || std::env::var_os("NO_COLOR").is_some() {
+            return Err(text(Message::HeatmapNeedsColor, &[]));
+        }
+        let legend: Vec<String> = HEATMAP_COLORS
+            .iter()
+            .map(|(color, meaning)| format!("{:?} is {}", color, meaning).to_lowercase())
+            .collect();
+        eprintln!(
+            "Heatmap of each word's share of the passphrase's length: {}",
+            legend.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Where to send passphrases other than standard output, in the order to send them: a file and
+/// the keyring go before the clipboard, since they keep them for longer.
+fn chosen_sinks(opt: &Args) -> Vec<Sink> {
+    let mut sinks = vec![];
+    if let Some(ref output_path) = opt.output_path {
+        sinks.push(Sink::File(output_path.clone()));
+    }
+    if let Some([service, account]) = opt.store_keyring.as_deref() {
+        sinks.push(Sink::Keyring {
+            service: service.clone(),
+            account: account.clone(),
+        });
+    }
+    if opt.copy {
+        sinks.push(Sink::Clipboard);
+    }
+    sinks
+}
+
+/// Make sure --label can be used: labels only mean something in a YAML map, and a map can't
+/// have the same key twice.
+fn check_labels(labels: &[String], output_format: OutputFormat) -> Result<(), String> {
+    if output_format != OutputFormat::Yaml {
+        return Err("--label only works with --format yaml".to_string());
+    }
+    let mut seen = HashSet::new();
+    match labels.iter().find(|label| !seen.insert(*label)) {
+        Some(label) => Err(format!("--label '{}' was given more than once", label)),
+        None => Ok(()),
+    }
+}
+
+/// Security answers don't need to be as strong as passphrases, so --security-answers has its own
+/// defaults: 2 words, with a space between them. Anything the user gave on the command line
+/// still wins.
+fn apply_security_answer_defaults(security_answers: usize, opt: &mut Args, matches: &ArgMatches) {
+    opt.n_passphrases = security_answers;
+    if opt.number_of_words.is_none()
+        && opt.minimum_entropy.is_none()
+        && opt.strength_count == 0
+        && opt.level.is_none()
+    {
+        opt.number_of_words = Some(2);
+    }
+    if matches.value_source("separator") != Some(ValueSource::CommandLine)
+        && opt.separator_pool.is_none()
+    {
+        opt.separator = " ".to_string();
+    }
+}
+
+/// The format to read a custom list in: the one given with --custom-list-format, or else the one
+/// its extension says.
+fn list_format_for(custom_list_file_path: &Path, opt: &Args) -> ListFormat {
+    opt.custom_list_format
+        .unwrap_or_else(|| ListFormat::from_path(custom_list_file_path))
+}
+
+/// Use the separator and case a custom list asks for in its header, unless the user chose their
+/// own on the command line.
+fn apply_list_directives(directives: ListDirectives, opt: &mut Args, matches: &ArgMatches) {
+    let separator_given = matches.value_source("separator") == Some(ValueSource::CommandLine)
+        || opt.separator_pool.is_some()
+        || opt.min_distinct_separators.is_some();
+    if let Some(separator) = directives.separator {
+        if !separator_given {
+            opt.separator = separator;
+        }
+    }
+    if directives.title_case == Some(true) && opt.capitalize_nth.is_empty() {
+        opt.title_case = true;
+    }
+}
+
+/// Get the password policy for --policy-url. Failing to get it is an error, so we never generate
+/// passphrases that might not follow the policy, unless the user said that's OK.
+fn fetch_policy(policy_url: &str, offline_ok: bool) -> Result<Option<Policy>, String> {
+    if !cfg!(feature = "network") {
+        return Err(
+            "This version of Phraze was built without network support (the `network` feature), so it can't use --policy-url"
+                .to_string(),
+        );
+    }
+    match load_policy(policy_url) {
+        Ok(policy) => Ok(Some(policy)),
+        Err(e) if offline_ok => {
+            eprintln!("{}", text(Message::PolicyUnavailable, &[&e]));
+            Ok(None)
+        }
+        Err(e) => Err(format!(
+            "{}. Not generating any passphrases without the password policy (use --offline-ok to go ahead anyway)",
+            e
+        )),
+    }
+}
+
+/// How many words go in each passphrase, and what they're worth, worked out once before any
+/// passphrases are generated.
+#[derive(Clone, Copy, Debug)]
+struct WordCountPlan {
+    /// Words in each passphrase. With --compound, this counts slots, not words.
+    number_of_words: usize,
+    /// How many of the list's words go in each slot: 2 with --compound, otherwise 1
+    words_per_slot: usize,
+    /// What each of the list's words is worth, if that isn't log2 of the list's length
+    entropy_per_word: Option<f64>,
+    /// What each slot is worth, if that isn't log2 of the list's length
+    entropy_per_slot: Option<f64>,
+    /// The password policy's minimum entropy, if there's a policy, and it has one
+    policy_minimum_entropy: Option<usize>,
+}
+
+/// Work out how many words passphrases need, from the number of words, minimum entropy or
+/// strength the user asked for, and what words from the list (or, with --require-from, the
+/// themed list, or with --grammar and --per-position, each slot's own list) are worth.
+fn plan_word_count(
+    opt: &Args,
+    list: &[&str],
+    weights: &Option<Vec<f64>>,
+    policy: Option<&Policy>,
+    required_list: Option<&[String]>,
+    grammar_slots: Option<&[Vec<&str>]>,
+    position_sources: Option<&[(String, Vec<&str>)]>,
+) -> Result<WordCountPlan, String> {
+    let list_length = list.len();
+    // Since user can define a minimum entropy, we might have to do a little math to
+    // figure out how many words we need to include in this passphrase.
+    // A strength level is just another way of giving a minimum entropy
+    let minimum_entropy = match opt.level {
+        Some(level) => Some(level.minimum_entropy()),
+        None => opt.minimum_entropy,
+    };
+    // A policy's minimum entropy is a floor: it can raise the user's target, but never lower it
+    let policy_minimum_entropy = policy.and_then(|policy| policy.minimum_entropy);
+    let (minimum_entropy, strength_count) = match policy_minimum_entropy {
+        Some(policy_minimum_entropy) => (
+            Some(
+                target_minimum_entropy(minimum_entropy, opt.strength_count)
+                    .max(policy_minimum_entropy),
+            ),
+            0,
+        ),
+        None => (minimum_entropy, opt.strength_count),
+    };
+    // Words from a weighted list are worth less than words from an even list of the same length,
+    // so we need more of them. So are words after the first with --qwerty-flow, so count every
+    // word at what those are worth.
+    let entropy_per_word = match (weights, opt.qwerty_flow) {
+        (Some(weights), _) => Some(shannon_entropy_per_word(weights)),
+        (None, true) if list_length > 1 => Some(qwerty_flow_entropy_per_word(list)),
+        (None, _) => None,
+    };
+    if entropy_per_word == Some(0.0) && opt.number_of_words.is_none() {
+        return Err(
+            "Weighted list's words are worth 0 bits each, so no number of them can reach a minimum entropy"
+                .to_string(),
+        );
+    }
+    // With --compound, each slot (what --words counts) is two words from the list, so it's
+    // worth twice as much
+    let entropy_per_slot = match (opt.compound, entropy_per_word) {
+        (true, Some(entropy_per_word)) => Some(compound_slot_entropy(entropy_per_word)),
+        (true, None) if list_length > 1 => Some(compound_slot_entropy((list_length as f64).log2())),
+        _ => entropy_per_word,
+    };
+    let words_per_slot = match opt.compound {
+        true => WORDS_PER_COMPOUND,
+        false => 1,
+    };
+    let number_of_words_to_put_in_passphrase = try_calculate_number_words_needed(
+        opt.number_of_words,
+        minimum_entropy,
+        strength_count,
+        list_length,
+        entropy_per_slot,
+    )?;
+
+    // Themed words are usually worth a different number of bits than main list words, so
+    // account for that when working out how many words we need
+    let number_of_words_to_put_in_passphrase = match (required_list, opt.number_of_words) {
+        (Some(required_list), None) => number_of_words_needed_with_required(
+            target_minimum_entropy(minimum_entropy, strength_count),
+            entropy_per_word.unwrap_or((list_length as f64).log2()),
+            opt.require_count,
+            required_list.len(),
+        ),
+        _ => number_of_words_to_put_in_passphrase,
+    };
+    if required_list.is_some() {
+        check_required_count(number_of_words_to_put_in_passphrase, opt.require_count)?;
+    }
+    // A grammar has exactly one word per slot
+    let number_of_words_to_put_in_passphrase = match grammar_slots {
+        Some(grammar_slots) => grammar_slots.len(),
+        None => number_of_words_to_put_in_passphrase,
+    };
+    // With --per-position, each position's word is worth as much as its own list, so add
+    // positions until they reach the minimum entropy, unless the user said how many words they
+    // want. Then each position works like a grammar slot.
+    let number_of_words_to_put_in_passphrase = match (position_sources, opt.number_of_words) {
+        (Some(position_sources), None) => {
+            let list_lengths: Vec<usize> = position_sources
+                .iter()
+                .map(|(_, words)| words.len())
+                .collect();
+            positions_needed_for_entropy(
+                target_minimum_entropy(minimum_entropy, strength_count),
+                &list_lengths,
+            )
+        }
+        _ => number_of_words_to_put_in_passphrase,
+    };
+    Ok(WordCountPlan {
+        number_of_words: number_of_words_to_put_in_passphrase,
+        words_per_slot,
+        entropy_per_word,
+        entropy_per_slot,
+        policy_minimum_entropy,
+    })
+}
+
+/// With --verbose, start with the targets the passphrases were planned around: the strength
+/// level the user picked, the password policy's minimum, and how short words are cut down to.
+fn print_targets(opt: &Args, plan: WordCountPlan, abbreviation_length: Option<usize>) {
+    if opt.verbose {
+        if let Some(level) = opt.level {
+            opt.entropy_to.print_line(&format!(
+                "Strength level {} targets at least {} bits of entropy",
+                format!("{:?}", level).to_lowercase(),
+                level.minimum_entropy()
+            ));
+        }
+        if let Some(policy_minimum_entropy) = plan.policy_minimum_entropy {
+            opt.entropy_to.print_line(&format!(
+                "Password policy requires at least {} bits of entropy",
+                policy_minimum_entropy
+            ));
+        }
+        if let Some(abbreviation_length) = abbreviation_length {
+            opt.entropy_to.print_line(&format!(
+                "Abbreviating words to their first {} characters, which is enough to tell every word on the list apart, so entropy is unchanged",
+                abbreviation_length
+            ));
+        }
+    }
+}
+
+/// Work out the passphrases' entropy, if we're going to report it (or check it against a
+/// password policy), along with what the words alone are worth, to show next to it.
+fn estimate_passphrase_entropy(
+    sources: &WordSources,
+    weights: &Option<Vec<f64>>,
+    plan: WordCountPlan,
+    injected_digit_entropy: Option<f64>,
+    opt: &Args,
+) -> Option<(f64, f64)> {
+    if opt.verbose
+        || opt.annotate_entropy
+        || opt.security_answers.is_some()
+        || opt.stats_only
+        || plan.policy_minimum_entropy.is_some()
+    {
+        // Usually entropy is simple to calculate, but --qwerty-flow makes some words more likely
+        // than others, so we need the list itself to work it out
+        let words_entropy = match (&sources.grammar_slots, sources.required_list) {
+            // Each grammar slot's word is only worth as much as that slot's number of words
+            (Some(grammar_slots), _) => grammar_entropy(grammar_slots),
+            // Required words are worth whatever their own list's words are worth
+            (None, Some(required_list)) => {
+                estimate_entropy(
+                    plan.number_of_words - opt.require_count,
+                    sources.list,
+                    weights,
+                    opt,
+                ) + required_words_entropy(opt.require_count, required_list.len())
+            }
+            (None, None) => estimate_entropy(
+                plan.number_of_words * plan.words_per_slot,
+                sources.list,
+                weights,
+                opt,
+            ),
+        };
+        // Injected digits change entropy, for better or worse
+        let passphrase_entropy = words_entropy;
+        let passphrase_entropy = match injected_digit_entropy {
+            Some(injected_digit_entropy) => {
+                if opt.verbose {
+                    opt.entropy_to.print_line(&format!(
+                        "Injecting digits changes entropy by an estimated {:.2} bits, after taking away what the replaced letters were worth",
+                        injected_digit_entropy
+                    ));
+                }
+                passphrase_entropy + injected_digit_entropy
+            }
+            None => passphrase_entropy,
+        };
+        // A word of the user's own is only worth where it goes
+        let passphrase_entropy = match opt.inject_word {
+            Some(_) => {
+                let position_entropy = injected_word_entropy(plan.number_of_words);
+                if opt.verbose {
+                    opt.entropy_to.print_line(&format!(
+                        "The injected word adds no entropy, since you know it, but its position adds log2({}) ≈ {:.2} bits",
+                        plan.number_of_words + 1,
+                        position_entropy
+                    ));
+                }
+                passphrase_entropy + position_entropy
+            }
+            None => passphrase_entropy,
+        };
+        // Separators from a pool are drawn uniformly, so unlike other separators, we can count them
+        let passphrase_entropy = match sources.separator_pool {
+            Some(separator_pool) => {
+                let separator_entropy =
+                    separator_pool_entropy(plan.number_of_words, separator_pool.len());
+                if opt.verbose {
+                    opt.entropy_to.print_line(&format!(
+                        "Separators add {} × log2({}) ≈ {:.2} bits, since each is drawn from a pool of {} characters",
+                        plan.number_of_words.saturating_sub(1),
+                        separator_pool.len(),
+                        separator_entropy,
+                        separator_pool.len()
+                    ));
+                }
+                passphrase_entropy + separator_entropy
+            }
+            None => passphrase_entropy,
+        };
+        Some((words_entropy, passphrase_entropy))
+    } else {
+        None
+    }
+}
+
+/// Report the passphrases' estimated entropy: on its own for security answers, and with
+/// everything that went into it for --verbose.
+fn report_entropy(
+    entropy_estimates: Option<(f64, f64)>,
+    sources: &WordSources,
+    weights: &Option<Vec<f64>>,
+    plan: WordCountPlan,
+    position_sources: Option<&[(String, Vec<&str>)]>,
+    opt: &Args,
+) {
+    // With --per-position, words come from more than one list, so say how many lists rather
+    // than how long their union is
+    let number_of_lists = position_sources.map(|position_sources| position_sources.len());
+    // Security answers always come with their entropy, since they're meant to be short
+    if let (Some(_), false, Some((_, passphrase_entropy))) =
+        (opt.security_answers, opt.verbose, entropy_estimates)
+    {
+        let message = match number_of_lists {
+            Some(_) => Message::EachAnswerEntropyFromLists,
+            None => Message::EachAnswerEntropy,
+        };
+        opt.entropy_to.print_line(&text(
+            message,
+            &[
+                &format_entropy(passphrase_entropy, opt.entropy_precision, opt.entropy_round),
+                &(plan.number_of_words * plan.words_per_slot),
+                &number_of_lists.unwrap_or(sources.list.len()),
+            ],
+        ));
+    }
+    if let (true, Some((words_entropy, passphrase_entropy))) = (opt.verbose, entropy_estimates) {
+        // print entropy information, by default with eprint to only print it
+        // to the terminal
+        print_entropy(
+            &format_entropy(passphrase_entropy, opt.entropy_precision, opt.entropy_round),
+            plan.number_of_words * plan.words_per_slot,
+            sources.list.len(),
+            number_of_lists,
+            opt.n_passphrases,
+            opt.entropy_to,
+        );
+        if let (true, Some(entropy_per_slot)) = (opt.compound, plan.entropy_per_slot) {
+            opt.entropy_to.print_line(&format!(
+                "With --compound, --words counts slots rather than words: each of the {} slots is {} words joined together, so it's worth {:.2} bits",
+                plan.number_of_words, WORDS_PER_COMPOUND, entropy_per_slot
+            ));
+        }
+        // Generated separators and random case aren't counted in the estimate, so show what
+        // they'd add, next to what the words alone are worth
+        let generated_separators = is_generated_separator(&opt.separator);
+        if sources.separator_pool.is_some() || generated_separators || opt.random_case {
+            let mut full_entropy = passphrase_entropy;
+            if generated_separators && sources.separator_pool.is_none() {
+                full_entropy += generated_separator_entropy(plan.number_of_words, &opt.separator);
+            }
+            if opt.random_case {
+                full_entropy +=
+                    random_case_entropy_per_word(sources.list) * plan.number_of_words as f64;
+            }
+            opt.entropy_to.print_line(&text(
+                Message::EntropyBreakdown,
+                &[
+                    &format_entropy(words_entropy, opt.entropy_precision, opt.entropy_round),
+                    &format_entropy(full_entropy, opt.entropy_precision, opt.entropy_round),
+                ],
+            ));
+        }
+        if let Some(position_sources) = position_sources {
+            for (position, (name, words)) in position_sources
+                .iter()
+                .cycle()
+                .take(plan.number_of_words)
+                .enumerate()
+            {
+                opt.entropy_to.print_line(&format!(
+                    "Position {}: {} ({} words), {:.2} bits",
+                    position + 1,
+                    name,
+                    words.len(),
+                    (words.len() as f64).log2()
+                ));
+            }
+        } else if let Some(ref grammar_slots) = sources.grammar_slots {
+            opt.entropy_to.print_line(&format!(
+                "Each grammar slot picks from only its own words ({}), so entropy is the sum of log2 of each slot's number of words",
+                describe_grammar_slots(grammar_slots)
+            ));
+        }
+        if opt.symbolic_entropy {
+            // The symbolic form only holds when every word is equally likely
+            if weights.is_some()
+                || opt.qwerty_flow
+                || sources.required_list.is_some()
+                || sources.grammar_slots.is_some()
+                || opt.inject_digit.is_some()
+                || opt.inject_word.is_some()
+            {
+                opt.entropy_to.print_line("Can't show the symbolic form of this estimate, since with these options not every word is equally likely");
+            } else {
+                opt.entropy_to.print_line(&describe_entropy_symbolically(
+                    plan.number_of_words * plan.words_per_slot,
+                    sources.list.len(),
+                ));
+            }
+        }
+    }
+}
+
+/// For --stats-only: print the plan for these passphrases (the list, or each position's list,
+/// how many words passphrases have, what they're worth, and how long they'll be), without
+/// generating any.
+fn print_plan(
+    sources: &WordSources,
+    plan: WordCountPlan,
+    position_sources: Option<&[(String, Vec<&str>)]>,
+    list_metadata: &ListMetadata,
+    passphrase_entropy: f64,
+    opt: &Args,
+) -> Result<(), String> {
+    let mut report = vec![];
+    let list_name = displayed_list_name(opt);
+    let separators_length =
+        plan.number_of_words.saturating_sub(1) as f64 * separator_length(opt) as f64;
+    match position_sources {
+        // Report each position's own list
+        Some(position_sources) => {
+            let mut expected_length = separators_length;
+            for (position, (name, words)) in position_sources
+                .iter()
+                .cycle()
+                .take(plan.number_of_words)
+                .enumerate()
+            {
+                report.push(format!(
+                    "Position {} list: {} ({} words, {:.2} bits)",
+                    position + 1,
+                    name,
+                    words.len(),
+                    (words.len() as f64).log2()
+                ));
+                expected_length += mean_word_length(words);
+            }
+            report.push(format!("Words per passphrase: {}", plan.number_of_words));
+            report.push(format!(
+                "Entropy per passphrase: {:.2} bits",
+                passphrase_entropy
+            ));
+            report.push(format!(
+                "Expected length: about {:.0} characters",
+                expected_length
+            ));
+            return print_lines(&report);
+        }
+        None => {
+            report.push(format!("List: {}", list_name));
+            if let Some(ref name) = list_metadata.name {
+                report.push(format!("List name: {}", name));
+            }
+            if let Some(ref source) = list_metadata.source {
+                report.push(format!("List source: {}", source));
+            }
+            report.push(format!("List length: {} words", sources.list.len()));
+            report.push(format!(
+                "Bits per word: {:.2}",
+                plan.entropy_per_word
+                    .unwrap_or((sources.list.len() as f64).log2())
+            ));
+        }
+    }
+    let slot_length = match opt.compound {
+        true => {
+            WORDS_PER_COMPOUND as f64 * mean_word_length(sources.list)
+                + grapheme_length(&opt.compound_joiner) as f64
+        }
+        false => mean_word_length(sources.list),
+    };
+    let expected_length = plan.number_of_words as f64 * slot_length + separators_length;
+    if let (true, Some(entropy_per_slot)) = (opt.compound, plan.entropy_per_slot) {
+        report.push(format!(
+            "Bits per slot: {:.2} ({} words each)",
+            entropy_per_slot, WORDS_PER_COMPOUND
+        ));
+        report.push(format!("Slots per passphrase: {}", plan.number_of_words));
+    }
+    report.push(format!(
+        "Words per passphrase: {}",
+        plan.number_of_words * plan.words_per_slot
+    ));
+    report.push(format!(
+        "Entropy per passphrase: {:.2} bits",
+        passphrase_entropy
+    ));
+    report.push(format!(
+        "Expected length: about {:.0} characters",
+        expected_length
+    ));
+    print_lines(&report)
+}
+
+/// For --entropy-histogram: generate the passphrases, but only print a histogram of their
+/// lengths.
+fn print_length_histogram(
+    sources: &WordSources,
+    plan: WordCountPlan,
+    opt: &Args,
+) -> Result<(), String> {
+    let mut rng = thread_rng();
+    let mut lengths = vec![];
+    for _ in 0..opt.n_passphrases {
+        let (_words, passphrase) = make_passphrase(&mut rng, plan.number_of_words, sources, opt)?;
+        lengths.push(grapheme_length(&passphrase));
+    }
+    print_lines(&[
+        format!(
+            "Lengths (in characters) of {} passphrases:",
+            opt.n_passphrases
+        ),
+        format_histogram(&length_histogram(lengths.into_iter())),
+    ])
+}
+
+/// Pick where passphrases' randomness comes from: the user's seed, a hardware token's response
+/// or (usually) `thread_rng`. Passphrases from a seed or a token can be made again, so the user
+/// is told as much.
+fn choose_rng_source(opt: &Args) -> Result<RngSource, String> {
+    // Seeded passphrases are only as secret as the seed, so make sure the user knows that
+    if opt.seed.is_some() {
+        eprintln!("{}", text(Message::SeededPassphrases, &[]));
+    }
+    let token_response_source = match (opt.token_stdin, &opt.token_helper) {
+        (true, _) => Some(TokenResponseSource::Stdin),
+        (false, Some(command)) => Some(TokenResponseSource::Helper(command.to_string())),
+        (false, None) => None,
+    };
+    let rng_source = match token_response_source {
+        Some(source) => {
+            let response = read_token_response(&source)?;
+            eprintln!("{}", text(Message::TokenPassphrases, &[]));
+            RngSource::Token { response }
+        }
+        None => RngSource::choose(opt.seed, opt.salt.as_deref(), opt.index),
+    };
+    if opt.rng_info {
+        eprintln!("{}", rng_source.describe());
+    }
+    Ok(rng_source)
+}
+
+/// The longest any one passphrase made with this plan could be, in bytes, so the batch can be
+/// made big enough up front.
+fn longest_possible_passphrase_for_plan(
+    sources: &WordSources,
+    plan: WordCountPlan,
+    opt: &Args,
+) -> usize {
+    // An injected word isn't on any list, so it needs room of its own
+    let injected_word_length = sources
+        .injected_word
+        .map_or(0, |word| longest_possible_extra_word(word, &opt.separator));
+    // And so do the joiners inside compound slots, which aren't separators
+    let compound_joiners_length = match opt.compound {
+        true => plan.number_of_words * (WORDS_PER_COMPOUND - 1) * opt.compound_joiner.len(),
+        false => 0,
+    };
+    longest_possible_passphrase(
+        plan.number_of_words * plan.words_per_slot,
+        sources.list.iter().copied().chain(
+            sources
+                .required_list
+                .iter()
+                .copied()
+                .flatten()
+                .map(|word| word.as_str()),
+        ),
+        &opt.separator,
+    ) + injected_word_length
+        + compound_joiners_length
+}
+
+/// Catch restrictions that can't work (or, like a --min-vowel-ratio no word reaches, won't
+/// work) with these passphrases, before generating anything.
+fn check_restrictions(
+    opt: &Args,
+    list: &[&str],
+    number_of_words: usize,
+    grammar_slots: Option<&[Vec<&str>]>,
+    required_list: Option<&[String]>,
+) -> Result<(), String> {
+    // Make sure every position the user wants capitalized is in the passphrase
+    for position in &opt.capitalize_nth {
+        if *position == 0 || *position > number_of_words {
+            return Err(format!(
+                "Can't capitalize word {}, since passphrases have words 1 to {}",
+                position, number_of_words
+            ));
+        }
+    }
+
+    // No passphrase with any characters can pass a limit of 0
+    if opt.max_repeated_chars == Some(0) {
+        return Err("--max-repeated-chars must be at least 1".to_string());
+    }
+    if opt.max_same_length_run == Some(0) {
+        return Err("--max-same-length-run must be at least 1".to_string());
+    }
+    if let Some(min_vowel_ratio) = opt.min_vowel_ratio {
+        if !(0.0..=1.0).contains(&min_vowel_ratio) {
+            return Err(format!(
+                "--min-vowel-ratio ({}) should be from 0 to 1",
+                min_vowel_ratio
+            ));
+        }
+        // A passphrase can't have more vowels than its most vowel-heavy word, so some lists
+        // can't ever get there
+        let highest_vowel_ratio = highest_vowel_ratio(list);
+        if highest_vowel_ratio < min_vowel_ratio {
+            eprintln!(
+                "{}",
+                text(
+                    Message::NoWordMeetsVowelRatio,
+                    &[
+                        &format!("{:.0}", min_vowel_ratio * 100.0),
+                        &format!("{:.0}", highest_vowel_ratio * 100.0),
+                        &min_vowel_ratio
+                    ]
+                )
+            );
+        }
+    }
+
+    // Catch impossible separator requirements before generating anything
+    if let Some(min_distinct_separators) = opt.min_distinct_separators {
+        check_min_distinct_separators(&opt.separator, number_of_words, min_distinct_separators)?;
+    }
+
+    if let Some(max_word_frequency) = opt.max_word_frequency {
+        if !(max_word_frequency > 0.0 && max_word_frequency <= 1.0) {
+            return Err(format!(
+                "--max-word-frequency ({}) should be more than 0 and at most 1",
+                max_word_frequency
+            ));
+        }
+    }
+
+    // A list can only start so many passphrases with different words
+    if opt.distinct_first_words {
+        let possible_first_words = match (grammar_slots, required_list) {
+            (Some(grammar_slots), _) => grammar_slots[0].len(),
+            // A required word can end up first too
+            (None, Some(required_list)) => list
+                .iter()
+                .copied()
+                .chain(required_list.iter().map(|word| word.as_str()))
+                .collect::<HashSet<&str>>()
+                .len(),
+            (None, None) => list.len(),
+        };
+        check_distinct_first_words(opt.n_passphrases, possible_first_words)?;
+    }
+    Ok(())
+}
+
+/// With --inject-digit, make sure every passphrase will have enough letters to replace with
+/// digits, and work out what replacing them does to entropy.
+fn plan_injected_digits(
+    opt: &Args,
+    list: &[&str],
+    plan: WordCountPlan,
+    required_list: Option<&[String]>,
+) -> Result<Option<f64>, String> {
+    match opt.inject_digit {
+        Some(count) => {
+            let (main_words, required_words) = match required_list {
+                Some(required_list) => (
+                    plan.number_of_words - opt.require_count,
+                    fewest_letters(opt.require_count, required_list),
+                ),
+                None => (plan.number_of_words * plan.words_per_slot, 0),
+            };
+            let fewest_letters = fewest_letters(main_words, list) + required_words;
+            if fewest_letters < count {
+                return Err(format!(
+                    "Passphrases could have as few as {} letters, so can't replace {} of them with digits",
+                    fewest_letters, count
+                ));
+            }
+            let distinct_letters = distinct_letters(
+                list.iter().copied().chain(
+                    required_list
+                        .iter()
+                        .copied()
+                        .flatten()
+                        .map(|word| word.as_str()),
+                ),
+                opt.title_case,
+            );
+            Ok(Some(injected_digits_entropy(
+                count,
+                fewest_letters,
+                distinct_letters,
+            )))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Some options don't generate passphrases, but do something else with the list and stop, like
+/// checking whether a passphrase could've been made from it, or making a passphrase from dice
+/// rolls. Run whichever one the user asked for, if any, and say whether one ran.
+fn run_list_only_modes(
+    list: &[&str],
+    separator_pool: Option<&[char]>,
+    opt: &Args,
+    sinks: &[Sink],
+) -> Result<bool, String> {
+    // If user wants to check a passphrase against the list, do that and stop
+    if let Some(ref passphrase) = opt.could_be_generated {
+        let check = check_could_be_generated(
+            passphrase,
+            list,
+            &opt.separator,
+            separator_pool,
+            opt.title_case,
+        );
+        if check.could_be_generated() {
+            println!(
+                "Yes: all {} words of this passphrase are on the list",
+                check.words.len()
+            );
+            return Ok(true);
+        }
+        println!(
+            "No: some words of this passphrase aren't on the list ({} of {}): {}",
+            check.unmatched.len(),
+            check.words.len(),
+            check
+                .unmatched
+                .iter()
+                .map(|word| format!("'{}'", word))
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+        return Err(
+            "Passphrase couldn't have been generated from this list with these settings"
+                .to_string(),
+        );
+    }
+
+    // If user wants to rebuild a passphrase from a word bank, do that and stop
+    if let (Some(ref from_word_bank_path), Some(ref selections)) =
+        (&opt.from_word_bank_path, &opt.selections)
+    {
+        let bank = read_word_bank(from_word_bank_path)?;
+        let words = words_from_selections(selections, &bank)?;
+        let passphrase = assemble_words(&mut thread_rng(), &words, separator_pool, opt)?;
+        println!("{}", passphrase);
+        return Ok(true);
+    }
+
+    // If user wants to replay a passphrase from an index file, do that and stop
+    if let Some(ref replay_indices_path) = opt.replay_indices_path {
+        let (fingerprint, passphrase_indices) = read_index_file(replay_indices_path)?;
+        if fingerprint != list_fingerprint(list) {
+            return Err("Index file was made with a different word list. Use the same --list or --custom-list as when it was made.".to_string());
+        }
+        let mut rng = thread_rng();
+        for indices in passphrase_indices {
+            let words = words_from_indices(&indices, list)?;
+            let passphrase = assemble_words(&mut rng, &words, separator_pool, opt)?;
+            println!("{}", passphrase);
+        }
+        return Ok(true);
+    }
+
+    // Likewise if user gave us an encoded string of indices
+    if let Some(ref encoded_indices) = opt.encoded_indices {
+        let encoding = opt.encode_indices.unwrap_or(IndexEncoding::Base58);
+        let indices = decode_indices(encoded_indices, list.len(), encoding)?;
+        let words = words_from_indices(&indices, list)?;
+        let passphrase = assemble_words(&mut thread_rng(), &words, separator_pool, opt)?;
+        println!("{}", passphrase);
+        return Ok(true);
+    }
+
+    // Or if user wants a worksheet for using dice instead
+    if opt.worksheet {
+        let worksheet = dice_worksheet(list, &displayed_list_name(opt), opt.dice_base)?;
+        match sinks.is_empty() {
+            true => print!("{}", worksheet),
+            false => send_to_sinks(&worksheet, sinks)?,
+        }
+        return Ok(true);
+    }
+
+    // Or if user gave us dice rolls to make a passphrase from
+    if let Some(ref dice_input) = opt.dice_input {
+        let rolls_per_word = rolls_per_word(list.len(), opt.dice_base)?;
+        let indices = parse_rolls(dice_input, opt.dice_base, rolls_per_word)?
+            .iter()
+            .map(|word_rolls| rolls_to_index(word_rolls, opt.dice_base))
+            .collect::<Result<Vec<usize>, String>>()?;
+        let words = words_from_indices(&indices, list)?;
+        let passphrase = assemble_words(&mut thread_rng(), &words, separator_pool, opt)?;
+        println!("{}", passphrase);
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+/// Take words the user doesn't want out of the list (and their weights along with them): words
+/// already used, according to the history file, words that look like other words, with
+/// --avoid-visual-confusables, and words that are the same as another once canonicalized.
+fn remove_unwanted_words(
+    list: &mut Vec<&str>,
+    weights: &mut Option<Vec<f64>>,
+    history: Option<&History>,
+    opt: &Args,
+) -> Result<(), String> {
+    if let Some(history) = history {
+        let original_list_length = list.len();
+        // Take any used words' weights out too, so weights still line up with words
+        if let Some(weights) = weights {
+            let mut words = list.iter();
+            weights.retain(|_| !history.contains(words.next().unwrap()));
+        }
+        history.exclude_used_words(list);
+        if list.is_empty() {
+            return Err(
+                "Every word in this list has already been used, according to the history file"
+                    .to_string(),
+            );
+        }
+        if let Some(warning) = pool_exhaustion_warning(list.len(), original_list_length) {
+            eprintln!("{}", warning);
+        }
+    }
+
+    // If user wants to avoid words that look like other words, take those out too
+    if opt.avoid_visual_confusables {
+        let confusable = visually_confusable_words(list);
+        if let Some(weights) = weights {
+            let mut words = list.iter();
+            weights.retain(|_| !confusable.contains(words.next().unwrap()));
+        }
+        list.retain(|word| !confusable.contains(word));
+        if list.len() < MIN_USABLE_LIST_LENGTH {
+            return Err(format!(
+                "Only {} words are left after taking out words that look like other words, which isn't enough to make a passphrase",
+                list.len()
+            ));
+        }
+        if opt.verbose {
+            opt.entropy_to.print_line(&format!(
+                "Left out {} words that could be misread as other words, leaving {}",
+                confusable.len(),
+                list.len()
+            ));
+        }
+    }
+
+    // Words that are the same once canonicalized would make the same passphrases, so only count
+    // each of them once
+    if opt.canonicalize {
+        let mut seen = HashSet::new();
+        let distinct: Vec<bool> = list
+            .iter()
+            .map(|word| seen.insert(canonical_form(word)))
+            .collect();
+        if let Some(weights) = weights {
+            let mut distinct = distinct.iter();
+            weights.retain(|_| *distinct.next().unwrap());
+        }
+        let mut distinct = distinct.iter();
+        let original_list_length = list.len();
+        list.retain(|_| *distinct.next().unwrap());
+        if list.len() < original_list_length {
+            eprintln!(
+                "Note: Merged {} words on this list that were identical once lowercased, so --canonicalize leaves {} different words",
+                original_list_length - list.len(),
+                list.len()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Cut the list down to the words the user asked for, with --word-pattern, and then to a length
+/// that works with dice (--dice-compatible) or a coin (--pow2). Weights are cut down along with
+/// their words (only --word-pattern can be used with a weighted list).
+fn narrow_list(
+    list: &mut Vec<&str>,
+    weights: &mut Option<Vec<f64>>,
+    opt: &Args,
+) -> Result<(), String> {
+    // If user only wants words that match a pattern, take the rest out. This comes before
+    // anything else looks at the list, so --dice-compatible cuts down the words that match.
+    if let Some(ref word_pattern) = opt.word_pattern {
+        let original_list_length = list.len();
+        let matching = words_matching_pattern(list, word_pattern)?;
+        if let Some(weights) = weights {
+            let mut matching = matching.iter();
+            weights.retain(|_| *matching.next().unwrap());
+        }
+        let mut matching = matching.iter();
+        list.retain(|_| *matching.next().unwrap());
+        if list.len() < MIN_USABLE_LIST_LENGTH {
+            return Err(format!(
+                "Only {} words on this list match --word-pattern '{}', which isn't enough to make a passphrase",
+                list.len(),
+                word_pattern
+            ));
+        }
+        if opt.verbose {
+            opt.entropy_to.print_line(&format!(
+                "Kept {} of this list's {} words that match --word-pattern",
+                list.len(),
+                original_list_length
+            ));
+        }
+    }
+    // If the list isn't a power of the dice's sides, cut it down to one. Sorting first means the
+    // same list always keeps the same words, in the same order, whatever order it came in.
+    if opt.dice_compatible {
+        let original_list_length = list.len();
+        let dice_compatible_length = dice_compatible_length(list.len(), opt.dice_base)?;
+        if dice_compatible_length < original_list_length {
+            list.sort_unstable();
+            list.truncate(dice_compatible_length);
+            eprintln!(
+                "Note: --dice-compatible dropped {} of this list's {} words, keeping the first {} in sorted order. Each word is now worth {} bits.",
+                original_list_length - list.len(),
+                original_list_length,
+                list.len(),
+                format_entropy(
+                    (list.len() as f64).log2(),
+                    opt.entropy_precision,
+                    opt.entropy_round
+                )
+            );
+        }
+    }
+    // Likewise for --pow2, which works like --dice-compatible with a coin
+    if opt.pow2 {
+        let original_list_length = list.len();
+        let pow2_length = dice_compatible_length(list.len(), 2)?;
+        let bits_per_word = pow2_length.trailing_zeros();
+        if pow2_length < original_list_length {
+            list.sort_unstable();
+            list.truncate(pow2_length);
+            eprintln!(
+                "{}",
+                text(
+                    Message::Pow2DroppedWords,
+                    &[
+                        &(original_list_length - list.len()),
+                        &original_list_length,
+                        &list.len(),
+                        &bits_per_word
+                    ]
+                )
+            );
+        } else {
+            eprintln!(
+                "{}",
+                text(
+                    Message::Pow2AlreadyPowerOfTwo,
+                    &[&list.len(), &bits_per_word]
+                )
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Make sure the password policy allows every list the user picked. With --per-position, that
+/// can be more than one.
+fn check_lists_allowed(
+    policy: &Policy,
+    opt: &Args,
+    matches: &ArgMatches,
+    has_custom_list: bool,
+) -> Result<(), String> {
+    let mut list_names = vec![];
+    if !has_custom_list || matches.value_source("list_choice") == Some(ValueSource::CommandLine) {
+        list_names.extend(
+            opt.list_choices
+                .iter()
+                .map(|list_choice| list_choice_name(*list_choice)),
+        );
+    }
+    if has_custom_list {
+        list_names.push("custom");
+    }
+    for list_name in list_names {
+        policy.check_list_allowed(list_name)?;
+    }
+    Ok(())
+}
+
+/// The lists for --per-position, in the order they were given on the command line, each with a
+/// name to report it by. `custom_lists` has the words of each --custom-list, in order. If the
+/// user didn't give any lists, that's just the default list.
+fn per_position_sources<'a>(
+    matches: &ArgMatches,
+    opt: &Args,
+    custom_lists: &[&'a [String]],
+) -> Vec<(String, Vec<&'a str>)> {
+    let indices_of = |id: &str| -> Vec<usize> {
+        matches
+            .indices_of(id)
+            .map(|indices| indices.collect())
+            .unwrap_or_default()
+    };
+    // Where each list was given on the command line, so we can put them back in order
+    let mut sources: Vec<(usize, String, Vec<&'a str>)> = vec![];
+    if custom_lists.is_empty()
+        || matches.value_source("list_choice") == Some(ValueSource::CommandLine)
+    {
+        let indices = indices_of("list_choice");
+        for (n, list_choice) in opt.list_choices.iter().enumerate() {
+            let index = indices.get(n).copied().unwrap_or(0);
+            sources.push((
+                index,
+                list_choice.name().to_string(),
+                fetch_list(*list_choice).to_vec(),
+            ));
+        }
+    }
+    let indices = indices_of("custom_list_file_path");
+    for (n, (custom_list, path)) in custom_lists
+        .iter()
+        .zip(&opt.custom_list_file_paths)
+        .enumerate()
+    {
+        let index = indices.get(n).copied().unwrap_or(0);
+        let words = custom_list.iter().map(|word| word.as_str()).collect();
+        sources.push((index, path.display().to_string(), words));
+    }
+    sources.sort_by_key(|(index, _, _)| *index);
+    sources
+        .into_iter()
+        .map(|(_, name, words)| (name, words))
+        .collect()
+}
+
+/// Generate passphrases as fast as we can for `phraze benchmark`, and report the throughput.
+fn run_benchmark(args: &BenchmarkArgs) -> Result<(), String> {
+    if args.iterations == 0 {
+        return Err("Benchmark needs at least 1 iteration".to_string());
+    }
+    let custom_list = match args.custom_list_file_path {
+        Some(ref custom_list_file_path) => Some(read_in_custom_list(custom_list_file_path)?),
+        None => None,
+    };
+    let list: Vec<&str> = match custom_list {
+        Some(ref custom_list) => custom_list.iter().map(|word| word.as_str()).collect(),
+        None => fetch_list(args.list_choice).to_vec(),
+    };
+    if list.is_empty() {
+        return Err("Can't benchmark an empty list".to_string());
+    }
+    let mut rng = thread_rng();
+    let start = Instant::now();
+    for _ in 0..args.iterations {
+        // Make sure the compiler doesn't skip generating passphrases we never use
+        black_box(generate_passphrase_with_rng(
+            &mut rng,
+            args.number_of_words,
+            &args.separator,
+            args.title_case,
+            &list,
+        ));
+    }
+    let seconds = start.elapsed().as_secs_f64();
+    println!(
+        "Generated {} passphrases of {} words in {:.2} seconds: about {:.0} passphrases per second",
+        args.iterations,
+        args.number_of_words,
+        seconds,
+        args.iterations as f64 / seconds
+    );
+    Ok(())
+}
+
+/// Clean up a word list for `phraze clean-list`, reporting what changed. A cleaned list is meant
+/// to be shared, so unlike history, index and word bank files, it's written with plain
+/// `std::fs::write`, not just for its owner (see `write_private_file`).
+fn run_clean_list(args: &CleanListArgs) -> Result<(), String> {
+    let cleaned_list = clean_list(&args.input, args.case_fold)?;
+    let report = &cleaned_list.report;
+    for (count, change) in [
+        (report.diceware_numbers, "Diceware numbers taken off"),
+        (
+            report.normalized,
+            "Words put in Unicode Normalization Form C",
+        ),
+        (report.trimmed, "Lines trimmed of whitespace"),
+        (report.blank_lines, "Blank lines dropped"),
+        (report.duplicates, "Duplicate words removed"),
+        (
+            report.case_duplicates,
+            "Words removed that only differed in case",
+        ),
+    ] {
+        eprintln!("{}: {}", change, count);
+    }
+    if cleaned_list.words.len() < args.min_words {
+        return Err(format!(
+            "Cleaned list only has {} words, fewer than the minimum of {}, so it wasn't written",
+            cleaned_list.words.len(),
+            args.min_words
+        ));
+    }
+    let text = format_cleaned_list(&cleaned_list);
+    // The whole point is that the cleaned list reads back in just as it is, so check it does
+    if custom_list_from_reader(text.as_bytes())? != cleaned_list.words {
+        return Err(
+            "Cleaned list wouldn't read back in the same, so it wasn't written".to_string(),
+        );
+    }
+    match args.output {
+        Some(ref output) => {
+            if let Err(e) = std::fs::write(output, text) {
+                return Err(format!("Error writing cleaned list: {}", e));
+            }
+            eprintln!(
+                "Wrote {} words to {}",
+                cleaned_list.words.len(),
+                output.display()
+            );
+        }
+        None => print!("{}", text),
+    }
+    Ok(())
+}
+
+/// Check a custom list over for `phraze audit-list`, printing how it did on each check.
+fn run_audit_list(args: &AuditListArgs) -> Result<(), String> {
+    if let Some(check) = args.warn.iter().find(|check| args.require.contains(check)) {
+        return Err(format!(
+            "Check '{}' can't be both a warning and required",
+            check.name()
+        ));
+    }
+    let words = read_in_words_as_written(&args.input)?;
+    let analysis = ListAnalysis::new(&words);
+    println!(
+        "{}: {} words ({} distinct)",
+        args.input.display(),
+        analysis.words_read,
+        analysis.distinct_words
+    );
+    let mut failed_required_checks = vec![];
+    for result in analysis.audit(args.min_words) {
+        let required = (result.check.required_by_default() || args.require.contains(&result.check))
+            && !args.warn.contains(&result.check);
+        let status = match (result.passed, required) {
+            (true, _) => "PASS",
+            (false, true) => "FAIL",
+            (false, false) => "WARN",
+        };
+        println!("{}  {}: {}", status, result.check.name(), result.details);
+        if !result.passed && required {
+            failed_required_checks.push(result.check.name());
+        }
+    }
+    match failed_required_checks.len() {
+        0 => Ok(()),
+        count => Err(format!(
+            "List failed {} required check{}: {}",
+            count,
+            if count == 1 { "" } else { "s" },
+            failed_required_checks.join(", ")
+        )),
+    }
+}
+
+/// Rebuild a phraze command that gives the same settings as this run, from everything the user
+/// gave on the command line. Options are written out in full (`--words 5` rather than `-w5`),
+/// in the order they're listed in the help text. Defaults are left out, since some options
+/// (like --index) can only be given along with others.
+fn reproducing_command(matches: &ArgMatches) -> String {
+    let mut command = vec!["phraze".to_string()];
+    let mut listed_sources = false;
+    for arg in Args::command().get_arguments() {
+        let id = arg.get_id().as_str();
+        if id == "print_command" || matches.value_source(id) != Some(ValueSource::CommandLine) {
+            continue;
+        }
+        let flag = match arg.get_long() {
+            Some(long) => format!("--{}", long),
+            None => continue,
+        };
+        // With --per-position, the order of the lists matters, so write out --list and
+        // --custom-list together, in the order they were given
+        if id == "custom_list_file_path" && listed_sources {
+            continue;
+        }
+        if id == "list_choice" || id == "custom_list_file_path" {
+            let mut sources = vec![];
+            for (id, flag) in [
+                ("list_choice", "--list"),
+                ("custom_list_file_path", "--custom-list"),
+            ] {
+                if matches.value_source(id) != Some(ValueSource::CommandLine) {
+                    continue;
+                }
+                let indices = matches.indices_of(id).into_iter().flatten();
+                for (index, value) in indices.zip(matches.get_raw(id).into_iter().flatten()) {
+                    sources.push((index, flag, shell_quote(&value.to_string_lossy())));
+                }
+            }
+            sources.sort();
+            for (_, flag, value) in sources {
+                command.push(flag.to_string());
+                command.push(value);
+            }
+            listed_sources = true;
+            continue;
+        }
+        match arg.get_action() {
+            ArgAction::SetTrue => command.push(flag),
+            ArgAction::Count => {
+                for _ in 0..matches.get_count(id) {
+                    command.push(flag.clone());
+                }
+            }
+            _ => {
+                for value in matches.get_raw(id).into_iter().flatten() {
+                    command.push(flag.clone());
+                    command.push(shell_quote(&value.to_string_lossy()));
+                }
+            }
+        }
+    }
+    command.join(" ")
+}
+
+/// Print a passphrase in the plain format, padded and along with its memorability score and
+/// entropy if the user asked for them.
+fn print_passphrase(
+    passphrase: &str,
+    details: &PassphraseDetails,
+    opt: &Args,
+) -> std::io::Result<()> {
+    writeln!(stdout(), "{}", passphrase_line(passphrase, details, opt))
+}
+
+/// What to do when printing passphrases fails. If whatever was reading them has stopped (a
+/// broken pipe, like `phraze -n 100 | head -n 1`), there's no one left to print to, so just stop
+/// without an error. Anything else is an error.
+fn print_error(e: std::io::Error) -> Result<(), String> {
+    match e.kind() {
+        ErrorKind::BrokenPipe => Ok(()),
+        _ => Err(format!("Couldn't print passphrases: {}", e)),
+    }
+}
+
+/// Print lines, like the --stats-only report, to stdout. Like `print_error`, this stops quietly
+/// if whatever was reading them has stopped.
+fn print_lines(lines: &[String]) -> Result<(), String> {
+    let mut stdout = stdout().lock();
+    for line in lines {
+        if let Err(e) = writeln!(stdout, "{}", line) {
+            return print_error(e);
+        }
+    }
+    Ok(())
+}
+
+/// For --stream: print passphrases, one per line, until whatever's reading them stops or the
+/// user presses Ctrl-C. Passphrases are buffered, and flushed every `STREAM_FLUSH_INTERVAL` (or
+/// right away, if --rate is slow enough that we'd wait longer than that for the next one).
+fn stream_passphrases(
+    rng: &mut impl Rng,
+    number_of_words: usize,
+    sources: &WordSources,
+    opt: &Args,
+) -> Result<(), String> {
+    // Time to wait between passphrases, with --rate
+    let wait = match opt.rate {
+        Some(rate) if rate > 0.0 && rate.is_finite() => Some(Duration::from_secs_f64(1.0 / rate)),
+        Some(rate) => {
+            return Err(format!(
+                "--rate ({}) should be a number of passphrases per second, more than 0",
+                rate
+            ))
+        }
+        None => None,
+    };
+    // Stop at the next passphrase on Ctrl-C, so everything so far can be flushed first
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let handler_interrupted = interrupted.clone();
+    if let Err(e) = ctrlc::set_handler(move || handler_interrupted.store(true, Ordering::SeqCst)) {
+        return Err(format!("Couldn't set up Ctrl-C handling: {}", e));
+    }
+
+    let mut out = BufWriter::new(stdout().lock());
+    let mut last_flush = Instant::now();
+    let mut next_passphrase_at = Instant::now();
+    while !interrupted.load(Ordering::SeqCst) {
+        let (_words, passphrase) = make_passphrase(rng, number_of_words, sources, opt)?;
+        if let Err(e) = writeln!(out, "{}", passphrase) {
+            return print_error(e);
+        }
+        if let Some(wait) = wait {
+            next_passphrase_at += wait;
+        }
+        let waiting = next_passphrase_at.saturating_duration_since(Instant::now());
+        if last_flush.elapsed() + waiting >= STREAM_FLUSH_INTERVAL {
+            if let Err(e) = out.flush() {
+                return print_error(e);
+            }
+            last_flush = Instant::now();
+        }
+        std::thread::sleep(waiting);
+    }
+    match out.flush() {
+        Ok(()) => Ok(()),
+        Err(e) => print_error(e),
+    }
+}
+
+/// A passphrase as it's printed, with anything the user asked for along with it, like --score.
+fn passphrase_line(passphrase: &str, details: &PassphraseDetails, opt: &Args) -> String {
+    let passphrase = match (opt.pad_words, opt.heatmap) {
+        (true, _) => pad_words(passphrase, &details.word_lengths, separator_length(opt)),
+        (false, true) => heatmap(passphrase, &details.word_lengths, separator_length(opt)),
+        (false, false) => passphrase.to_string(),
+    };
+    let line = match opt.score {
+        true => format!("{}\t{:.0}", passphrase, details.score),
+        false => passphrase,
+    };
+    match details.entropy {
+        Some(entropy) => annotate_entropy(&line, entropy, &opt.annotate_prefix),
+        None => line,
+    }
+}
+
+/// How many characters each word takes up in a passphrase, once the user's case options have
+/// been applied. Title Case can change a word's length, like "ß" becoming "Ss".
+fn displayed_word_lengths(words: &[&str], opt: &Args) -> Vec<usize> {
+    let every_position: Vec<usize>;
+    let capitalized_positions = if opt.title_case {
+        every_position = (1..=words.len()).collect();
+        &every_position
+    } else {
+        &opt.capitalize_nth
+    };
+    capitalize_words_at(words, capitalized_positions)
+        .iter()
+        .map(|word| word.chars().count())
+        .collect()
+}
+
+/// Read in the system dictionary for --system-dict, with the user's length limits, making sure
+/// enough of it is left to make a passphrase.
+fn system_dictionary_words(system_dict_path: &Path, opt: &Args) -> Result<Vec<String>, String> {
+    let dictionary = read_in_system_dictionary(
+        system_dict_path,
+        opt.system_dict_min_length,
+        opt.system_dict_max_length,
+    )?;
+    if dictionary.words.len() < MIN_USABLE_LIST_LENGTH {
+        return Err(format!(
+                "Only {} of the {} entries in {} are between {} and {} characters long and aren't proper nouns or possessives, which isn't enough to make a passphrase",
+                dictionary.words.len(),
+                dictionary.entries,
+                system_dict_path.display(),
+                opt.system_dict_min_length,
+                opt.system_dict_max_length
+            ));
+    }
+    if opt.verbose {
+        opt.entropy_to.print_line(&format!(
+            "Kept {} of the {} entries in {}, so each word is worth {} bits",
+            dictionary.words.len(),
+            dictionary.entries,
+            system_dict_path.display(),
+            format_entropy(
+                (dictionary.words.len() as f64).log2(),
+                opt.entropy_precision,
+                opt.entropy_round
+            )
+        ));
+    }
+    Ok(dictionary.words)
+}
+
+/// What to call the list the user chose in reports: its file path, if it's a custom list, or the
+/// program, if it came from --list-cmd.
+fn displayed_list_name(opt: &Args) -> String {
+    match (
+        opt.custom_list_file_path()
+            .or(opt.system_dict_path.as_ref()),
+        &opt.list_command,
+    ) {
+        (Some(custom_list_file_path), _) => custom_list_file_path.display().to_string(),
+        (None, Some(list_command)) => list_command.clone(),
+        (None, None) => opt.list_choice().name().to_string(),
+    }
+}
+
+/// How many characters each separator takes up in the user's passphrases. Generated separators
+/// are always one character.
+fn separator_length(opt: &Args) -> usize {
+    if opt.separator_pool.is_some() || is_generated_separator(&opt.separator) {
+        1
+    } else {
+        opt.separator.chars().count()
+    }
+}
+
+/// Generate one passphrase from the given list, respecting all of the user's options. Returns
+/// the words that were picked, along with the finished passphrase.
+fn make_passphrase<'a>(
+    rng: &mut impl Rng,
+    number_of_words: usize,
+    sources: &WordSources<'a>,
+    opt: &Args,
+) -> Result<(Vec<&'a str>, String), String> {
+    // Keep generating passphrases until we get one that passes all of the user's restrictions
+    let mut failed_restriction = None;
+    for _ in 0..REJECTION_MAX_TRIES {
+        let (words, passphrase) = make_candidate_passphrase(rng, number_of_words, sources, opt)?;
+        failed_restriction = first_failed_restriction(&words, &passphrase, opt);
+        if failed_restriction.is_none() {
+            return Ok((words, passphrase));
+        }
+        sources.rejected.set(sources.rejected.get() + 1);
+    }
+    Err(text(
+        Message::RestrictionFailed,
+        &[
+            &failed_restriction.unwrap_or_default(),
+            &REJECTION_MAX_TRIES,
+        ],
+    ))
+}
+
+/// Make the `i`th passphrase of the batch, with `make_passphrase_for_batch`, using the RNG from
+/// `rng_source`. Each seeded (or token) passphrase gets its own RNG, so that any one of them can
+/// be reproduced on its own (with --index, for a seed); otherwise they all share `rng`.
+fn make_nth_passphrase_for_batch<'a>(
+    i: usize,
+    rng_source: &RngSource,
+    rng: &mut impl Rng,
+    number_of_words: usize,
+    sources: &WordSources<'a>,
+    opt: &Args,
+    batch_words: &BatchWords<'a>,
+) -> Result<(Vec<&'a str>, String), String> {
+    match rng_source {
+        RngSource::Seeded {
+            seed,
+            salt,
+            first_index,
+        } => make_passphrase_for_batch(
+            &mut match salt {
+                Some(salt) => salted_seeded_rng(*seed, salt, first_index + i as u64),
+                None => seeded_rng(*seed, first_index + i as u64),
+            },
+            number_of_words,
+            sources,
+            opt,
+            batch_words,
+        ),
+        RngSource::Token { response } => make_passphrase_for_batch(
+            &mut token_rng(response, i as u64),
+            number_of_words,
+            sources,
+            opt,
+            batch_words,
+        ),
+        RngSource::ThreadRng => {
+            make_passphrase_for_batch(rng, number_of_words, sources, opt, batch_words)
+        }
+    }
+}
+
+/// Same as `make_passphrase`, but keeps generating passphrases until one fits in with the
+/// passphrases already in the batch, for --distinct-first-words and --max-word-frequency. The
+/// first passphrase in a batch always does.
+fn make_passphrase_for_batch<'a>(
+    rng: &mut impl Rng,
+    number_of_words: usize,
+    sources: &WordSources<'a>,
+    opt: &Args,
+    batch_words: &BatchWords<'a>,
+) -> Result<(Vec<&'a str>, String), String> {
+    let mut failed_check = None;
+    for _ in 0..REJECTION_MAX_TRIES {
+        let (words, passphrase) = make_passphrase(rng, number_of_words, sources, opt)?;
+        failed_check = batch_words.first_failed_check(&words);
+        if failed_check.is_none() {
+            return Ok((words, passphrase));
+        }
+    }
+    Err(text(
+        Message::BatchCheckFailed,
+        &[&failed_check.unwrap_or_default(), &REJECTION_MAX_TRIES],
+    ))
+}
+
+/// Check a finished passphrase (and the words it was made from) against the user's
+/// restrictions, returning the option for the first one it fails, if any.
+fn first_failed_restriction(words: &[&str], passphrase: &str, opt: &Args) -> Option<&'static str> {
+    if opt.no_keyboard_runs && longest_keyboard_run(passphrase) > KEYBOARD_RUN_MAX_LENGTH {
+        return Some("--no-keyboard-runs");
+    }
+    if let Some(max_repeated_chars) = opt.max_repeated_chars {
+        if longest_repeated_run(passphrase) > max_repeated_chars {
+            return Some("--max-repeated-chars");
+        }
+    }
+    if opt.no_common_substrings && find_common_substring(passphrase).is_some() {
+        return Some("--no-common-substrings");
+    }
+    if let Some(max_same_length_run) = opt.max_same_length_run {
+        if longest_same_length_run(words) > max_same_length_run {
+            return Some("--max-same-length-run");
+        }
+    }
+    if let Some(min_word_chars) = opt.min_word_chars {
+        if word_character_count(words) < min_word_chars {
+            return Some("--min-word-chars");
+        }
+    }
+    if let Some(min_vowel_ratio) = opt.min_vowel_ratio {
+        if vowel_ratio(words) < min_vowel_ratio {
+            return Some("--min-vowel-ratio");
+        }
+    }
+    None
+}
+
+/// Generate one passphrase, without checking it against any of the user's restrictions.
+fn make_candidate_passphrase<'a>(
+    rng: &mut impl Rng,
+    number_of_words: usize,
+    sources: &WordSources<'a>,
+    opt: &Args,
+) -> Result<(Vec<&'a str>, String), String> {
+    // Leave room for any required words, which we'll add in afterward
+    let number_of_words = match sources.required_list {
+        Some(_) => number_of_words - opt.require_count,
+        None => number_of_words,
+    };
+    // And each compound slot takes two words
+    let number_of_words = match opt.compound {
+        true => number_of_words * WORDS_PER_COMPOUND,
+        false => number_of_words,
+    };
+    let words = if let Some(ref grammar_slots) = sources.grammar_slots {
+        generate_grammatical_words(rng, grammar_slots)
+    } else if let Some(ref weighted_distribution) = sources.weighted_distribution {
+        generate_weighted_words(rng, number_of_words, sources.list, weighted_distribution)
+    } else if opt.pow2 {
+        generate_words_from_bits(rng, number_of_words, sources.list)?
+    } else if opt.qwerty_flow {
+        generate_words_with_qwerty_flow(rng, number_of_words, sources.list)
+    } else {
+        generate_words(rng, number_of_words, sources.list)
+    };
+    let words = match sources.required_list {
+        Some(required_list) => insert_required_words(rng, &words, required_list, opt.require_count),
+        None => words,
+    };
+    let words = match sources.injected_word {
+        Some(injected_word) => inject_word(rng, &words, injected_word),
+        None => words,
+    };
+    let abbreviated: Vec<&str>;
+    let shown_words = match sources.abbreviation_length {
+        Some(length) => {
+            abbreviated = words.iter().map(|word| abbreviate(word, length)).collect();
+            &abbreviated
+        }
+        None => &words,
+    };
+    let compounds: Vec<String>;
+    let compound_words: Vec<&str>;
+    let shown_words = match opt.compound {
+        true => {
+            compounds = join_compounds(shown_words, &opt.compound_joiner);
+            compound_words = compounds.iter().map(|compound| compound.as_str()).collect();
+            &compound_words
+        }
+        false => shown_words,
+    };
+    let passphrase = assemble_words(rng, shown_words, sources.separator_pool, opt)?;
+    let passphrase = match opt.inject_digit {
+        Some(count) => inject_digits(rng, &passphrase, count)?,
+        None => passphrase,
+    };
+    Ok((words, passphrase))
+}
+
+/// Join chosen words into a passphrase, respecting the user's separator and case options.
+/// Separators come from the user's separator pool if they gave one. If the user asked for an
+/// output normalization form, the passphrase is put in that form.
+fn assemble_words(
+    rng: &mut impl Rng,
+    words: &[&str],
+    separator_pool: Option<&[char]>,
+    opt: &Args,
+) -> Result<String, String> {
+    // Capitalize any words the user asked for by position before putting them together
+    let capitalized: Vec<String>;
+    let capitalized_words: Vec<&str>;
+    let random_positions: Vec<usize>;
+    let capitalized_positions = if opt.random_case {
+        random_positions = (1..=words.len()).filter(|_| rng.gen_bool(0.5)).collect();
+        &random_positions
+    } else {
+        &opt.capitalize_nth
+    };
+    let words = if capitalized_positions.is_empty() {
+        words
+    } else {
+        capitalized = capitalize_words_at(words, capitalized_positions);
+        capitalized_words = capitalized.iter().map(|word| word.as_str()).collect();
+        &capitalized_words
+    };
+    let gaps = words.len().saturating_sub(1);
+    let passphrase = match (separator_pool, opt.min_distinct_separators) {
+        (Some(separator_pool), _) => {
+            let separators = generate_pool_separators(rng, separator_pool, gaps);
+            assemble_passphrase_with_separators(words, &separators, opt.title_case)
+        }
+        (None, Some(min_distinct_separators)) => {
+            let separators =
+                generate_distinct_separators(rng, &opt.separator, gaps, min_distinct_separators)?;
+            assemble_passphrase_with_separators(words, &separators, opt.title_case)
+        }
+        (None, None) => assemble_passphrase(rng, words, &opt.separator, opt.title_case),
+    };
+    Ok(match (opt.canonicalize, opt.output_normalization) {
+        (true, _) => canonical_form(&passphrase),
+        (false, Some(form)) => normalize(&passphrase, form),
+        (false, None) => passphrase,
+    })
+}
+
+/// Estimate the entropy of a passphrase generated from the given list, respecting all of the
+/// user's options
+fn estimate_entropy<T: AsRef<str>>(
+    number_of_words: usize,
+    list: &[T],
+    weights: &Option<Vec<f64>>,
+    opt: &Args,
+) -> f64 {
+    if let Some(weights) = weights {
+        let entropy_per_word = shannon_entropy_per_word(weights);
+        if opt.verbose {
+            opt.entropy_to.print_line(&format!(
+                "Words are weighted, so each word is worth an estimated {:.2} bits, rather than the {:.2} bits of an unweighted list of the same length",
+                entropy_per_word,
+                (list.len() as f64).log2()
+            ));
+        }
+        entropy_per_word * number_of_words as f64
+    } else if opt.qwerty_flow {
+        qwerty_flow_entropy(number_of_words, list)
+    } else {
+        (list.len() as f64).log2() * number_of_words as f64
+    }
+}
+
+/// Print the calculated (estimated) entropy of a passphrase, already formatted with
+/// `format_entropy`
+fn print_entropy(
+    passphrase_entropy: &str,
+    number_of_words: usize,
+    list_length: usize,
+    number_of_lists: Option<usize>,
+    n_passphrases: usize,
+    stream: OutputStream,
+) {
+    // Depending on how many different passphrases the user wants printed, and whether the words
+    // come from more than one list, change the printed text accordingly
+    let message = match (n_passphrases == 1, number_of_lists) {
+        (true, None) => Message::PassphraseEntropy,
+        (false, None) => Message::EachPassphraseEntropy,
+        (true, Some(_)) => Message::PassphraseEntropyFromLists,
+        (false, Some(_)) => Message::EachPassphraseEntropyFromLists,
+    };
+    stream.print_line(&text(
+        message,
+        &[
+            &passphrase_entropy,
+            &number_of_words,
+            &number_of_lists.unwrap_or(list_length),
+        ],
+    ));
+}
+
+/// Convert list_choice string slice into a ListChoice enum. Clap calls this function.
+fn parse_list_choice(list_choice: &str) -> Result<ListChoice, String> {
+    match list_choice.to_lowercase().as_ref() {
+        "l" => Ok(ListChoice::Long),
+        "m" => Ok(ListChoice::Medium),
+        "e" => Ok(ListChoice::Eff),
+        "n" => Ok(ListChoice::Mnemonicode),
+        "s" => Ok(ListChoice::Effshort),
+        "q" => Ok(ListChoice::Qwerty),
+        "a" => Ok(ListChoice::Alpha),
+        "j" => Ok(ListChoice::Emoji),
+        _ => Err(format!(
+            "Inputted list choice '{}' doesn't correspond to an available word list",
+            list_choice
+        )),
+    }
+}
+
+/// The name --list takes for a built-in list, for checking it against a password policy.
+fn list_choice_name(list_choice: ListChoice) -> &'static str {
+    match list_choice {
+        ListChoice::Long => "l",
+        ListChoice::Medium => "m",
+        ListChoice::Eff => "e",
+        ListChoice::Mnemonicode => "n",
+        ListChoice::Effshort => "s",
+        ListChoice::Qwerty => "q",
+        ListChoice::Alpha => "a",
+        ListChoice::Emoji => "j",
+    }
+}
+
+/// Convert strength level string slice into a StrengthLevel enum. Clap calls this function.
+fn parse_strength_level(level: &str) -> Result<StrengthLevel, String> {
+    match level.to_lowercase().as_ref() {
+        "low" => Ok(StrengthLevel::Low),
+        "medium" => Ok(StrengthLevel::Medium),
+        "high" => Ok(StrengthLevel::High),
+        "paranoid" => Ok(StrengthLevel::Paranoid),
+        _ => Err(format!(
+            "Inputted strength level '{}' should be low, medium, high or paranoid",
+            level
+        )),
+    }
+}
+
+/// Convert output format string slice into an OutputFormat enum. Clap calls this function.
+fn parse_output_format(output_format: &str) -> Result<OutputFormat, String> {
+    match output_format.to_lowercase().as_ref() {
+        "plain" => Ok(OutputFormat::Plain),
+        // Without the feature, say so now, rather than after generating everything
+        "yaml" if cfg!(feature = "yaml") => Ok(OutputFormat::Yaml),
+        "yaml" => Err(
+            "This version of Phraze was built without YAML support (the `yaml` feature)"
+                .to_string(),
+        ),
+        _ => Err(format!(
+            "Inputted output format '{}' isn't one Phraze can print",
+            output_format
+        )),
+    }
+}
+
+/// Convert a list format's name into a ListFormat enum. Clap calls this function.
+fn parse_list_format(list_format: &str) -> Result<ListFormat, String> {
+    match list_format.to_lowercase().as_ref() {
+        "text" | "txt" => Ok(ListFormat::Text),
+        "json" => Ok(ListFormat::Json),
+        "toml" => Ok(ListFormat::Toml),
+        _ => Err(format!(
+            "List format '{}' should be text, json or toml",
+            list_format
+        )),
+    }
+}
+
+/// Convert an audit check's name into an AuditCheck enum. Clap calls this function.
+fn parse_audit_check(check: &str) -> Result<AuditCheck, String> {
+    match AuditCheck::from_name(check) {
+        Some(check) => Ok(check),
+        None => Err(format!(
+            "Inputted check '{}' isn't one of {}",
+            check,
+            AuditCheck::ALL
+                .iter()
+                .map(|check| check.name())
+                .collect::<Vec<&str>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Convert normalization form string slice into a NormalizationForm enum. Clap calls this
+/// function.
+fn parse_normalization_form(normalization_form: &str) -> Result<NormalizationForm, String> {
+    match normalization_form.to_lowercase().as_ref() {
+        "nfc" => Ok(NormalizationForm::Nfc),
+        "nfd" => Ok(NormalizationForm::Nfd),
+        "nfkc" => Ok(NormalizationForm::Nfkc),
+        "nfkd" => Ok(NormalizationForm::Nfkd),
+        _ => Err(format!(
+            "Inputted normalization form '{}' isn't one of nfc, nfd, nfkc or nfkd",
+            normalization_form
+        )),
+    }
+}
+
+/// Convert output stream string slice into an OutputStream enum. Clap calls this function.
+fn parse_output_stream(output_stream: &str) -> Result<OutputStream, String> {
+    match output_stream.to_lowercase().as_ref() {
+        "stdout" => Ok(OutputStream::Stdout),
+        "stderr" => Ok(OutputStream::Stderr),
+        _ => Err(format!(
+            "Inputted output stream '{}' should be stdout or stderr",
+            output_stream
+        )),
+    }
+}
+
+/// Convert language code string slice into a Language enum. Clap calls this function.
+fn parse_language(lang: &str) -> Result<Language, String> {
+    match Language::from_code(lang) {
+        Some(language) => Ok(language),
+        None => Err(format!("Inputted language '{}' should be en or es", lang)),
+    }
+}
+
+/// Convert entropy rounding string slice into an EntropyRounding enum. Clap calls this function.
+fn parse_entropy_rounding(entropy_rounding: &str) -> Result<EntropyRounding, String> {
+    match entropy_rounding.to_lowercase().as_ref() {
+        "nearest" => Ok(EntropyRounding::Nearest),
+        "floor" => Ok(EntropyRounding::Floor),
+        _ => Err(format!(
+            "Inputted entropy rounding '{}' should be nearest or floor",
+            entropy_rounding
+        )),
+    }
+}
+
+/// Convert index encoding string slice into an IndexEncoding enum. Clap calls this function.
+fn parse_index_encoding(index_encoding: &str) -> Result<IndexEncoding, String> {
+    match index_encoding.to_lowercase().as_ref() {
+        "base32" => Ok(IndexEncoding::Base32),
+        "base58" => Ok(IndexEncoding::Base58),
+        _ => Err(format!(
+            "Inputted index encoding '{}' isn't one Phraze knows",
+            index_encoding
         )),
     }
 }
@@ -0,0 +1,118 @@
+use crate::grapheme_length;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// A few hundred of the most common English words that appear in Phraze's built-in lists, most
+/// common first, one per line. This is only a rough guide to how familiar a word is: it's
+/// nowhere near a full frequency list, so every word that isn't in it counts as uncommon.
+const COMMON_WORDS: &str = include_str!("../word-lists/common-words.txt");
+
+/// How much each part of the memorability score counts. These add up to 1.
+const LENGTH_WEIGHT: f64 = 0.3;
+const FREQUENCY_WEIGHT: f64 = 0.3;
+const SYLLABLE_WEIGHT: f64 = 0.3;
+const ALLITERATION_WEIGHT: f64 = 0.1;
+
+/// Where a word ranks in the embedded table of common words (0 is the most common), if it's
+/// there at all.
+pub fn frequency_rank(word: &str) -> Option<usize> {
+    static RANKS: OnceLock<HashMap<&'static str, usize>> = OnceLock::new();
+    let ranks = RANKS.get_or_init(|| {
+        COMMON_WORDS
+            .lines()
+            .enumerate()
+            .map(|(rank, word)| (word, rank))
+            .collect()
+    });
+    ranks.get(word.to_lowercase().as_str()).copied()
+}
+
+/// Roughly estimate how many syllables a word has, by counting groups of vowels (counting "y"
+/// as a vowel) and not counting a silent "e" at the end. Every word has at least one syllable.
+pub fn estimate_syllables(word: &str) -> usize {
+    let word = word.to_lowercase();
+    let is_vowel = |c: char| "aeiouy".contains(c);
+    let mut syllables = 0;
+    let mut previous_was_vowel = false;
+    for c in word.chars() {
+        let vowel = is_vowel(c);
+        if vowel && !previous_was_vowel {
+            syllables += 1;
+        }
+        previous_was_vowel = vowel;
+    }
+    // "stone" has one syllable, but "table" has two
+    if syllables > 1 && word.ends_with('e') && !word.ends_with("le") && !word.ends_with("ee") {
+        syllables -= 1;
+    }
+    syllables.max(1)
+}
+
+/// Score how memorable a passphrase's words are likely to be, from 0 (hard) to 100 (easy). This
+/// is a rough heuristic, combining:
+///
+/// - length: shorter words are easier (4 letters or fewer gets full marks, 12 or more gets none)
+/// - frequency: common words are easier (see `frequency_rank`)
+/// - syllables: fewer syllables per word are easier (1 gets full marks, 4 or more gets none)
+/// - alliteration: neighboring words that start with the same letter are easier to link up
+///
+/// The score only looks at words that have already been picked, so it never affects which words
+/// are picked, or the passphrase's entropy.
+pub fn memorability_score(words: &[&str]) -> f64 {
+    if words.is_empty() {
+        return 0.0;
+    }
+    let number_of_words = words.len() as f64;
+    let mean = |part: &dyn Fn(&str) -> f64| {
+        words.iter().map(|word| part(word)).sum::<f64>() / number_of_words
+    };
+
+    let length = mean(&|word| 1.0 - ((grapheme_length(word) as f64 - 4.0) / 8.0).clamp(0.0, 1.0));
+    let frequency = mean(&|word| match frequency_rank(word) {
+        Some(rank) => 1.0 - rank as f64 / COMMON_WORDS.lines().count() as f64,
+        None => 0.0,
+    });
+    let syllables =
+        mean(&|word| 1.0 - ((estimate_syllables(word) as f64 - 1.0) / 3.0).clamp(0.0, 1.0));
+    let alliteration = if words.len() < 2 {
+        0.0
+    } else {
+        let first_letter = |word: &str| word.chars().next().map(|c| c.to_lowercase().to_string());
+        let shared = words
+            .windows(2)
+            .filter(|pair| first_letter(pair[0]) == first_letter(pair[1]))
+            .count();
+        shared as f64 / (words.len() - 1) as f64
+    };
+
+    100.0
+        * (LENGTH_WEIGHT * length
+            + FREQUENCY_WEIGHT * frequency
+            + SYLLABLE_WEIGHT * syllables
+            + ALLITERATION_WEIGHT * alliteration)
+}
+
+#[test]
+fn can_estimate_syllables() {
+    assert_eq!(estimate_syllables("cat"), 1);
+    assert_eq!(estimate_syllables("stone"), 1);
+    assert_eq!(estimate_syllables("table"), 2);
+    assert_eq!(estimate_syllables("garden"), 2);
+    assert_eq!(estimate_syllables("information"), 4);
+    assert_eq!(estimate_syllables("tree"), 1);
+    assert_eq!(estimate_syllables("hmm"), 1);
+}
+
+#[test]
+fn short_common_words_are_more_memorable() {
+    assert_eq!(frequency_rank("time"), Some(0));
+    assert_eq!(frequency_rank("Time"), Some(0));
+    assert_eq!(frequency_rank("incumbent"), None);
+    let easy = memorability_score(&["dog", "fire", "moon", "tree"]);
+    let hard = memorability_score(&["incumbent", "emergence", "pervasive", "destroyed"]);
+    assert!(easy > hard);
+    assert!((0.0..=100.0).contains(&easy) && (0.0..=100.0).contains(&hard));
+    // Alliteration helps
+    assert!(memorability_score(&["kiwi", "kelp"]) > memorability_score(&["kiwi", "pelt"]));
+    assert_eq!(memorability_score(&[]), 0.0);
+}
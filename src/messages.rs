@@ -0,0 +1,254 @@
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+/// Languages Phraze's own messages (warnings, entropy estimates and errors) can be printed in.
+/// clap's help and usage errors are always in English.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum Language {
+    English,
+    Spanish,
+}
+
+impl Language {
+    /// The language for a code like "es", or a locale like "es_ES.UTF-8" (the form LANG uses).
+    /// Returns None for languages Phraze doesn't have messages in.
+    pub fn from_code(code: &str) -> Option<Language> {
+        let language = code
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        match language.as_ref() {
+            "en" => Some(Language::English),
+            "es" => Some(Language::Spanish),
+            _ => None,
+        }
+    }
+
+    /// The language the user's environment asks for, from the first of LC_ALL, LC_MESSAGES and
+    /// LANG that's set, like other command-line tools. Falls back to English.
+    pub fn from_environment() -> Language {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|variable| std::env::var(variable).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Language::from_code(&value))
+            .unwrap_or(Language::English)
+    }
+}
+
+/// Everything Phraze can tell the user that's been translated. Each "{}" in a message's text is
+/// filled in by `text`.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum Message {
+    MixedNormalization,
+    /// Words left, and words the list started with
+    HistoryRunningLow,
+    ClearAfterScrollback,
+    TitleCaseHasNoEffect,
+    SeededPassphrases,
+    MemoryNotLocked,
+    /// Why the policy couldn't be fetched
+    PolicyUnavailable,
+    /// Where the passphrases were going, and what went wrong
+    SinkFailed,
+    /// Bits of entropy, number of words and list length
+    PassphraseEntropy,
+    /// Bits of entropy, number of words and list length
+    EachPassphraseEntropy,
+    /// Bits of entropy, number of words and list length
+    EachAnswerEntropy,
+    /// What the words alone are worth, and everything together
+    EntropyBreakdown,
+    /// What went wrong
+    ListReadFailed,
+    SeparatorRequired,
+    TitleCaseCantMarkWords,
+    ClearAfterNeedsTerminal,
+    HeatmapNeedsColor,
+    /// The option that wasn't passed, and how many tries were made
+    RestrictionFailed,
+    /// The option that wasn't passed, and how many tries were made
+    BatchCheckFailed,
+    /// Words dropped, words the list had, words kept, and bits per word
+    Pow2DroppedWords,
+    /// Words the list has, and bits per word
+    Pow2AlreadyPowerOfTwo,
+    /// The percentage asked for, the highest percentage on the list, and the ratio as given
+    NoWordMeetsVowelRatio,
+    /// One pair of words, the pair it collides with, and how many collisions there are
+    CompoundCollisions,
+    TokenPassphrases,
+    /// Entropy, how many words, and how many lists they're from, with --per-position
+    PassphraseEntropyFromLists,
+    /// Same as `PassphraseEntropyFromLists`
+    EachPassphraseEntropyFromLists,
+    /// Same as `PassphraseEntropyFromLists`
+    EachAnswerEntropyFromLists,
+}
+
+impl Message {
+    /// Every message, for checking that each language has them all.
+    pub const ALL: &'static [Message] = &[
+        Message::MixedNormalization,
+        Message::HistoryRunningLow,
+        Message::ClearAfterScrollback,
+        Message::TitleCaseHasNoEffect,
+        Message::SeededPassphrases,
+        Message::MemoryNotLocked,
+        Message::PolicyUnavailable,
+        Message::SinkFailed,
+        Message::PassphraseEntropy,
+        Message::EachPassphraseEntropy,
+        Message::EachAnswerEntropy,
+        Message::EntropyBreakdown,
+        Message::ListReadFailed,
+        Message::SeparatorRequired,
+        Message::TitleCaseCantMarkWords,
+        Message::ClearAfterNeedsTerminal,
+        Message::HeatmapNeedsColor,
+        Message::RestrictionFailed,
+        Message::BatchCheckFailed,
+        Message::Pow2DroppedWords,
+        Message::Pow2AlreadyPowerOfTwo,
+        Message::NoWordMeetsVowelRatio,
+        Message::CompoundCollisions,
+        Message::TokenPassphrases,
+        Message::PassphraseEntropyFromLists,
+        Message::EachPassphraseEntropyFromLists,
+        Message::EachAnswerEntropyFromLists,
+    ];
+}
+
+static LANGUAGE: OnceLock<Language> = OnceLock::new();
+
+/// Print Phraze's messages in this language from now on, rather than the one the environment
+/// asks for. Only the first call counts, so call it before anything's printed.
+pub fn set_language(language: Language) {
+    let _ = LANGUAGE.set(language);
+}
+
+/// The language Phraze's messages are being printed in.
+pub fn language() -> Language {
+    *LANGUAGE.get_or_init(Language::from_environment)
+}
+
+/// A message in the current language, with each "{}" in it filled in by the next of `args`.
+pub fn text(message: Message, args: &[&dyn Display]) -> String {
+    fill(template(message, language()), args)
+}
+
+fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut pieces = template.split("{}");
+    let mut filled = pieces.next().unwrap_or_default().to_string();
+    let mut args = args.iter();
+    for piece in pieces {
+        match args.next() {
+            Some(arg) => filled += &arg.to_string(),
+            None => filled += "{}",
+        }
+        filled += piece;
+    }
+    filled
+}
+
+/// A message's text in a language, before it's filled in.
+pub fn template(message: Message, language: Language) -> &'static str {
+    match language {
+        Language::English => english(message),
+        Language::Spanish => spanish(message),
+    }
+}
+
+fn english(message: Message) -> &'static str {
+    match message {
+        Message::MixedNormalization => "WARNING: Custom word list has multiple Unicode normalizations. Consider normalizing the Unicode of all words on the list before making a passphrase.",
+        Message::HistoryRunningLow => "WARNING: Only {} of {} words in this list haven't been used yet, according to the history file. Passphrases are getting weaker; consider switching lists or starting a new history file.",
+        Message::ClearAfterScrollback => "WARNING: --clear-after only clears passphrases off the screen. They can still be in your terminal's scrollback, or a log of the session.",
+        Message::TitleCaseHasNoEffect => "WARNING: Title Case has no effect on the words in this list",
+        Message::SeededPassphrases => "WARNING: Seeded passphrases are deterministic and have at most 64 bits of entropy, no matter how many words they have. Anyone who knows the seed and settings can reproduce them.",
+        Message::MemoryNotLocked => "WARNING: Couldn't lock memory for the passphrases (RLIMIT_MEMLOCK may be too low), so they could be swapped to disk",
+        Message::PolicyUnavailable => "WARNING: Couldn't get the password policy, so not following it: {}",
+        Message::SinkFailed => "WARNING: Couldn't send passphrases to {}: {}",
+        Message::PassphraseEntropy => "Passphrase has an estimated {} bits of entropy ({} words from a list of {} words)",
+        Message::EachPassphraseEntropy => "Each passphrase has an estimated {} bits of entropy ({} words from a list of {} words)",
+        Message::EachAnswerEntropy => "Each answer has an estimated {} bits of entropy ({} words from a list of {} words)",
+        Message::EntropyBreakdown => "Words only: {} bits; with separators and case: {} bits",
+        Message::ListReadFailed => "Error reading word list file: {}",
+        Message::SeparatorRequired => "Must use a separator or Title Case when using a custom word list",
+        Message::TitleCaseCantMarkWords => "Title Case can't mark where words begin with this list, since its words don't have uppercase letters. Use a separator instead.",
+        Message::ClearAfterNeedsTerminal => "--clear-after only works when printing passphrases to a terminal, since it clears them off the screen",
+        Message::HeatmapNeedsColor => "--heatmap only works when printing passphrases to a terminal that shows color (and NO_COLOR isn't set)",
+        Message::RestrictionFailed => "Couldn't generate a passphrase that passes {} after {} tries",
+        Message::BatchCheckFailed => "Couldn't generate a passphrase that passes {} after {} tries. Try fewer passphrases or a longer list.",
+        Message::Pow2DroppedWords => "WARNING: --pow2 dropped {} of this list's {} words, keeping the first {} in sorted order. Each word is now worth exactly {} bits.",
+        Message::Pow2AlreadyPowerOfTwo => "Note: This list's {} words are already a power of two, so each word is worth exactly {} bits",
+        Message::NoWordMeetsVowelRatio => "WARNING: No word on this list is {}% vowels (the most is {}%), so no passphrase can pass --min-vowel-ratio {}",
+        Message::CompoundCollisions => "WARNING: With nothing joining the words of each --compound slot, some compounds can be made from two different pairs of words on this list, like {}+{} and {}+{} ({} found), so passphrases are worth a little less than estimated. Consider a --compound-joiner, like \"'\".",
+        Message::TokenPassphrases => "Note: These passphrases can be made again by anyone with the same token, challenge and settings, so keep the token safe.",
+        Message::PassphraseEntropyFromLists => "Passphrase has an estimated {} bits of entropy ({} words from {} lists)",
+        Message::EachPassphraseEntropyFromLists => "Each passphrase has an estimated {} bits of entropy ({} words from {} lists)",
+        Message::EachAnswerEntropyFromLists => "Each answer has an estimated {} bits of entropy ({} words from {} lists)",
+    }
+}
+
+fn spanish(message: Message) -> &'static str {
+    match message {
+        Message::MixedNormalization => "ADVERTENCIA: La lista de palabras personalizada usa más de una normalización Unicode. Considere normalizar el Unicode de todas las palabras de la lista antes de generar una frase de contraseña.",
+        Message::HistoryRunningLow => "ADVERTENCIA: Según el archivo de historial, solo {} de las {} palabras de esta lista no se han usado todavía. Las frases de contraseña son cada vez más débiles; considere cambiar de lista o empezar un archivo de historial nuevo.",
+        Message::ClearAfterScrollback => "ADVERTENCIA: --clear-after solo borra las frases de contraseña de la pantalla. Pueden seguir en el historial de la terminal, o en un registro de la sesión.",
+        Message::TitleCaseHasNoEffect => "ADVERTENCIA: Title Case no tiene efecto en las palabras de esta lista",
+        Message::SeededPassphrases => "ADVERTENCIA: Las frases de contraseña con semilla son deterministas y tienen como máximo 64 bits de entropía, sin importar cuántas palabras tengan. Cualquiera que conozca la semilla y la configuración puede reproducirlas.",
+        Message::MemoryNotLocked => "ADVERTENCIA: No se pudo bloquear la memoria de las frases de contraseña (puede que RLIMIT_MEMLOCK sea demasiado bajo), así que podrían acabar en el disco de intercambio",
+        Message::PolicyUnavailable => "ADVERTENCIA: No se pudo obtener la política de contraseñas, así que no se aplicará: {}",
+        Message::SinkFailed => "ADVERTENCIA: No se pudieron enviar las frases de contraseña a {}: {}",
+        Message::PassphraseEntropy => "La frase de contraseña tiene una entropía estimada de {} bits ({} palabras de una lista de {} palabras)",
+        Message::EachPassphraseEntropy => "Cada frase de contraseña tiene una entropía estimada de {} bits ({} palabras de una lista de {} palabras)",
+        Message::EachAnswerEntropy => "Cada respuesta tiene una entropía estimada de {} bits ({} palabras de una lista de {} palabras)",
+        Message::EntropyBreakdown => "Solo palabras: {} bits; con separadores y mayúsculas: {} bits",
+        Message::ListReadFailed => "Error al leer el archivo de la lista de palabras: {}",
+        Message::SeparatorRequired => "Hay que usar un separador o Title Case con una lista de palabras personalizada",
+        Message::TitleCaseCantMarkWords => "Title Case no puede marcar dónde empieza cada palabra con esta lista, porque sus palabras no tienen mayúsculas. Use un separador.",
+        Message::ClearAfterNeedsTerminal => "--clear-after solo funciona al imprimir las frases de contraseña en una terminal, ya que las borra de la pantalla",
+        Message::HeatmapNeedsColor => "--heatmap solo funciona al imprimir las frases de contraseña en una terminal que muestre colores (y sin NO_COLOR definido)",
+        Message::RestrictionFailed => "No se pudo generar una frase de contraseña que cumpla {} después de {} intentos",
+        Message::BatchCheckFailed => "No se pudo generar una frase de contraseña que cumpla {} después de {} intentos. Pida menos frases de contraseña o use una lista más larga.",
+        Message::Pow2DroppedWords => "ADVERTENCIA: --pow2 quitó {} de las {} palabras de esta lista y se quedó con las primeras {} en orden. Cada palabra vale ahora exactamente {} bits.",
+        Message::Pow2AlreadyPowerOfTwo => "Nota: Las {} palabras de esta lista ya son una potencia de dos, así que cada palabra vale exactamente {} bits",
+        Message::NoWordMeetsVowelRatio => "ADVERTENCIA: Ninguna palabra de esta lista tiene un {}% de vocales (el máximo es {}%), así que ninguna frase de contraseña puede cumplir --min-vowel-ratio {}",
+        Message::CompoundCollisions => "ADVERTENCIA: Sin nada que una las palabras de cada hueco de --compound, algunos compuestos se pueden formar con dos pares de palabras distintos de esta lista, como {}+{} y {}+{} ({} en total), así que las frases de contraseña valen un poco menos de lo estimado. Considera usar un --compound-joiner, como \"'\".",
+        Message::TokenPassphrases => "Nota: Cualquiera con el mismo token, desafío y configuración puede volver a generar estas frases de contraseña, así que guarda bien el token.",
+        Message::PassphraseEntropyFromLists => "La frase de contraseña tiene una entropía estimada de {} bits ({} palabras de {} listas)",
+        Message::EachPassphraseEntropyFromLists => "Cada frase de contraseña tiene una entropía estimada de {} bits ({} palabras de {} listas)",
+        Message::EachAnswerEntropyFromLists => "Cada respuesta tiene una entropía estimada de {} bits ({} palabras de {} listas)",
+    }
+}
+
+#[test]
+fn can_pick_a_language_from_a_locale() {
+    assert_eq!(Language::from_code("es"), Some(Language::Spanish));
+    assert_eq!(Language::from_code("es_MX.UTF-8"), Some(Language::Spanish));
+    assert_eq!(Language::from_code("en-GB"), Some(Language::English));
+    assert_eq!(Language::from_code("fr_FR.UTF-8"), None);
+    assert_eq!(Language::from_code("C"), None);
+}
+
+#[test]
+fn every_language_fills_in_the_same_things() {
+    for message in Message::ALL {
+        let placeholders = |language| template(*message, language).matches("{}").count();
+        assert_eq!(
+            placeholders(Language::English),
+            placeholders(Language::Spanish),
+            "{:?}",
+            message
+        );
+    }
+    assert_eq!(
+        fill(
+            english(Message::RestrictionFailed),
+            &[&"--min-word-chars", &1000]
+        ),
+        "Couldn't generate a passphrase that passes --min-word-chars after 1000 tries"
+    );
+}
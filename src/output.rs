@@ -0,0 +1,347 @@
+use crate::grapheme_length;
+use crossterm::style::{Color, ResetColor, SetForegroundColor};
+use std::time::Duration;
+
+/// The ways Phraze can print the passphrases it generates.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum OutputFormat {
+    /// One passphrase per line (the default)
+    Plain,
+    /// A YAML list of passphrases. Requires the `yaml` feature.
+    Yaml,
+}
+
+/// Where to print extra information, like entropy estimates, that isn't a passphrase.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum OutputStream {
+    /// Standard output, along with the passphrases
+    Stdout,
+    /// Standard error (the default), so the information shows up in the terminal but not in
+    /// piped or redirected output
+    Stderr,
+}
+
+/// How to round entropy estimates when printing them.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum EntropyRounding {
+    /// Round to the nearest value (the default)
+    Nearest,
+    /// Round down, so an estimate is never more than the real figure. Handy when the estimate
+    /// backs up a claim, like meeting a policy's minimum.
+    Floor,
+}
+
+/// Format an entropy estimate in bits, with `precision` decimal places, like "52.31".
+pub fn format_entropy(bits: f64, precision: usize, rounding: EntropyRounding) -> String {
+    let bits = match rounding {
+        EntropyRounding::Nearest => bits,
+        EntropyRounding::Floor => {
+            let scale = 10f64.powi(precision as i32);
+            (bits * scale).floor() / scale
+        }
+    };
+    format!("{:.*}", precision, bits)
+}
+
+/// Longest Phraze holds passphrases back with --stream before flushing them to whatever's
+/// reading them.
+pub const STREAM_FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Start of every line of the entropy report, when it's printed to standard output along with
+/// the passphrases, so that anything reading passphrases line by line can easily skip it.
+pub const STDOUT_REPORT_PREFIX: &str = "# entropy: ";
+
+impl OutputStream {
+    /// Print a line of the entropy report to this stream. On standard output, the line starts
+    /// with `STDOUT_REPORT_PREFIX`.
+    pub fn print_line(&self, line: &str) {
+        match self {
+            OutputStream::Stdout => println!("{}{}", STDOUT_REPORT_PREFIX, line),
+            OutputStream::Stderr => eprintln!("{}", line),
+        }
+    }
+}
+
+/// For `--no-tty-print`: refuse to print passphrases if standard output is an interactive
+/// terminal, where they could linger in the terminal's scrollback, unless they're going
+/// somewhere other than the screen (like a file, with `--output`).
+pub fn check_tty_print(stdout_is_terminal: bool, other_sink_chosen: bool) -> Result<(), String> {
+    if stdout_is_terminal && !other_sink_chosen {
+        Err("Refusing to print passphrases to a terminal, where they could be left in the scrollback (--no-tty-print). Pipe or redirect Phraze's output instead.".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// Add a passphrase's estimated entropy after it as a comment, for --annotate-entropy, like
+/// "correct-horse-battery-staple  # 52.0 bits". The annotation is only ever added when
+/// printing: passphrases are kept (and recorded) without it.
+pub fn annotate_entropy(passphrase: &str, entropy: f64, prefix: &str) -> String {
+    format!("{}  {} {:.1} bits", passphrase, prefix, entropy)
+}
+
+/// Pad each word of a passphrase with spaces to the width of its longest word, for --pad-words,
+/// so that words line up in columns, like "cat   -bird  -whale". `word_lengths` is the number of
+/// characters each word takes up in the passphrase, and every separator is `separator_length`
+/// characters. The last word isn't padded.
+///
+/// This is only for showing a passphrase: the padding isn't part of the passphrase itself.
+pub fn pad_words(passphrase: &str, word_lengths: &[usize], separator_length: usize) -> String {
+    let mut chars = passphrase.chars();
+    let mut words = vec![];
+    let mut separators = vec![];
+    for (i, length) in word_lengths.iter().enumerate() {
+        words.push(chars.by_ref().take(*length).collect::<String>());
+        if i != word_lengths.len() - 1 {
+            separators.push(chars.by_ref().take(separator_length).collect::<String>());
+        }
+    }
+    let width = words
+        .iter()
+        .map(|word| grapheme_length(word))
+        .max()
+        .unwrap_or(0);
+    let mut padded = String::new();
+    for (i, word) in words.iter().enumerate() {
+        padded += word;
+        if let Some(separator) = separators.get(i) {
+            padded += &" ".repeat(width - grapheme_length(word));
+            padded += separator;
+        }
+    }
+    padded
+}
+
+/// The colors --heatmap uses, from words that make up the least of a passphrase's length to
+/// words that make up the most, with what each means.
+pub const HEATMAP_COLORS: &[(Color, &str)] = &[
+    (Color::Blue, "shorter than average"),
+    (Color::Green, "about average"),
+    (Color::Yellow, "longer than average"),
+    (Color::Red, "much longer than average"),
+];
+
+/// Color each word of a passphrase by how much of the passphrase's length it makes up, for
+/// --heatmap: compared to the average word in the passphrase, shorter words are blue, and
+/// longer ones go through green and yellow to red. Since every word is picked with the same
+/// chance, words all add the same entropy, so this is only a picture of where the length comes
+/// from. Separators aren't colored. `word_lengths` and `separator_length` work like they do for
+/// `pad_words`. Lengths are compared as they look, in graphemes, so an emoji made of several
+/// characters (like 🏳️‍🌈) counts as one.
+pub fn heatmap(passphrase: &str, word_lengths: &[usize], separator_length: usize) -> String {
+    let mut chars = passphrase.chars();
+    let mut words = vec![];
+    let mut separators = vec![];
+    for (i, length) in word_lengths.iter().enumerate() {
+        words.push(chars.by_ref().take(*length).collect::<String>());
+        if i != word_lengths.len() - 1 {
+            separators.push(chars.by_ref().take(separator_length).collect::<String>());
+        }
+    }
+    let lengths: Vec<usize> = words.iter().map(|word| grapheme_length(word)).collect();
+    let average_length = lengths.iter().sum::<usize>() as f64 / lengths.len() as f64;
+    let mut colored = String::new();
+    for (i, (word, length)) in words.iter().zip(lengths).enumerate() {
+        // Each level is half the average length wide, and "about average" is from 0.75 to
+        // 1.25 times the average
+        let heat = ((length as f64 / average_length - 0.75) / 0.5)
+            .ceil()
+            .max(0.0) as usize;
+        let (color, _) = HEATMAP_COLORS[heat.min(HEATMAP_COLORS.len() - 1)];
+        colored += &format!("{}{}{}", SetForegroundColor(color), word, ResetColor);
+        if let Some(separator) = separators.get(i) {
+            colored += separator;
+        }
+    }
+    colored
+}
+
+/// Split a passphrase into its words, each with the separator after it (if there is one), for
+/// --reveal-delay. `word_lengths` and `separator_length` work like they do for `pad_words`.
+pub fn passphrase_pieces(
+    passphrase: &str,
+    word_lengths: &[usize],
+    separator_length: usize,
+) -> Vec<String> {
+    let mut chars = passphrase.chars();
+    word_lengths
+        .iter()
+        .enumerate()
+        .map(|(i, length)| {
+            let mut piece: String = chars.by_ref().take(*length).collect();
+            if i != word_lengths.len() - 1 {
+                piece.extend(chars.by_ref().take(separator_length));
+            }
+            piece
+        })
+        .collect()
+}
+
+/// Quote a value so a POSIX shell (like bash or zsh) reads it back exactly, for
+/// --print-command. Values made only of characters that are safe in a shell word are left as
+/// they are; anything else is wrapped in single quotes.
+pub fn shell_quote(value: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c);
+    if !value.is_empty() && value.chars().all(is_safe) {
+        value.to_string()
+    } else {
+        // A single quote can't appear inside single quotes, so end the quoted part, add an
+        // escaped quote, and start a new quoted part
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// Turn a batch of generated passphrases into the text we'll print, in the requested format.
+/// If there are `labels` (one for each passphrase, in order), a YAML batch is a map of labels to
+/// passphrases rather than a list. Plain passphrases don't have labels.
+pub fn format_passphrases<T: AsRef<str>>(
+    passphrases: &[T],
+    labels: &[String],
+    format: OutputFormat,
+) -> Result<String, String> {
+    match format {
+        OutputFormat::Plain => Ok(passphrases
+            .iter()
+            .map(|passphrase| passphrase.as_ref())
+            .collect::<Vec<&str>>()
+            .join("\n")),
+        OutputFormat::Yaml => format_yaml(passphrases, labels),
+    }
+}
+
+/// Use serde_yaml to write passphrases as a YAML list, or a map if they have labels. serde_yaml
+/// takes care of quoting any passphrases (and labels) that contain characters YAML considers
+/// special (for example, when using `_s` separators), so they always round-trip as plain
+/// strings.
+#[cfg(feature = "yaml")]
+fn format_yaml<T: AsRef<str>>(passphrases: &[T], labels: &[String]) -> Result<String, String> {
+    let passphrases: Vec<&str> = passphrases
+        .iter()
+        .map(|passphrase| passphrase.as_ref())
+        .collect();
+    let yaml = if labels.is_empty() {
+        serde_yaml::to_string(&passphrases)
+    } else {
+        if labels.len() != passphrases.len() {
+            return Err(format!(
+                "Can't label {} passphrases with {} labels",
+                passphrases.len(),
+                labels.len()
+            ));
+        }
+        // A Mapping keeps its keys in the order they're added, so labels stay in order
+        let map: serde_yaml::Mapping = labels
+            .iter()
+            .zip(passphrases)
+            .map(|(label, passphrase)| (label.as_str().into(), passphrase.into()))
+            .collect();
+        serde_yaml::to_string(&map)
+    };
+    match yaml {
+        Ok(yaml) => Ok(yaml.trim_end().to_string()),
+        Err(e) => Err(format!("Error writing passphrases as YAML: {}", e)),
+    }
+}
+
+#[cfg(not(feature = "yaml"))]
+fn format_yaml<T: AsRef<str>>(_passphrases: &[T], _labels: &[String]) -> Result<String, String> {
+    Err("This version of Phraze was built without YAML support (the `yaml` feature)".to_string())
+}
+
+#[test]
+fn can_annotate_a_passphrase_with_its_entropy() {
+    assert_eq!(
+        annotate_entropy("correct-horse-battery-staple", 52.0, "#"),
+        "correct-horse-battery-staple  # 52.0 bits"
+    );
+    assert_eq!(annotate_entropy("a-b", 92.44, "//"), "a-b  // 92.4 bits");
+}
+
+#[test]
+fn can_pad_words_to_line_up() {
+    assert_eq!(
+        pad_words("cat-bird-whale", &[3, 4, 5], 1),
+        "cat  -bird -whale"
+    );
+    assert_eq!(pad_words("Cat1Bird", &[3, 4], 1), "Cat 1Bird");
+    assert_eq!(pad_words("élan::ox", &[4, 2], 2), "élan::ox");
+    assert_eq!(pad_words("ox::élan", &[2, 4], 2), "ox  ::élan");
+    assert_eq!(pad_words("alone", &[5], 1), "alone");
+}
+
+#[test]
+fn can_round_entropy_estimates() {
+    use EntropyRounding::*;
+    assert_eq!(format_entropy(90.999, 2, Nearest), "91.00");
+    assert_eq!(format_entropy(90.999, 2, Floor), "90.99");
+    assert_eq!(format_entropy(90.999, 0, Floor), "90");
+    assert_eq!(format_entropy(90.5, 0, Nearest), "90");
+    assert_eq!(format_entropy(91.0, 0, Floor), "91");
+    assert_eq!(format_entropy(51.699250014, 4, Nearest), "51.6993");
+}
+
+#[test]
+fn can_color_words_by_their_share_of_the_length() {
+    let colored = heatmap("ox-bird-whale-hippopotamus", &[2, 4, 5, 12], 1);
+    let colors: Vec<String> = [Color::Blue, Color::Blue, Color::Green, Color::Red]
+        .iter()
+        .map(|color| SetForegroundColor(*color).to_string())
+        .collect();
+    assert_eq!(
+        colored,
+        format!(
+            "{}ox{reset}-{}bird{reset}-{}whale{reset}-{}hippopotamus{reset}",
+            colors[0],
+            colors[1],
+            colors[2],
+            colors[3],
+            reset = ResetColor
+        )
+    );
+}
+
+#[test]
+fn can_quote_values_for_a_shell() {
+    assert_eq!(shell_quote("words.txt"), "words.txt");
+    assert_eq!(shell_quote("-"), "-");
+    assert_eq!(shell_quote(""), "''");
+    assert_eq!(shell_quote(" "), "' '");
+    assert_eq!(shell_quote("my list.txt"), "'my list.txt'");
+    assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    assert_eq!(shell_quote("$HOME"), "'$HOME'");
+}
+
+#[test]
+fn refuses_to_print_to_a_terminal() {
+    assert!(check_tty_print(true, false).is_err());
+    assert!(check_tty_print(true, true).is_ok());
+    assert!(check_tty_print(false, false).is_ok());
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn can_quote_special_characters_in_yaml() {
+    let passphrases = vec![
+        "plain-words-here".to_string(),
+        "#starts-with-a-symbol".to_string(),
+        "key: value".to_string(),
+        "[brackets]".to_string(),
+    ];
+    let yaml = format_passphrases(&passphrases, &[], OutputFormat::Yaml).unwrap();
+    let round_tripped: Vec<String> = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(round_tripped, passphrases);
+
+    let labels = vec!["db-password".to_string(), "api: key".to_string()];
+    let yaml = format_passphrases(&passphrases[2..], &labels, OutputFormat::Yaml).unwrap();
+    assert_eq!(yaml, "db-password: 'key: value'\n'api: key': '[brackets]'");
+    assert!(format_passphrases(&passphrases, &labels, OutputFormat::Yaml).is_err());
+}
+
+#[test]
+fn can_split_a_passphrase_into_pieces() {
+    assert_eq!(
+        passphrase_pieces("Ox1Bird2Whale", &[2, 4, 5], 1),
+        ["Ox1", "Bird2", "Whale"]
+    );
+    assert_eq!(passphrase_pieces("caféau", &[4, 2], 0), ["café", "au"]);
+}
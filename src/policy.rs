@@ -0,0 +1,265 @@
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// How long a fetched policy is reused, in seconds, before it's fetched again. Short enough that
+/// a change to the central policy takes effect quickly.
+pub const POLICY_CACHE_SECONDS: u64 = 300;
+
+/// Most bytes we'll read from a policy URL. Real policies are a few dozen bytes.
+#[cfg(feature = "network")]
+const MAX_POLICY_BYTES: u64 = 64 * 1024;
+
+/// A centrally defined password policy, like an organization might publish for everyone's
+/// passphrases to follow.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Policy {
+    /// Fewest bits of entropy a passphrase can have
+    pub minimum_entropy: Option<usize>,
+    /// Lists passphrases can be made from, by the names `--list` takes (like "l" or "e"), plus
+    /// "custom" for any custom list. If this is None, any list is allowed.
+    pub allowed_lists: Option<Vec<String>>,
+}
+
+impl Policy {
+    /// Make sure the policy allows a list, given by the name `--list` takes, or "custom".
+    pub fn check_list_allowed(&self, list: &str) -> Result<(), String> {
+        match &self.allowed_lists {
+            Some(allowed_lists) if !allowed_lists.iter().any(|allowed| allowed == list) => {
+                Err(format!(
+                    "Password policy doesn't allow list '{}' (it allows: {})",
+                    list,
+                    allowed_lists.join(", ")
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Parse a policy from JSON, like `{"min_entropy": 100, "allowed_lists": ["l", "e"]}`. Both keys
+/// are optional, and any other keys are ignored, so a policy can carry settings for other tools
+/// too.
+#[cfg(feature = "network")]
+pub fn parse_policy(json: &str) -> Result<Policy, String> {
+    let value: serde_json::Value = match serde_json::from_str(json) {
+        Ok(value) => value,
+        Err(e) => return Err(format!("Password policy isn't valid JSON: {}", e)),
+    };
+    let minimum_entropy = match value.get("min_entropy") {
+        None => None,
+        Some(min_entropy) => match min_entropy.as_u64() {
+            Some(min_entropy) if min_entropy > 0 => Some(min_entropy as usize),
+            _ => {
+                return Err(format!(
+                    "Password policy's min_entropy ({}) should be a positive whole number",
+                    min_entropy
+                ))
+            }
+        },
+    };
+    let allowed_lists = match value.get("allowed_lists") {
+        None => None,
+        Some(allowed_lists) => {
+            let lists: Option<Vec<String>> = allowed_lists.as_array().and_then(|lists| {
+                lists
+                    .iter()
+                    .map(|list| list.as_str().map(|list| list.to_lowercase()))
+                    .collect()
+            });
+            match lists {
+                Some(lists) => Some(lists),
+                None => {
+                    return Err(
+                        "Password policy's allowed_lists should be a list of list names"
+                            .to_string(),
+                    )
+                }
+            }
+        }
+    };
+    Ok(Policy {
+        minimum_entropy,
+        allowed_lists,
+    })
+}
+
+#[cfg(not(feature = "network"))]
+pub fn parse_policy(_json: &str) -> Result<Policy, String> {
+    Err(NO_NETWORK_SUPPORT.to_string())
+}
+
+/// Get the policy at a URL, reusing a copy fetched in the last `POLICY_CACHE_SECONDS` if there
+/// is one. Copies are cached in the user's own cache directory (`$XDG_CACHE_HOME/phraze`, or
+/// `~/.cache/phraze`), so other users can't swap in a weaker policy. Any problem fetching or
+/// parsing the policy is an error.
+pub fn load_policy(url: &str) -> Result<Policy, String> {
+    let cache_path = policy_cache_path(url);
+    if let Some(ref cache_path) = cache_path {
+        if let Some(json) = read_fresh_cache(cache_path) {
+            if let Ok(policy) = parse_policy(&json) {
+                return Ok(policy);
+            }
+        }
+    }
+    let json = fetch_url(url)?;
+    let policy = parse_policy(&json)?;
+    // Caching is only to save a round trip, so it's fine if it doesn't work
+    if let Some(cache_path) = cache_path {
+        if let Some(cache_dir) = cache_path.parent() {
+            let _ = fs::create_dir_all(cache_dir);
+        }
+        let _ = fs::write(cache_path, json);
+    }
+    Ok(policy)
+}
+
+/// Where a URL's policy is cached, named after a hash of the URL, if the user has a cache
+/// directory.
+fn policy_cache_path(url: &str) -> Option<PathBuf> {
+    let cache_dir = match std::env::var_os("XDG_CACHE_HOME") {
+        Some(cache_home) if !cache_home.is_empty() => PathBuf::from(cache_home),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".cache"),
+    };
+    let hash: String = Sha256::digest(url.as_bytes())[..8]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    Some(
+        cache_dir
+            .join("phraze")
+            .join(format!("policy-{}.json", hash)),
+    )
+}
+
+/// Read a cached policy, if it was saved recently enough.
+fn read_fresh_cache(cache_path: &PathBuf) -> Option<String> {
+    let age = SystemTime::now()
+        .duration_since(fs::metadata(cache_path).ok()?.modified().ok()?)
+        .ok()?;
+    if age > Duration::from_secs(POLICY_CACHE_SECONDS) {
+        return None;
+    }
+    fs::read_to_string(cache_path).ok()
+}
+
+/// Fetch a plain http:// URL with a bare-bones HTTP/1.0 GET request (so the server won't use
+/// chunked encoding), returning the body if the server answers 200 OK. There's no TLS support,
+/// so https:// URLs are an error rather than being quietly fetched in the clear.
+#[cfg(feature = "network")]
+fn fetch_url(url: &str) -> Result<String, String> {
+    use std::io::{Read, Write};
+    use std::net::{TcpStream, ToSocketAddrs};
+
+    let rest = match url.strip_prefix("http://") {
+        Some(rest) => rest,
+        None if url.starts_with("https://") => {
+            return Err("Phraze can't fetch https:// policy URLs, since it has no TLS support. Serve the policy over http:// from a trusted host (like localhost, behind a proxy that handles HTTPS).".to_string())
+        }
+        None => return Err(format!("Policy URL '{}' should start with http://", url)),
+    };
+    let (host_and_port, path) = match rest.find('/') {
+        Some(slash) => rest.split_at(slash),
+        None => (rest, "/"),
+    };
+    let address = if host_and_port.contains(':') {
+        host_and_port.to_string()
+    } else {
+        format!("{}:80", host_and_port)
+    };
+    let timeout = Duration::from_secs(10);
+    let error = |e: std::io::Error| format!("Error fetching password policy from {}: {}", url, e);
+    let socket_address = match address.to_socket_addrs().map_err(error)?.next() {
+        Some(socket_address) => socket_address,
+        None => return Err(format!("Couldn't find the host in policy URL '{}'", url)),
+    };
+    let mut stream = TcpStream::connect_timeout(&socket_address, timeout).map_err(error)?;
+    stream.set_read_timeout(Some(timeout)).map_err(error)?;
+    stream.set_write_timeout(Some(timeout)).map_err(error)?;
+    write!(
+        stream,
+        "GET {} HTTP/1.0\r\nHost: {}\r\nUser-Agent: phraze/{}\r\nAccept: application/json\r\n\r\n",
+        path,
+        host_and_port,
+        env!("CARGO_PKG_VERSION")
+    )
+    .map_err(error)?;
+    let mut response = vec![];
+    stream
+        .take(MAX_POLICY_BYTES)
+        .read_to_end(&mut response)
+        .map_err(error)?;
+
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = match response.split_once("\r\n\r\n") {
+        Some(parts) => parts,
+        None => return Err(format!("Policy server at {} sent a broken response", url)),
+    };
+    let status_line = head.lines().next().unwrap_or("");
+    match status_line.split_whitespace().nth(1) {
+        Some("200") => Ok(body.to_string()),
+        _ => Err(format!(
+            "Policy server at {} answered '{}' instead of 200 OK",
+            url, status_line
+        )),
+    }
+}
+
+#[cfg(not(feature = "network"))]
+fn fetch_url(_url: &str) -> Result<String, String> {
+    Err(NO_NETWORK_SUPPORT.to_string())
+}
+
+#[cfg(not(feature = "network"))]
+const NO_NETWORK_SUPPORT: &str =
+    "This version of Phraze was built without network support (the `network` feature)";
+
+#[test]
+fn can_check_a_policy_allows_a_list() {
+    let policy = Policy {
+        minimum_entropy: Some(100),
+        allowed_lists: Some(vec!["l".to_string(), "custom".to_string()]),
+    };
+    assert!(policy.check_list_allowed("l").is_ok());
+    assert!(policy.check_list_allowed("custom").is_ok());
+    assert!(policy.check_list_allowed("s").is_err());
+    assert!(Policy::default().check_list_allowed("s").is_ok());
+}
+
+#[cfg(feature = "network")]
+#[test]
+fn can_parse_and_fetch_a_policy() {
+    let policy = parse_policy(r#"{"min_entropy": 100, "allowed_lists": ["L", "e"]}"#).unwrap();
+    assert_eq!(policy.minimum_entropy, Some(100));
+    assert_eq!(
+        policy.allowed_lists,
+        Some(vec!["l".to_string(), "e".to_string()])
+    );
+    assert_eq!(
+        parse_policy(r#"{"owner": "it"}"#).unwrap(),
+        Policy::default()
+    );
+    assert!(parse_policy(r#"{"min_entropy": -5}"#).is_err());
+    assert!(parse_policy(r#"{"allowed_lists": "l"}"#).is_err());
+    assert!(parse_policy("not json").is_err());
+
+    // Serve one policy from a local server
+    use std::io::{Read, Write};
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/policy.json", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = [0; 1024];
+        let read = stream.read(&mut request).unwrap();
+        assert!(request[..read].starts_with(b"GET /policy.json HTTP/1.0\r\n"));
+        stream
+            .write_all(
+                b"HTTP/1.0 200 OK\r\nContent-Type: application/json\r\n\r\n{\"min_entropy\": 90}",
+            )
+            .unwrap();
+    });
+    assert_eq!(fetch_url(&url).unwrap(), "{\"min_entropy\": 90}");
+    server.join().unwrap();
+    assert!(fetch_url("https://example.com/policy.json").is_err());
+}
@@ -0,0 +1,85 @@
+use crate::choose_word;
+use rand::seq::index::sample;
+use rand::Rng;
+
+/// Add `required_count` words from a second, themed list to a passphrase's words, at random
+/// positions. Every possible set of positions is equally likely. The words from the main list
+/// keep their order relative to each other.
+pub fn insert_required_words<'a, T: AsRef<str>>(
+    rng: &mut impl Rng,
+    words: &[&'a str],
+    required_list: &'a [T],
+    required_count: usize,
+) -> Vec<&'a str> {
+    let number_of_words = words.len() + required_count;
+    let mut required_positions = vec![false; number_of_words];
+    for position in sample(rng, number_of_words, required_count) {
+        required_positions[position] = true;
+    }
+    let mut main_words = words.iter();
+    required_positions
+        .iter()
+        .map(|required| {
+            if *required {
+                choose_word(rng, required_list)
+            } else {
+                // There's a main word for every position that isn't a required one
+                main_words.next().unwrap()
+            }
+        })
+        .collect()
+}
+
+/// Put a word of the user's own (like the name of their hometown) into a passphrase's words, at
+/// a random position: before the first word, between any two, or after the last.
+pub fn inject_word<'a>(rng: &mut impl Rng, words: &[&'a str], word: &'a str) -> Vec<&'a str> {
+    let position = rng.gen_range(0..=words.len());
+    let mut injected = words.to_vec();
+    injected.insert(position, word);
+    injected
+}
+
+/// Entropy, in bits, that injecting a word of the user's own into a passphrase of
+/// `number_of_words` words adds. The word itself adds none, since the user knows it (and so
+/// might anyone who knows them), but which of the `number_of_words + 1` positions it went in
+/// adds a little.
+pub fn injected_word_entropy(number_of_words: usize) -> f64 {
+    ((number_of_words + 1) as f64).log2()
+}
+
+/// Entropy, in bits, that the required words add to a passphrase. We only count the words
+/// themselves, not their positions: the themed list may share words with the main list, in
+/// which case you can't always tell which words were the required ones.
+pub fn required_words_entropy(required_count: usize, required_list_length: usize) -> f64 {
+    (required_list_length as f64).log2() * required_count as f64
+}
+
+/// Work out how many words a passphrase needs to reach a minimum entropy, when `required_count`
+/// of them come from the themed list and the rest from the main list, each worth
+/// `entropy_per_word` bits (log2 of the main list's length, unless its words aren't all equally
+/// likely). The result always has room for all of the required words.
+pub fn number_of_words_needed_with_required(
+    minimum_entropy: usize,
+    entropy_per_word: f64,
+    required_count: usize,
+    required_list_length: usize,
+) -> usize {
+    let remaining_entropy =
+        minimum_entropy as f64 - required_words_entropy(required_count, required_list_length);
+    if remaining_entropy <= 0.0 {
+        return required_count;
+    }
+    required_count + (remaining_entropy / entropy_per_word).ceil() as usize
+}
+
+/// Make sure a passphrase of the given number of words has room for the required words.
+pub fn check_required_count(number_of_words: usize, required_count: usize) -> Result<(), String> {
+    if required_count > number_of_words {
+        Err(format!(
+            "Can't fit {} required words in a passphrase of {} words",
+            required_count, number_of_words
+        ))
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,137 @@
+use crate::grapheme_length;
+use unicode_normalization::UnicodeNormalization;
+
+/// Maximum number of passphrases we'll generate while looking for one that passes all of the
+/// user's restrictions (like --no-keyboard-runs or --max-repeated-chars), before giving up with
+/// an error. Each rejected passphrase makes the ones we do print very slightly more predictable,
+/// but restrictions that would need anywhere near this many tries are a sign that the settings
+/// don't work together.
+pub const REJECTION_MAX_TRIES: usize = 1000;
+
+/// Check that a batch of passphrases can all start with different words (for
+/// --distinct-first-words), given how many different words could start a passphrase. There's no
+/// way around a list that's too short, so this is an error rather than something to retry.
+pub fn check_distinct_first_words(
+    n_passphrases: usize,
+    possible_first_words: usize,
+) -> Result<(), String> {
+    if n_passphrases > possible_first_words {
+        return Err(format!(
+            "Can't give {} passphrases different first words, since only {} different words can start a passphrase",
+            n_passphrases, possible_first_words
+        ));
+    }
+    Ok(())
+}
+
+/// A few of the most common passwords and sequences, which password crackers try first. With
+/// no separator, words can run together to spell one of these (like "pass" and "words"), so
+/// --no-common-substrings rejects any passphrase containing one. Checks ignore case.
+pub const COMMON_SUBSTRINGS: &[&str] = &[
+    "password", "passw0rd", "123456", "1234", "qwerty", "asdf", "letmein", "welcome", "admin",
+    "login", "iloveyou", "monkey", "dragon", "master", "shadow", "sunshine", "princess",
+    "football", "baseball", "superman", "trustno1", "abc123", "111111", "000000",
+];
+
+/// Find the first of the `COMMON_SUBSTRINGS` that appears anywhere in a passphrase, ignoring
+/// case.
+pub fn find_common_substring(passphrase: &str) -> Option<&'static str> {
+    let passphrase = passphrase.to_lowercase();
+    COMMON_SUBSTRINGS
+        .iter()
+        .find(|common| passphrase.contains(*common))
+        .copied()
+}
+
+/// Length of the longest run of neighboring words that are all the same length (in characters),
+/// like 3 for "oak-elm-ash-birch". Passphrases with long runs like this can look monotonous.
+pub fn longest_same_length_run(words: &[&str]) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    let mut previous = None;
+    for length in words.iter().map(|word| grapheme_length(word)) {
+        if Some(length) == previous {
+            current += 1;
+        } else {
+            current = 1;
+            previous = Some(length);
+        }
+        longest = longest.max(current);
+    }
+    longest
+}
+
+/// How many letters a passphrase's words have in total, for --min-word-chars. Only alphabetic
+/// characters count, so separators, digits and punctuation in words (like the hyphen in
+/// "drop-down") don't, and neither do combining accents: "café" has 4 letters, however it's
+/// written.
+pub fn word_character_count(words: &[&str]) -> usize {
+    words
+        .iter()
+        .flat_map(|word| word.chars())
+        .filter(|c| c.is_alphabetic())
+        .count()
+}
+
+/// What share of a passphrase's letters are vowels, for --min-vowel-ratio: 0.5 means half of
+/// them are. Like `word_character_count`, only letters count. Vowels are a, e, i, o and u, with
+/// or without accents ("é" is a vowel, however it's written), and "y" is a consonant. Words
+/// with no letters at all have a ratio of 0.
+pub fn vowel_ratio(words: &[&str]) -> f64 {
+    let letters = word_character_count(words);
+    if letters == 0 {
+        return 0.0;
+    }
+    let vowels = words
+        .iter()
+        .flat_map(|word| word.nfd())
+        .filter(|c| "aeiouAEIOU".contains(*c))
+        .count();
+    vowels as f64 / letters as f64
+}
+
+/// The highest vowel ratio any passphrase made from this list can have, which is the ratio of
+/// its most vowel-heavy word. Passphrases are a mix of words, so they can't beat their best word.
+pub fn highest_vowel_ratio(list: &[&str]) -> f64 {
+    list.iter()
+        .map(|word| vowel_ratio(&[word]))
+        .fold(0.0, f64::max)
+}
+
+/// Length of the longest run of identical characters in a string, like 3 for "seeeing", including
+/// runs that cross from one word to the next (like "freeeels", with no separator).
+pub fn longest_repeated_run(s: &str) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    let mut previous = None;
+    for c in s.chars() {
+        if Some(c) == previous {
+            current += 1;
+        } else {
+            current = 1;
+            previous = Some(c);
+        }
+        longest = longest.max(current);
+    }
+    longest
+}
+
+#[test]
+fn can_find_runs_of_repeated_characters() {
+    assert_eq!(longest_repeated_run(""), 0);
+    assert_eq!(longest_repeated_run("abc"), 1);
+    assert_eq!(longest_repeated_run("coffee"), 2);
+    assert_eq!(longest_repeated_run("seeeing"), 3);
+    assert_eq!(longest_repeated_run("free-eels"), 2);
+    assert_eq!(longest_repeated_run("freeeels"), 4);
+    // Different cases aren't identical
+    assert_eq!(longest_repeated_run("aAa"), 1);
+    assert_eq!(longest_repeated_run("ééé"), 3);
+}
+
+#[test]
+fn can_find_common_substrings() {
+    assert_eq!(find_common_substring("compass-wordy"), None);
+    assert_eq!(find_common_substring("compasswordy"), Some("password"));
+    assert_eq!(find_common_substring("LetMeInside"), Some("letmein"));
+}
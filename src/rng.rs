@@ -0,0 +1,175 @@
+use crate::{try_generate_passphrase_with_rng, PassphraseConfig};
+use rand::rngs::OsRng;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
+
+/// Confirm that the operating system's secure random number source is available by asking it for
+/// a handful of bytes. Phraze's default RNG (rand's `thread_rng`) is seeded from this same source,
+/// via the getrandom crate.
+///
+/// The only sources that count as acceptable are the ones getrandom uses by default on each
+/// platform: the `getrandom` syscall on Linux and Android (falling back to reading
+/// `/dev/urandom` only after `/dev/random` has been successfully polled, i.e. once the kernel
+/// pool is initialized), `getentropy` on macOS and OpenBSD, `BCryptGenRandom` on Windows, and so
+/// on. Phraze does NOT enable getrandom's `rdrand` or `custom` features, so no CPU-instruction or
+/// user-supplied source can be silently substituted for the OS.
+pub fn check_os_rng() -> Result<(), String> {
+    let mut probe = [0u8; 32];
+    match OsRng.try_fill_bytes(&mut probe) {
+        Ok(()) => Ok(()),
+        Err(e) => Err(format!(
+            "Operating system's secure random number source is unavailable: {}",
+            e
+        )),
+    }
+}
+
+/// Create the deterministic RNG used for the passphrase at a given index of a seeded batch.
+///
+/// Each passphrase in a seeded batch gets its own ChaCha20 stream (the stream number is the
+/// passphrase's index), starting from the beginning of that stream. This means we can jump
+/// straight to any passphrase in the batch without generating the ones before it, so separate
+/// machines can each generate their own slice of the same batch.
+///
+/// Note that a seeded passphrase can never be more secret than the seed it came from. A 64-bit
+/// seed means at most 64 bits of entropy, no matter how many words are in the passphrase.
+pub fn seeded_rng(seed: u64, index: u64) -> ChaCha20Rng {
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+    rng.set_stream(index);
+    rng.set_word_pos(0);
+    rng
+}
+
+/// Like `seeded_rng`, but with a salt mixed into the seed, so that one seed can be shared by
+/// several applications without any of them getting the same passphrases. The salt is meant to
+/// be a constant for each application, like its name.
+///
+/// The seed and salt are hashed together with SHA-256 to make the full 256-bit ChaCha20 seed,
+/// so any change to the salt gives an unrelated batch. Salting doesn't add any entropy, though:
+/// the passphrases are still only as secret as the 64-bit seed.
+pub fn salted_seeded_rng(seed: u64, salt: &str, index: u64) -> ChaCha20Rng {
+    let mut hasher = Sha256::new();
+    hasher.update(seed.to_le_bytes());
+    hasher.update(salt.as_bytes());
+    let mut rng = ChaCha20Rng::from_seed(hasher.finalize().into());
+    rng.set_stream(index);
+    rng.set_word_pos(0);
+    rng
+}
+
+/// Label hashed in with a hardware token's response by `token_rng`, so that a response that's
+/// also used for something else gives unrelated bytes here.
+const TOKEN_RESPONSE_LABEL: &[u8] = b"phraze hardware token response v1";
+
+/// Create the deterministic RNG for the passphrase at a given index of a batch made from a
+/// hardware token's HMAC response to a challenge (see `token::read_token_response`).
+///
+/// Like with `salted_seeded_rng`, the response is hashed with SHA-256 to make the full 256-bit
+/// ChaCha20 seed, and each passphrase gets its own stream. Words are then picked from the
+/// stream with rand's uniform sampling, which rejects values that would make some words more
+/// likely than others. Unlike a 64-bit seed, the response can have as much entropy as the
+/// token's secret key gives it (160 bits, for an HMAC-SHA1 response), so the passphrases can be
+/// as strong as their length suggests, up to that.
+pub fn token_rng(response: &[u8], index: u64) -> ChaCha20Rng {
+    let mut hasher = Sha256::new();
+    hasher.update(TOKEN_RESPONSE_LABEL);
+    hasher.update(response);
+    let mut rng = ChaCha20Rng::from_seed(hasher.finalize().into());
+    rng.set_stream(index);
+    rng.set_word_pos(0);
+    rng
+}
+
+/// Where Phraze's randomness comes from for a run, for --rng-info.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RngSource {
+    /// rand's `thread_rng`, the default: a cryptographically secure RNG seeded from the
+    /// operating system's secure random source
+    ThreadRng,
+    /// The deterministic RNG made by `seeded_rng` or `salted_seeded_rng`
+    Seeded {
+        seed: u64,
+        salt: Option<String>,
+        first_index: u64,
+    },
+    /// The deterministic RNG made by `token_rng` from a hardware token's response
+    Token { response: Vec<u8> },
+}
+
+impl RngSource {
+    /// Work out which RNG to use from the user's options.
+    pub fn choose(seed: Option<u64>, salt: Option<&str>, first_index: u64) -> Self {
+        match seed {
+            Some(seed) => RngSource::Seeded {
+                seed,
+                salt: salt.map(str::to_string),
+                first_index,
+            },
+            None => RngSource::ThreadRng,
+        }
+    }
+
+    /// Describe this RNG for an audit log. This never includes the seed, the salt or a token's
+    /// response, or anything worked out from them (even a hash of a 64-bit seed would let someone
+    /// check guesses of the seed against it).
+    pub fn describe(&self) -> String {
+        match self {
+            RngSource::ThreadRng => "RNG: thread_rng (ChaCha12, a cryptographically secure RNG, seeded from the operating system's secure random source)".to_string(),
+            RngSource::Seeded {
+                salt, first_index, ..
+            } => format!(
+                "RNG: deterministic ChaCha20 from a user-given seed ({}, starting at index {}). Not suitable for real credentials.",
+                if salt.is_some() { "salted" } else { "unsalted" },
+                first_index
+            ),
+            RngSource::Token { .. } => "RNG: deterministic ChaCha20 keyed by a hardware token's response to a challenge. Anyone with the token, the challenge and the same settings can make the same passphrases.".to_string(),
+        }
+    }
+}
+
+/// Generate the passphrase at a given index of the deterministic batch created from a seed.
+/// Calling this for index 0, 1, 2, ... gives the same passphrases, in the same order, as
+/// Phraze's CLI does with `--seed`, as long as the CLI was only given what a `PassphraseConfig`
+/// can hold: a list (`--list` or `--custom-list`), a number of words (`--words`, or a minimum
+/// entropy that works out to the same number), `--sep` and `--title-case`. Anything else, like
+/// restrictions that throw out passphrases and try again, or options that change passphrases
+/// after the words are picked, isn't in the config, so it can't be reproduced here.
+///
+/// Returns an error if the list is empty, rather than panicking.
+pub fn nth_passphrase<T: AsRef<str>>(
+    seed: u64,
+    index: u64,
+    config: &PassphraseConfig,
+    list: &[T],
+) -> Result<String, String> {
+    let mut rng = seeded_rng(seed, index);
+    try_generate_passphrase_with_rng(
+        &mut rng,
+        config.number_of_words,
+        &config.separator,
+        config.title_case,
+        list,
+    )
+}
+
+#[test]
+fn can_use_os_rng() {
+    assert!(check_os_rng().is_ok());
+}
+
+#[test]
+fn can_report_which_rng_is_in_use() {
+    let default = RngSource::choose(None, None, 0);
+    assert_eq!(default, RngSource::ThreadRng);
+    assert!(default.describe().contains("thread_rng"));
+
+    let seeded = RngSource::choose(Some(987654321), Some("mail"), 5);
+    let description = seeded.describe();
+    assert!(description.contains("deterministic"));
+    assert!(description.contains("(salted"));
+    assert!(description.contains("index 5"));
+    // Neither the seed nor the salt is given away
+    assert!(!description.contains("987654321"));
+    assert!(!description.contains("mail"));
+}
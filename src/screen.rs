@@ -0,0 +1,161 @@
+use crate::grapheme_length;
+use crossterm::cursor::{MoveToColumn, MoveUp};
+use crossterm::event::{poll, read, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, size, Clear, ClearType};
+use std::io::{stdout, Write};
+use std::time::{Duration, Instant};
+
+/// Why `show_then_clear` cleared the screen.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum ClearReason {
+    /// The time was up
+    TimedOut,
+    /// The user pressed Enter
+    Enter,
+    /// The user pressed Ctrl-C. The text is still cleared first.
+    Interrupted,
+}
+
+/// Print text to the terminal, then wait `seconds` (or until the user presses Enter or Ctrl-C)
+/// and erase it again, along with a line saying how to clear it sooner. Standard output must be
+/// an interactive terminal.
+///
+/// This only clears what's on the screen: the text may still be in the terminal's scrollback,
+/// or a log of the session.
+pub fn show_then_clear(text: &str, seconds: u64) -> Result<ClearReason, String> {
+    let prompt = format!(
+        "(Clearing in {} second{}. Press Enter to clear now.)",
+        seconds,
+        if seconds == 1 { "" } else { "s" }
+    );
+    let columns = match size() {
+        Ok((columns, _rows)) => columns as usize,
+        Err(e) => return Err(format!("Couldn't get the terminal's size: {}", e)),
+    };
+    let rows: usize = text
+        .lines()
+        .chain(std::iter::once(prompt.as_str()))
+        .map(|line| rows_taken(line, columns))
+        .sum();
+
+    let mut out = stdout();
+    let print = writeln!(out, "{}{}", text, prompt).and_then(|_| out.flush());
+    if let Err(e) = print {
+        return Err(format!("Couldn't print to the terminal: {}", e));
+    }
+    // In raw mode, Ctrl-C comes in as a key press rather than stopping Phraze, so we get to
+    // clear the screen first
+    if let Err(e) = enable_raw_mode() {
+        let _ = clear_rows(rows);
+        return Err(format!("Couldn't read keys from the terminal: {}", e));
+    }
+    let reason = wait_for_key(Duration::from_secs(seconds));
+    let _ = disable_raw_mode();
+    clear_rows(rows)?;
+    Ok(reason)
+}
+
+/// Print a passphrase to the terminal one piece (a word and its separator) at a time, waiting
+/// `delay` after each, so it's never all on the screen at once if `clear_each` is set: then each
+/// word is erased before the next one is shown, and the last one is erased at the end.
+/// Otherwise the passphrase builds up on one line, as if being typed out.
+pub fn reveal_words(pieces: &[String], delay: Duration, clear_each: bool) -> Result<(), String> {
+    let columns = match size() {
+        Ok((columns, _rows)) => columns as usize,
+        Err(e) => return Err(format!("Couldn't get the terminal's size: {}", e)),
+    };
+    let mut out = stdout();
+    for (i, piece) in pieces.iter().enumerate() {
+        let shown = match clear_each {
+            true => format!("Word {} of {}: {}", i + 1, pieces.len(), piece),
+            false => piece.to_string(),
+        };
+        let print = write!(out, "{}", shown).and_then(|_| out.flush());
+        if let Err(e) = print {
+            return Err(format!("Couldn't print to the terminal: {}", e));
+        }
+        std::thread::sleep(delay);
+        if clear_each {
+            // The cursor is still on the word's last row, so go up to its first row and clear
+            clear_rows(rows_taken(&shown, columns) - 1)?;
+        }
+    }
+    if !clear_each {
+        if let Err(e) = writeln!(out) {
+            return Err(format!("Couldn't print to the terminal: {}", e));
+        }
+    }
+    Ok(())
+}
+
+/// Wait until time's up or the user presses Enter or Ctrl-C, whichever comes first.
+fn wait_for_key(wait: Duration) -> ClearReason {
+    let deadline = Instant::now() + wait;
+    loop {
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return ClearReason::TimedOut;
+        }
+        match poll(left) {
+            Ok(true) => {}
+            // Either time's up, or we can't read keys, so just clear when the time's up
+            Ok(false) => return ClearReason::TimedOut,
+            Err(_) => {
+                std::thread::sleep(left);
+                return ClearReason::TimedOut;
+            }
+        }
+        if let Ok(Event::Key(key)) = read() {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Enter => return ClearReason::Enter,
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return ClearReason::Interrupted
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// How many rows of the terminal a line takes up, once it wraps. Some terminals (like a bare
+/// pseudo-terminal) say they're 0 columns wide, which we take to mean lines don't wrap.
+fn rows_taken(line: &str, columns: usize) -> usize {
+    match columns {
+        0 => 1,
+        columns => grapheme_length(line).max(1).div_ceil(columns),
+    }
+}
+
+/// Move the cursor up `rows` rows, to the start of the line, and erase everything from there down.
+fn clear_rows(rows: usize) -> Result<(), String> {
+    let mut out = stdout();
+    let cleared = match u16::try_from(rows) {
+        // Moving up 0 rows would move up 1 in some terminals
+        Ok(0) => execute!(out, MoveToColumn(0), Clear(ClearType::FromCursorDown)),
+        Ok(rows) => execute!(
+            out,
+            MoveUp(rows),
+            MoveToColumn(0),
+            Clear(ClearType::FromCursorDown)
+        ),
+        // More rows than a terminal can move over at once, so clear the whole screen
+        Err(_) => execute!(out, Clear(ClearType::All)),
+    };
+    match cleared {
+        Ok(()) => Ok(()),
+        Err(e) => Err(format!("Couldn't clear the terminal: {}", e)),
+    }
+}
+
+#[test]
+fn can_count_the_rows_a_line_wraps_onto() {
+    assert_eq!(rows_taken("", 80), 1);
+    assert_eq!(rows_taken("correct-horse-battery-staple", 80), 1);
+    assert_eq!(rows_taken("correct-horse-battery-staple", 10), 3);
+    assert_eq!(rows_taken("café", 4), 1);
+    assert_eq!(rows_taken("correct-horse-battery-staple", 0), 1);
+}
@@ -1,5 +1,12 @@
 use rand::prelude::SliceRandom;
 use rand::Rng;
+use std::collections::HashSet;
+
+/// How many times to re-generate a passphrase's separators, looking for enough distinct ones,
+/// before giving up.
+pub const MIN_DISTINCT_SEPARATORS_MAX_TRIES: usize = 1000;
+
+const SYMBOLS: &[u8] = b"!@#$%&*(){}[]\\:;'<>?,./_-+=";
 
 #[derive(PartialEq)]
 enum SeparatorType {
@@ -10,16 +17,210 @@ enum SeparatorType {
 /// Parse user's separator choice. The only reason we need this as its own function is to check if
 /// they chose a "special" separator
 pub fn make_separator(rng: &mut impl Rng, sep: &str) -> String {
+    let mut separator = String::new();
+    push_separator(rng, &mut separator, sep);
+    separator
+}
+
+/// Same as `make_separator`, but adds the separator straight on to the end of a given String
+/// (usually the passphrase we're building) rather than allocating a new one.
+pub fn push_separator(rng: &mut impl Rng, passphrase: &mut String, sep: &str) {
     match sep {
-        "_n" => get_random_number(rng),
-        "_s" => get_random_symbol(rng),
-        "_b" => get_random_number_or_symbol(rng),
-        _ => sep.to_string(),
+        "_n" => passphrase.push(get_random_number(rng)),
+        "_s" => passphrase.push(get_random_symbol(rng)),
+        "_b" => passphrase.push(get_random_number_or_symbol(rng)),
+        _ => passphrase.push_str(sep),
+    }
+}
+
+/// Check whether a separator is one of the special values that generates random separators.
+pub fn is_generated_separator(sep: &str) -> bool {
+    matches!(sep, "_n" | "_s" | "_b")
+}
+
+/// Check whether a character could end up between words as a separator: whitespace, or a number
+/// or symbol that `_n`, `_s` or `_b` can generate. A word with one of these in it can make a
+/// passphrase's words hard to tell apart.
+pub fn is_separator_character(c: char) -> bool {
+    c.is_whitespace() || c.is_ascii_digit() || (c.is_ascii() && SYMBOLS.contains(&(c as u8)))
+}
+
+/// Check whether a character is one that a generated separator (`_n`, `_s` or `_b`) can be.
+pub fn could_be_generated_separator(c: char, sep: &str) -> bool {
+    let is_symbol = c.is_ascii() && SYMBOLS.contains(&(c as u8));
+    match sep {
+        "_n" => c.is_ascii_digit(),
+        "_s" => is_symbol,
+        "_b" => c.is_ascii_digit() || is_symbol,
+        _ => false,
+    }
+}
+
+/// How many different characters a generated separator can be.
+fn number_of_possible_separators(sep: &str) -> usize {
+    match sep {
+        "_n" => 10,
+        "_s" => SYMBOLS.len(),
+        _ => 10 + SYMBOLS.len(),
+    }
+}
+
+/// Make sure it's possible for a passphrase of the given number of words to have at least
+/// `min_distinct` different generated separators.
+pub fn check_min_distinct_separators(
+    sep: &str,
+    number_of_words: usize,
+    min_distinct: usize,
+) -> Result<(), String> {
+    if !is_generated_separator(sep) {
+        return Err(
+            "Can only require distinct separators when using generated separators (_n, _s or _b)"
+                .to_string(),
+        );
+    }
+    let gaps = number_of_words.saturating_sub(1);
+    if min_distinct > gaps {
+        return Err(format!(
+            "Can't have {} distinct separators in a passphrase of {} words, which only has {} separators",
+            min_distinct, number_of_words, gaps
+        ));
     }
+    if min_distinct > number_of_possible_separators(sep) {
+        return Err(format!(
+            "Can't have {} distinct separators, since {} only generates {} different characters",
+            min_distinct,
+            sep,
+            number_of_possible_separators(sep)
+        ));
+    }
+    Ok(())
+}
+
+/// Generate the separators for the gaps between a passphrase's words, re-generating the whole
+/// set until at least `min_distinct` of them are different from each other. This rejects some
+/// sets of separators, so the separators are very slightly more predictable than usual (Phraze
+/// never counts separators toward a passphrase's entropy anyway).
+///
+/// Gives up with an error after `MIN_DISTINCT_SEPARATORS_MAX_TRIES` tries, which only happens
+/// when nearly every separator has to be different.
+pub fn generate_distinct_separators(
+    rng: &mut impl Rng,
+    sep: &str,
+    gaps: usize,
+    min_distinct: usize,
+) -> Result<Vec<char>, String> {
+    check_min_distinct_separators(sep, gaps + 1, min_distinct)?;
+    for _ in 0..MIN_DISTINCT_SEPARATORS_MAX_TRIES {
+        let separators: Vec<char> = (0..gaps)
+            .map(|_| match sep {
+                "_n" => get_random_number(rng),
+                "_s" => get_random_symbol(rng),
+                _ => get_random_number_or_symbol(rng),
+            })
+            .collect();
+        if separators.iter().collect::<HashSet<_>>().len() >= min_distinct {
+            return Ok(separators);
+        }
+    }
+    Err(format!(
+        "Couldn't generate {} distinct separators after {} tries. Try a smaller --min-distinct-separators or more words.",
+        min_distinct, MIN_DISTINCT_SEPARATORS_MAX_TRIES
+    ))
+}
+
+/// Work out the characters in a separator pool for --sep-pool, from a comma-separated list of
+/// these parts:
+///
+/// - `digits`: 0 to 9
+/// - `symbols`: the same symbols `_s` uses
+/// - `unused-letters`: lowercase letters that aren't in any of the given words (ignoring case),
+///   so a separator can never be mistaken for part of a word
+/// - `custom:<characters>`: the given characters. Since these could include a comma, this takes
+///   everything after it, so it has to come last.
+///
+/// Characters that are in more than one part are only counted once, so every character in the
+/// pool is equally likely.
+pub fn parse_separator_pool<'a>(
+    pool: &str,
+    words: impl Iterator<Item = &'a str>,
+) -> Result<Vec<char>, String> {
+    // Only need to look through the words once, however many times unused-letters is given
+    let mut words = Some(words);
+    let mut characters: Vec<char> = vec![];
+    let mut rest = pool;
+    while !rest.is_empty() {
+        if let Some(custom) = rest.strip_prefix("custom:") {
+            if custom.is_empty() {
+                return Err(
+                    "custom: in --sep-pool needs at least one character after it".to_string(),
+                );
+            }
+            characters.extend(custom.chars());
+            break;
+        }
+        let (part, remaining) = rest.split_once(',').unwrap_or((rest, ""));
+        match part.trim() {
+            "digits" => characters.extend('0'..='9'),
+            "symbols" => characters.extend(SYMBOLS.iter().map(|b| *b as char)),
+            "unused-letters" => {
+                if let Some(words) = words.take() {
+                    let used: HashSet<char> = words
+                        .flat_map(|word| word.chars())
+                        .flat_map(|c| c.to_lowercase())
+                        .collect();
+                    characters.extend(('a'..='z').filter(|c| !used.contains(c)));
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "'{}' in --sep-pool should be digits, symbols, unused-letters or custom:<characters>",
+                    part.trim()
+                ))
+            }
+        }
+        rest = remaining;
+    }
+    // Keep the first of any repeated characters, so the pool stays in a predictable order
+    let mut seen = HashSet::new();
+    characters.retain(|c| seen.insert(*c));
+    if characters.is_empty() {
+        return Err(format!(
+            "Separator pool '{}' doesn't have any characters in it",
+            pool
+        ));
+    }
+    Ok(characters)
+}
+
+/// Draw a separator for each of a passphrase's gaps, each one independently and uniformly from
+/// a pool made by `parse_separator_pool`.
+pub fn generate_pool_separators(rng: &mut impl Rng, pool: &[char], gaps: usize) -> Vec<char> {
+    (0..gaps).map(|_| *pool.choose(rng).unwrap()).collect()
+}
+
+/// Entropy, in bits, that separators drawn from a pool add to a passphrase. Each of the
+/// passphrase's `number_of_words - 1` separators is drawn uniformly and independently, so each
+/// is worth log2 of the pool's size.
+pub fn separator_pool_entropy(number_of_words: usize, pool_size: usize) -> f64 {
+    number_of_words.saturating_sub(1) as f64 * (pool_size as f64).log2()
+}
+
+/// Entropy, in bits, that generated separators (`_n`, `_s` or `_b`) would add to a passphrase
+/// if they were counted. `_b` picks a number or a symbol with a coin flip, then one of those.
+pub fn generated_separator_entropy(number_of_words: usize, sep: &str) -> f64 {
+    let numbers = 10_f64.log2();
+    let symbols = (SYMBOLS.len() as f64).log2();
+    let per_separator = match sep {
+        "_n" => numbers,
+        "_s" => symbols,
+        "_b" => 1.0 + (numbers + symbols) / 2.0,
+        _ => 0.0,
+    };
+    number_of_words.saturating_sub(1) as f64 * per_separator
 }
 
 /// Get either a random number or symbol. 50/50 chance!
-fn get_random_number_or_symbol(rng: &mut impl Rng) -> String {
+fn get_random_number_or_symbol(rng: &mut impl Rng) -> char {
     // Randomly choose which separator type to use
     let separator_type_to_use: &SeparatorType = [SeparatorType::Number, SeparatorType::Symbol]
         .choose(rng)
@@ -32,13 +233,12 @@ fn get_random_number_or_symbol(rng: &mut impl Rng) -> String {
 }
 
 /// Pick a random symbol for a separator between words.
-fn get_random_symbol(rng: &mut impl Rng) -> String {
-    const CHARSET: &[u8] = b"!@#$%&*(){}[]\\:;'<>?,./_-+=";
-    let idx = rng.gen_range(0..CHARSET.len());
-    (CHARSET[idx] as char).to_string()
+fn get_random_symbol(rng: &mut impl Rng) -> char {
+    let idx = rng.gen_range(0..SYMBOLS.len());
+    SYMBOLS[idx] as char
 }
 
 /// Pick a random digit (0 to 9) for a separator between words.
-fn get_random_number(rng: &mut impl Rng) -> String {
-    rng.gen_range(0..=9).to_string()
+fn get_random_number(rng: &mut impl Rng) -> char {
+    char::from(b'0' + rng.gen_range(0..=9))
 }
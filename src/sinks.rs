@@ -0,0 +1,187 @@
+use crate::messages::{self, Message};
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Environment variable to set to the command Phraze should pipe passphrases into to copy them
+/// to the clipboard, like `xclip -selection primary`, instead of looking for a clipboard tool.
+/// The command is split on whitespace, so its arguments can't have spaces in them.
+pub const CLIPBOARD_COMMAND_VARIABLE: &str = "PHRAZE_CLIPBOARD_COMMAND";
+
+/// Somewhere other than standard output to send passphrases.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Sink {
+    /// Write them to a file. On Unix, a new file is only readable by its owner.
+    File(PathBuf),
+    /// Copy them to the clipboard, with the first clipboard tool that's installed
+    Clipboard,
+    /// Save it in the operating system's keyring, as the password for a service and account
+    Keyring { service: String, account: String },
+}
+
+impl Sink {
+    /// What to call the sink in messages, like "the clipboard".
+    pub fn describe(&self) -> String {
+        match self {
+            Sink::File(file_path) => file_path.display().to_string(),
+            Sink::Clipboard => "the clipboard".to_string(),
+            Sink::Keyring { service, account } => {
+                format!("the keyring (service {}, account {})", service, account)
+            }
+        }
+    }
+
+    /// Send text to this sink.
+    pub fn send(&self, text: &str) -> Result<(), String> {
+        match self {
+            Sink::File(file_path) => write_private_file(file_path, text),
+            Sink::Clipboard => copy_to_clipboard(text),
+            // The keyring holds the password itself, without the line break after it
+            Sink::Keyring { service, account } => {
+                store_in_keyring(service, account, text.trim_end_matches(['\r', '\n']))
+            }
+        }
+    }
+}
+
+/// Send text to every sink, in order. A sink failing doesn't stop the others: the text still
+/// goes to every sink that works, and then this returns an error that names the ones that
+/// failed. Each failure is also printed as a warning, as it happens.
+pub fn send_to_sinks(text: &str, sinks: &[Sink]) -> Result<(), String> {
+    let mut sent = vec![];
+    let mut failed = vec![];
+    for sink in sinks {
+        match sink.send(text) {
+            Ok(()) => sent.push(sink.describe()),
+            Err(e) => {
+                eprintln!(
+                    "{}",
+                    messages::text(Message::SinkFailed, &[&sink.describe(), &e])
+                );
+                failed.push(sink.describe());
+            }
+        }
+    }
+    match (failed.is_empty(), sent.is_empty()) {
+        (true, _) => Ok(()),
+        (false, true) => Err(format!(
+            "Couldn't send passphrases to {}",
+            failed.join(" or ")
+        )),
+        (false, false) => Err(format!(
+            "Couldn't send passphrases to {}, but they did go to {}",
+            failed.join(" or "),
+            sent.join(" and ")
+        )),
+    }
+}
+
+/// Write text to a file that only the user can read (mode 0600 on Unix), replacing whatever was
+/// there. A file that already existed is made private too, before anything's written to it.
+pub fn write_private_file(file_path: &Path, text: &str) -> Result<(), String> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    match options.open(file_path).and_then(|mut file| {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        }
+        file.write_all(text.as_bytes())
+    }) {
+        Ok(()) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Save a password in the operating system's keyring: the Keychain on macOS, Credential Manager
+/// on Windows, and the Secret Service (GNOME Keyring or KWallet) on Linux and the BSDs. Anything
+/// already saved for this service and account is replaced.
+#[cfg(feature = "keyring")]
+fn store_in_keyring(service: &str, account: &str, password: &str) -> Result<(), String> {
+    match keyring::Entry::new(service, account).and_then(|entry| entry.set_password(password)) {
+        Ok(()) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[cfg(not(feature = "keyring"))]
+fn store_in_keyring(_service: &str, _account: &str, _password: &str) -> Result<(), String> {
+    Err(
+        "this version of Phraze was built without keyring support (the `keyring` feature)"
+            .to_string(),
+    )
+}
+
+/// Clipboard tools to try, in order, each as a command and its arguments.
+fn clipboard_commands() -> Vec<Vec<String>> {
+    if let Ok(command) = std::env::var(CLIPBOARD_COMMAND_VARIABLE) {
+        return vec![command.split_whitespace().map(str::to_string).collect()];
+    }
+    let commands: &[&[&str]] = if cfg!(target_os = "macos") {
+        &[&["pbcopy"]]
+    } else if cfg!(windows) {
+        &[&["clip"]]
+    } else {
+        &[
+            &["wl-copy"],
+            &["xclip", "-selection", "clipboard"],
+            &["xsel", "--clipboard", "--input"],
+        ]
+    };
+    commands
+        .iter()
+        .map(|command| command.iter().map(|part| part.to_string()).collect())
+        .collect()
+}
+
+/// Pipe text into the first clipboard tool from `clipboard_commands` that's installed.
+fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    let commands = clipboard_commands();
+    for command in &commands {
+        let (program, arguments) = match command.split_first() {
+            Some(split) => split,
+            None => return Err(format!("{} is empty", CLIPBOARD_COMMAND_VARIABLE)),
+        };
+        let mut child = match Command::new(program)
+            .args(arguments)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            // Not installed, so try the next one
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => return Err(format!("couldn't run {}: {}", program, e)),
+        };
+        // Taking stdin closes it once it's written, so the tool knows the text is done
+        let written = match child.stdin.take() {
+            Some(mut stdin) => stdin.write_all(text.as_bytes()),
+            None => Ok(()),
+        };
+        let status = child.wait();
+        return match (written, status) {
+            (Ok(()), Ok(status)) if status.success() => Ok(()),
+            (Err(e), _) => Err(format!("couldn't write to {}: {}", program, e)),
+            (_, Ok(status)) => Err(format!("{} failed ({})", program, status)),
+            (_, Err(e)) => Err(format!("couldn't run {}: {}", program, e)),
+        };
+    }
+    let tried: Vec<&str> = commands
+        .iter()
+        .filter_map(|command| command.first())
+        .map(|program| program.as_str())
+        .collect();
+    Err(format!(
+        "no clipboard tool found (tried {}). Install one, or set {} to the command to use.",
+        tried.join(", "),
+        CLIPBOARD_COMMAND_VARIABLE
+    ))
+}
@@ -0,0 +1,114 @@
+/// Fewest bytes a hardware token's response can have. Most tokens' challenge-response modes
+/// (like a YubiKey's HMAC-SHA1 slots) give 20 bytes.
+pub const MIN_TOKEN_RESPONSE_BYTES: usize = 16;
+
+/// Where to get a hardware token's HMAC response to a challenge from. Phraze never talks to the
+/// token itself: another tool asks the token, and Phraze reads what it answered.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenResponseSource {
+    /// Read it from standard input, like `ykchalresp -2 -x mail | phraze --token-stdin`
+    Stdin,
+    /// Run this command and read it from the command's output. The command is split on
+    /// whitespace, so its arguments can't have spaces in them.
+    Helper(String),
+}
+
+/// Parse a token's response, written in hex, like "8b0d3d5f...". Spaces, line breaks and
+/// colons between the digits are ignored.
+pub fn parse_token_response(text: &str) -> Result<Vec<u8>, String> {
+    let digits: Vec<char> = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != ':')
+        .collect();
+    if !digits.len().is_multiple_of(2) {
+        return Err("Token response has an odd number of hex digits".to_string());
+    }
+    let mut response = Vec::with_capacity(digits.len() / 2);
+    for pair in digits.chunks(2) {
+        let byte: String = pair.iter().collect();
+        // from_str_radix would take a "+" sign too
+        match u8::from_str_radix(&byte, 16) {
+            Ok(parsed) if pair.iter().all(char::is_ascii_hexdigit) => response.push(parsed),
+            _ => return Err(format!("Token response has '{}', which isn't hex", byte)),
+        }
+    }
+    if response.len() < MIN_TOKEN_RESPONSE_BYTES {
+        return Err(format!(
+            "Token response is {} bytes, but should be at least {}",
+            response.len(),
+            MIN_TOKEN_RESPONSE_BYTES
+        ));
+    }
+    Ok(response)
+}
+
+/// Get a token's response from wherever the user said it'd be, and parse it.
+#[cfg(feature = "token")]
+pub fn read_token_response(source: &TokenResponseSource) -> Result<Vec<u8>, String> {
+    use std::io::Read;
+    use std::process::{Command, Stdio};
+
+    let text = match source {
+        TokenResponseSource::Stdin => {
+            let mut text = String::new();
+            match std::io::stdin().read_to_string(&mut text) {
+                Ok(_) => text,
+                Err(e) => return Err(format!("Couldn't read token response: {}", e)),
+            }
+        }
+        TokenResponseSource::Helper(command) => {
+            let mut parts = command.split_whitespace();
+            let program = match parts.next() {
+                Some(program) => program,
+                None => return Err("Token helper command is empty".to_string()),
+            };
+            // The helper may need to tell the user to touch the token, so let it use the
+            // terminal
+            let output = match Command::new(program)
+                .args(parts)
+                .stdin(Stdio::inherit())
+                .stderr(Stdio::inherit())
+                .output()
+            {
+                Ok(output) => output,
+                Err(e) => return Err(format!("Couldn't run token helper {}: {}", program, e)),
+            };
+            if !output.status.success() {
+                return Err(format!(
+                    "Token helper {} failed ({})",
+                    program, output.status
+                ));
+            }
+            match String::from_utf8(output.stdout) {
+                Ok(text) => text,
+                Err(_) => return Err(format!("Token helper {} didn't print hex", program)),
+            }
+        }
+    };
+    parse_token_response(&text)
+}
+
+#[cfg(not(feature = "token"))]
+pub fn read_token_response(_source: &TokenResponseSource) -> Result<Vec<u8>, String> {
+    Err(
+        "This version of Phraze was built without hardware token support (the `token` feature)"
+            .to_string(),
+    )
+}
+
+#[test]
+fn can_parse_token_responses() {
+    let response = parse_token_response("00112233445566778899aabbccddeeff01234567\n").unwrap();
+    assert_eq!(response.len(), 20);
+    assert_eq!(response[..3], [0x00, 0x11, 0x22]);
+    assert_eq!(
+        parse_token_response("00:11:22:33:44:55:66:77 88:99:AA:BB:CC:DD:EE:FF").unwrap()[15],
+        0xff
+    );
+
+    assert!(parse_token_response("0011223").is_err());
+    assert!(parse_token_response("zz112233445566778899aabbccddeeff").is_err());
+    assert!(parse_token_response("+f112233445566778899aabbccddeeff").is_err());
+    // Too short to be a real response
+    assert!(parse_token_response("00112233").is_err());
+}
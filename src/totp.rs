@@ -0,0 +1,31 @@
+use crate::indices::encode_base32;
+use rand::Rng;
+
+/// Length of a TOTP secret, in bytes. 160 bits is what RFC 4226 recommends, and what most
+/// authenticator apps expect.
+pub const TOTP_SECRET_BYTES: usize = 20;
+
+/// Generate a random shared secret for setting up TOTP two-factor authentication, encoded in
+/// base32 (without padding) the way authenticator apps take it. 160 bits encode to exactly 32
+/// characters.
+///
+/// This is only the secret that the server and the authenticator app share. Phraze doesn't
+/// compute TOTP codes from it.
+pub fn generate_totp_secret(rng: &mut impl Rng) -> String {
+    let mut secret = [0u8; TOTP_SECRET_BYTES];
+    rng.fill(&mut secret[..]);
+    encode_base32(&secret)
+}
+
+#[test]
+fn can_generate_a_totp_secret() {
+    use crate::indices::decode_base32;
+    let mut rng = rand::thread_rng();
+    let secret = generate_totp_secret(&mut rng);
+    assert_eq!(secret.len(), 32);
+    assert!(secret
+        .chars()
+        .all(|c| c.is_ascii_uppercase() || ('2'..='7').contains(&c)));
+    assert_eq!(decode_base32(&secret).unwrap().len(), TOTP_SECRET_BYTES);
+    assert_ne!(secret, generate_totp_secret(&mut rng));
+}
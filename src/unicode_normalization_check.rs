@@ -4,6 +4,7 @@ use unicode_normalization::is_nfd_quick;
 use unicode_normalization::is_nfkc_quick;
 use unicode_normalization::is_nfkd_quick;
 use unicode_normalization::IsNormalized;
+use unicode_normalization::UnicodeNormalization;
 
 /// Given a slice of Strings, this function will attempt to detect the Unicode normalization used
 /// in each String.
@@ -33,6 +34,72 @@ pub fn uniform_unicode_normalization(list: &[String]) -> bool {
     types_of_normalizations_discovered.len() == 1
 }
 
+/// Remove words that are the same as an earlier word once both are put in Normalization Form C,
+/// like "café" written with a single "é" character and "café" written with an "e" plus a
+/// combining accent. People can't tell these apart, so counting both would inflate entropy
+/// estimates. The first spelling of each word is kept as-is; words aren't rewritten. Returns how
+/// many words were removed.
+pub fn dedup_normalized(list: &mut Vec<String>) -> usize {
+    let original_length = list.len();
+    let mut seen = HashSet::new();
+    list.retain(|word| seen.insert(nfc_form(word)));
+    original_length - list.len()
+}
+
+/// A word in Normalization Form C, for comparing words that may be in different normalizations.
+pub fn nfc_form(word: &str) -> String {
+    word.nfc().collect()
+}
+
+/// The four Unicode normalization forms a passphrase can be put in.
+#[derive(Clone, Debug, Copy, PartialEq)]
+pub enum NormalizationForm {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+}
+
+/// Put text in the given Unicode normalization form, so that it's always made of the same bytes,
+/// no matter how the word list it came from was written. Normalizing text that's already in the
+/// form doesn't change it.
+pub fn normalize(text: &str, form: NormalizationForm) -> String {
+    match form {
+        NormalizationForm::Nfc => text.nfc().collect(),
+        NormalizationForm::Nfd => text.nfd().collect(),
+        NormalizationForm::Nfkc => text.nfkc().collect(),
+        NormalizationForm::Nfkd => text.nfkd().collect(),
+    }
+}
+
+/// Put a passphrase in the one form it should be stored in: all lowercase, in Normalization Form
+/// C. Two people typing the same passphrase on different keyboards (or with different input
+/// methods) then end up with the same bytes, whatever case or normalization they typed it in.
+pub fn canonical_form(text: &str) -> String {
+    text.to_lowercase().nfc().collect()
+}
+
+/// Check whether text is already in the given Unicode normalization form.
+pub fn is_normalized(text: &str, form: NormalizationForm) -> bool {
+    match form {
+        NormalizationForm::Nfc => unicode_normalization::is_nfc(text),
+        NormalizationForm::Nfd => unicode_normalization::is_nfd(text),
+        NormalizationForm::Nfkc => unicode_normalization::is_nfkc(text),
+        NormalizationForm::Nfkd => unicode_normalization::is_nfkd(text),
+    }
+}
+
+#[test]
+fn can_dedup_words_that_only_differ_in_normalization() {
+    let mut list = vec![
+        "cafe\u{301}".to_string(),
+        "caf\u{e9}".to_string(),
+        "tea".to_string(),
+    ];
+    assert_eq!(dedup_normalized(&mut list), 1);
+    assert_eq!(list, vec!["cafe\u{301}".to_string(), "tea".to_string()]);
+}
+
 #[test]
 fn can_detect_non_uniform_unicode_normalization_in_a_given_list() {
     let normalization_type_1 = "sécréter";
@@ -60,3 +127,39 @@ fn can_detect_non_uniform_unicode_normalization_in_a_given_list() {
     // Should still be detected as uniform
     assert!(uniform_unicode_normalization(&uniform_list2));
 }
+
+#[test]
+fn can_normalize_text_to_each_form() {
+    use NormalizationForm::*;
+    let composed = "caf\u{e9}-\u{fb01}sh";
+    let decomposed = "cafe\u{301}-\u{fb01}sh";
+    assert_eq!(normalize(decomposed, Nfc), composed);
+    assert_eq!(normalize(composed, Nfd), decomposed);
+    // The compatibility forms also split the "fi" ligature into two letters
+    assert_eq!(normalize(decomposed, Nfkc), "caf\u{e9}-fish");
+    assert_eq!(normalize(composed, Nfkd), "cafe\u{301}-fish");
+
+    for form in [Nfc, Nfd, Nfkc, Nfkd] {
+        for text in [composed, decomposed] {
+            let normalized = normalize(text, form);
+            assert!(is_normalized(&normalized, form));
+            // Normalizing again changes nothing
+            assert_eq!(normalize(&normalized, form), normalized);
+        }
+    }
+    // Canonical forms round-trip back to each other
+    assert_eq!(normalize(&normalize(composed, Nfd), Nfc), composed);
+    assert_eq!(normalize(&normalize(decomposed, Nfc), Nfd), decomposed);
+}
+
+#[test]
+fn can_put_passphrases_in_canonical_form() {
+    assert_eq!(
+        canonical_form("Cafe\u{301}-Cr\u{c8}me"),
+        "caf\u{e9}-cr\u{e8}me"
+    );
+    assert_eq!(canonical_form("correct-horse"), "correct-horse");
+    // Already canonical text doesn't change
+    let canonical = canonical_form("\u{c9}t\u{c9}-No\u{eb}l");
+    assert_eq!(canonical_form(&canonical), canonical);
+}
@@ -0,0 +1,161 @@
+use crate::separators::{could_be_generated_separator, is_generated_separator};
+use crate::{make_title_case, WordList};
+use std::collections::HashSet;
+
+/// Most separators a word on a list can have in it for `check_could_be_generated` to still find
+/// it, like "drop-down" (one) on the EFF long list.
+const MAX_SEPARATORS_IN_A_WORD: usize = 2;
+
+/// What `check_could_be_generated` found out about a passphrase.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PassphraseCheck {
+    /// The pieces the passphrase split into, one per word
+    pub words: Vec<String>,
+    /// Pieces that aren't a word on the list (with the right case), in the order they appear
+    pub unmatched: Vec<String>,
+}
+
+impl PassphraseCheck {
+    /// Whether every piece of the passphrase is a word on the list.
+    pub fn could_be_generated(&self) -> bool {
+        !self.words.is_empty() && self.unmatched.is_empty()
+    }
+}
+
+/// Check whether a passphrase could have been made from a word list with the given separator
+/// and case settings: that it splits into pieces on the separator, and every piece is a word on
+/// the list. This is handy for support, to check that someone's passphrase came from the list
+/// it was supposed to.
+///
+/// `separator` can be one of the special values (`_n`, `_s` or `_b`), in which case any character
+/// that value can generate counts as a separator, or there can be a `separator_pool` of
+/// characters. With no separator, words are split before each uppercase letter, which needs
+/// `title_case`. Words on the list that have the separator in them (like "drop-down", with
+/// `-`) are found too.
+///
+/// This only checks the words, so it can't confirm the passphrase was picked at random: a
+/// passphrase someone made up from list words passes too.
+pub fn check_could_be_generated<L: WordList + ?Sized>(
+    passphrase: &str,
+    list: &L,
+    separator: &str,
+    separator_pool: Option<&[char]>,
+    title_case: bool,
+) -> PassphraseCheck {
+    let list_words: HashSet<String> = list
+        .words()
+        .map(|word| match title_case {
+            true => make_title_case(word),
+            false => word.to_string(),
+        })
+        .collect();
+    let words = match separator_pool {
+        Some(separator_pool) => split_on(passphrase, |c| separator_pool.contains(&c)),
+        None if is_generated_separator(separator) => {
+            split_on(passphrase, |c| could_be_generated_separator(c, separator))
+        }
+        None if separator.is_empty() => split_before_uppercase(passphrase),
+        None => split_on_literal(passphrase, separator, &list_words),
+    };
+    let unmatched = words
+        .iter()
+        .filter(|word| !list_words.contains(*word))
+        .cloned()
+        .collect();
+    PassphraseCheck { words, unmatched }
+}
+
+fn split_on(passphrase: &str, is_separator: impl Fn(char) -> bool) -> Vec<String> {
+    passphrase
+        .split(is_separator)
+        .map(|word| word.to_string())
+        .collect()
+}
+
+/// Split a Title Case passphrase with no separator, like "CorrectHorseBattery".
+fn split_before_uppercase(passphrase: &str) -> Vec<String> {
+    let mut words: Vec<String> = vec![];
+    for c in passphrase.chars() {
+        match words.last_mut() {
+            Some(word) if !c.is_uppercase() => word.push(c),
+            _ => words.push(c.to_string()),
+        }
+    }
+    words
+}
+
+/// Split a passphrase on a separator, putting pieces back together where they make up a list
+/// word that has the separator in it.
+fn split_on_literal(
+    passphrase: &str,
+    separator: &str,
+    list_words: &HashSet<String>,
+) -> Vec<String> {
+    let pieces: Vec<&str> = passphrase.split(separator).collect();
+    let mut words = vec![];
+    let mut start = 0;
+    while start < pieces.len() {
+        // Try the longest word first
+        let longest = (pieces.len() - start).min(MAX_SEPARATORS_IN_A_WORD + 1);
+        let length = (2..=longest)
+            .rev()
+            .find(|length| list_words.contains(&pieces[start..start + length].join(separator)))
+            .unwrap_or(1);
+        words.push(pieces[start..start + length].join(separator));
+        start += length;
+    }
+    words
+}
+
+#[test]
+fn can_check_a_passphrase_against_a_list() {
+    let list = ["correct", "horse", "battery", "staple", "drop-down"];
+    let check = check_could_be_generated("correct-horse-battery-staple", &list, "-", None, false);
+    assert!(check.could_be_generated());
+    assert_eq!(check.words.len(), 4);
+
+    let check = check_could_be_generated("correct-horse-batter-stable", &list, "-", None, false);
+    assert!(!check.could_be_generated());
+    assert_eq!(check.unmatched, vec!["batter", "stable"]);
+
+    // A word with the separator in it is still one word
+    let check = check_could_be_generated("horse-drop-down-staple", &list, "-", None, false);
+    assert!(check.could_be_generated());
+    assert_eq!(check.words, vec!["horse", "drop-down", "staple"]);
+
+    // Case has to match the settings
+    assert!(
+        !check_could_be_generated("Horse-Staple", &list, "-", None, false).could_be_generated()
+    );
+    assert!(check_could_be_generated("Horse-Staple", &list, "-", None, true).could_be_generated());
+    assert!(check_could_be_generated("HorseStaple", &list, "", None, true).could_be_generated());
+}
+
+#[test]
+fn can_check_passphrases_with_generated_separators() {
+    let list = ["correct", "horse", "battery"];
+    assert!(
+        check_could_be_generated("correct4horse0battery", &list, "_n", None, false)
+            .could_be_generated()
+    );
+    assert!(
+        !check_could_be_generated("correct#horse0battery", &list, "_n", None, false)
+            .could_be_generated()
+    );
+    assert!(
+        check_could_be_generated("correct#horse0battery", &list, "_b", None, false)
+            .could_be_generated()
+    );
+    assert!(check_could_be_generated(
+        "correct~horse=battery",
+        &list,
+        "-",
+        Some(&['~', '=']),
+        false
+    )
+    .could_be_generated());
+    // Two separators in a row leave an empty piece, which isn't a word
+    assert!(
+        !check_could_be_generated("correct--horse", &list, "-", None, false).could_be_generated()
+    );
+}
@@ -0,0 +1,79 @@
+use rand::distributions::WeightedIndex;
+use rand::prelude::Distribution;
+use rand::Rng;
+use std::collections::HashMap;
+
+/// Set up a distribution for picking words in proportion to their weights. Weights must all be
+/// positive numbers, which `file_reader::read_in_weighted_custom_list` checks for.
+pub fn make_weighted_distribution(weights: &[f64]) -> Result<WeightedIndex<f64>, String> {
+    match WeightedIndex::new(weights) {
+        Ok(distribution) => Ok(distribution),
+        Err(e) => Err(format!("Invalid word weights: {}", e)),
+    }
+}
+
+/// Randomly pick the given number of words from a word list, where each word's chance of being
+/// picked is proportional to its weight (weights come from the given distribution, in the same
+/// order as the list).
+pub fn generate_weighted_words<'a, T: AsRef<str>>(
+    rng: &mut impl Rng,
+    number_of_words: usize,
+    list: &'a [T],
+    distribution: &WeightedIndex<f64>,
+) -> Vec<&'a str> {
+    (0..number_of_words)
+        .map(|_| list[distribution.sample(rng)].as_ref())
+        .collect()
+}
+
+/// Calculate the Shannon entropy, in bits, of picking one word with the given weights. For equal
+/// weights this is log2 of the number of words, just like an unweighted list. Uneven weights
+/// always give less entropy than that, since some words are more predictable than others.
+///
+/// The formula is H = -Σ p * log2(p), where p is each word's weight divided by the sum of all
+/// weights.
+pub fn shannon_entropy_per_word(weights: &[f64]) -> f64 {
+    let total_weight: f64 = weights.iter().sum();
+    -weights
+        .iter()
+        .map(|weight| weight / total_weight)
+        .filter(|p| *p > 0.0)
+        .map(|p| p * p.log2())
+        .sum::<f64>()
+}
+
+/// Work out how many bits each word of a weighted list is worth on its own: -log2(p), where p is
+/// the word's weight divided by the sum of all weights. Rarer words are worth more. On average,
+/// a word is worth `shannon_entropy_per_word` bits, but a passphrase that happens to have rare
+/// words in it is harder to guess than that, and one with common words is easier.
+pub fn word_self_information<'a, T: AsRef<str>>(
+    list: &'a [T],
+    weights: &[f64],
+) -> HashMap<&'a str, f64> {
+    let total_weight: f64 = weights.iter().sum();
+    list.iter()
+        .zip(weights)
+        .map(|(word, weight)| (word.as_ref(), -(weight / total_weight).log2()))
+        .collect()
+}
+
+#[test]
+fn can_calculate_shannon_entropy_of_weights() {
+    assert_eq!(shannon_entropy_per_word(&[1.0, 1.0, 1.0, 1.0]), 2.0);
+    assert_eq!(shannon_entropy_per_word(&[3.0, 3.0]), 1.0);
+    // Uneven weights mean less entropy than an even list of the same length
+    assert!(shannon_entropy_per_word(&[1.0, 2.0, 5.0, 8.0]) < 2.0);
+}
+
+#[test]
+fn rarer_words_are_worth_more_bits() {
+    let list = ["common", "rare", "rarer"];
+    let bits = word_self_information(&list, &[6.0, 1.0, 1.0]);
+    assert_eq!(bits["rare"], 3.0);
+    assert_eq!(bits["rarer"], 3.0);
+    assert!(bits["common"] < shannon_entropy_per_word(&[6.0, 1.0, 1.0]));
+    // Passphrases from the same list can be worth different amounts
+    let common_passphrase: f64 = ["common", "common"].iter().map(|word| bits[word]).sum();
+    let rare_passphrase: f64 = ["rare", "rarer"].iter().map(|word| bits[word]).sum();
+    assert!(rare_passphrase > common_passphrase);
+}
@@ -0,0 +1,162 @@
+use crate::indices::{word_indices, words_from_indices};
+use crate::sinks::write_private_file;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// How many words go in a word bank, unless the user asks for a different size.
+pub const DEFAULT_WORD_BANK_SIZE: usize = 100;
+
+/// Make a word bank for sharing passphrases in two parts: every word the passphrases use, plus
+/// enough other words picked at random from the list to make `size` words in all, shuffled.
+/// The passphrases are then given by their words' positions in the bank (see `bank_selections`).
+///
+/// If the list doesn't have enough words, the bank is the whole list. It's an error if the
+/// passphrases use more than `size` different words.
+pub fn make_word_bank<'a>(
+    rng: &mut impl Rng,
+    passphrase_words: &[Vec<&'a str>],
+    list: &[&'a str],
+    size: usize,
+) -> Result<Vec<&'a str>, String> {
+    let mut seen = HashSet::new();
+    let mut bank: Vec<&str> = passphrase_words
+        .iter()
+        .flatten()
+        .copied()
+        .filter(|word| seen.insert(*word))
+        .collect();
+    if bank.len() > size {
+        return Err(format!(
+            "The passphrases use {} different words, which won't fit in a word bank of {}",
+            bank.len(),
+            size
+        ));
+    }
+    let mut others: Vec<&str> = list
+        .iter()
+        .copied()
+        .filter(|word| !seen.contains(word))
+        .collect();
+    let needed = (size - bank.len()).min(others.len());
+    let (picked, _) = others.partial_shuffle(rng, needed);
+    bank.extend_from_slice(picked);
+    bank.shuffle(rng);
+    Ok(bank)
+}
+
+/// Where each of a passphrase's words is in a word bank, numbered from 1, like "12 5 88 40".
+pub fn bank_selections<T: AsRef<str>>(words: &[&str], bank: &[T]) -> Result<String, String> {
+    let numbers: Vec<String> = word_indices(words, bank)?
+        .iter()
+        .map(|index| (index + 1).to_string())
+        .collect();
+    Ok(numbers.join(" "))
+}
+
+/// Look up the words a string of selections (from `bank_selections`) picks out of a word bank.
+pub fn words_from_selections<'a, T: AsRef<str>>(
+    selections: &str,
+    bank: &'a [T],
+) -> Result<Vec<&'a str>, String> {
+    let indices: Vec<usize> = selections
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|number| !number.is_empty())
+        .map(|number| match number.parse::<usize>() {
+            Ok(number) if number > 0 => Ok(number - 1),
+            _ => Err(format!(
+                "Selection '{}' should be a word bank number, from 1 to {}",
+                number,
+                bank.len()
+            )),
+        })
+        .collect::<Result<Vec<usize>, String>>()?;
+    if indices.is_empty() {
+        return Err("No word bank selections given".to_string());
+    }
+    words_from_indices(&indices, bank)
+}
+
+/// Write a word bank to a file, one numbered word per line. It's half of what it takes to rebuild
+/// the passphrases, so only its owner can read it (mode 0600 on Unix).
+pub fn write_word_bank<T: AsRef<str>>(file_path: &Path, bank: &[T]) -> Result<(), String> {
+    let mut contents = "# Phraze word bank. Send this separately from the selections: anyone with both can rebuild the passphrase.\n".to_string();
+    for (index, word) in bank.iter().enumerate() {
+        contents += &format!("{} {}\n", index + 1, word.as_ref());
+    }
+    match write_private_file(file_path, &contents) {
+        Ok(()) => Ok(()),
+        Err(e) => Err(format!("Error writing word bank: {}", e)),
+    }
+}
+
+/// Read a word bank written by `write_word_bank`. Its words must be numbered 1, 2, 3 and so on,
+/// in order, so a bank that's been mangled in transit is an error rather than quietly giving
+/// the wrong passphrase.
+pub fn read_word_bank(file_path: &Path) -> Result<Vec<String>, String> {
+    let contents = match fs::read_to_string(file_path) {
+        Ok(contents) => contents,
+        Err(e) => return Err(format!("Error reading word bank: {}", e)),
+    };
+    let mut bank = vec![];
+    for (line_number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.split_once(char::is_whitespace) {
+            Some((number, word)) if number.parse() == Ok(bank.len() + 1) => {
+                bank.push(word.trim().to_string())
+            }
+            _ => {
+                return Err(format!(
+                    "Line {} of word bank should be word number {}",
+                    line_number + 1,
+                    bank.len() + 1
+                ))
+            }
+        }
+    }
+    Ok(bank)
+}
+
+#[test]
+fn can_make_a_word_bank_and_select_from_it() {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    let list = [
+        "apple", "banner", "candle", "dolphin", "ember", "forest", "garden", "harbor",
+    ];
+    let passphrases = vec![vec!["candle", "ember", "candle"], vec!["harbor", "apple"]];
+    let mut rng = StdRng::seed_from_u64(3);
+    let bank = make_word_bank(&mut rng, &passphrases, &list, 6).unwrap();
+    assert_eq!(bank.len(), 6);
+    assert_eq!(bank.iter().collect::<HashSet<_>>().len(), 6);
+    for words in &passphrases {
+        let selections = bank_selections(words, &bank).unwrap();
+        assert_eq!(&words_from_selections(&selections, &bank).unwrap(), words);
+    }
+    // Small lists just go in whole
+    assert_eq!(
+        make_word_bank(&mut rng, &passphrases, &list, 100)
+            .unwrap()
+            .len(),
+        8
+    );
+    assert!(make_word_bank(&mut rng, &passphrases, &list, 3).is_err());
+}
+
+#[test]
+fn can_only_select_words_in_the_bank() {
+    let bank = ["apple", "banner", "candle"];
+    assert_eq!(
+        words_from_selections("3, 1", &bank).unwrap(),
+        vec!["candle", "apple"]
+    );
+    assert!(words_from_selections("0", &bank).is_err());
+    assert!(words_from_selections("4", &bank).is_err());
+    assert!(words_from_selections("two", &bank).is_err());
+    assert!(words_from_selections(" ", &bank).is_err());
+}
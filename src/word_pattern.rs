@@ -0,0 +1,42 @@
+/// Which words of a list match a regular expression, like "^[a-m]" or "^.{4,6}$", in the same
+/// order as the list. The pattern can match anywhere in a word unless it's anchored with ^ or $.
+#[cfg(feature = "word-pattern")]
+pub fn words_matching_pattern(list: &[&str], pattern: &str) -> Result<Vec<bool>, String> {
+    let regex = match regex::Regex::new(pattern) {
+        Ok(regex) => regex,
+        Err(e) => {
+            return Err(format!(
+                "Inputted word pattern '{}' isn't a valid regular expression: {}",
+                pattern, e
+            ))
+        }
+    };
+    Ok(list.iter().map(|word| regex.is_match(word)).collect())
+}
+
+#[cfg(not(feature = "word-pattern"))]
+pub fn words_matching_pattern(_list: &[&str], _pattern: &str) -> Result<Vec<bool>, String> {
+    Err(
+        "This version of Phraze was built without --word-pattern (the `word-pattern` feature)"
+            .to_string(),
+    )
+}
+
+#[cfg(feature = "word-pattern")]
+#[test]
+fn can_find_words_matching_a_pattern() {
+    let list = ["apple", "kiwi", "mango", "nectarine", "zucchini"];
+    assert_eq!(
+        words_matching_pattern(&list, "^[a-m]").unwrap(),
+        [true, true, true, false, false]
+    );
+    assert_eq!(
+        words_matching_pattern(&list, "^.{4,5}$").unwrap(),
+        [true, true, true, false, false]
+    );
+    assert_eq!(
+        words_matching_pattern(&list, "cc").unwrap(),
+        [false, false, false, false, true]
+    );
+    assert!(words_matching_pattern(&list, "[a-").is_err());
+}
@@ -0,0 +1,55 @@
+mod common;
+
+mod abbreviate_tests {
+    use crate::common::phraze;
+
+    #[test]
+    fn can_print_abbreviated_passphrases() {
+        let output = phraze(&[
+            "--abbreviate",
+            "-c",
+            "tests/fixtures/abbreviation_list.txt",
+            "-w",
+            "4",
+            "-n",
+            "5",
+        ]);
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        let full_words: Vec<&str> = stderr
+            .lines()
+            .filter_map(|line| line.strip_prefix("Full words: "))
+            .collect();
+        assert_eq!(full_words.len(), 5);
+        // Every word on this list is told apart by its first 3 letters
+        for (passphrase, full_words) in stdout.lines().zip(full_words) {
+            let abbreviated: Vec<String> = full_words
+                .split(' ')
+                .map(|word| word.chars().take(3).collect())
+                .collect();
+            assert_eq!(passphrase, abbreviated.join("-"));
+        }
+    }
+
+    #[test]
+    fn reports_that_entropy_is_unchanged() {
+        let output = phraze(&[
+            "--abbreviate",
+            "-v",
+            "-c",
+            "tests/fixtures/abbreviation_list.txt",
+        ]);
+        assert!(output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("Abbreviating words to their first 3 characters"));
+        assert!(stderr.contains("entropy is unchanged"));
+    }
+
+    #[test]
+    fn lists_that_cannot_be_abbreviated_are_an_error() {
+        // Some words on the medium list are the start of another word
+        let output = phraze(&["--abbreviate", "-l", "m"]);
+        assert!(!output.status.success());
+    }
+}
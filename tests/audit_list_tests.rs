@@ -0,0 +1,117 @@
+mod common;
+
+mod audit_list_tests {
+    use crate::common::phraze_command;
+    use phraze::file_reader::read_in_words_as_written;
+    use phraze::list_analysis::{AuditCheck, ListAnalysis};
+    use std::path::Path;
+    use std::process::Output;
+
+    /// Audit a fixture, returning the checks it failed.
+    fn failed_checks(fixture: &str, min_words: usize) -> Vec<AuditCheck> {
+        let path = format!("tests/fixtures/{}", fixture);
+        let words = read_in_words_as_written(Path::new(&path)).unwrap();
+        ListAnalysis::new(&words)
+            .audit(min_words)
+            .into_iter()
+            .filter(|result| !result.passed)
+            .map(|result| result.check)
+            .collect()
+    }
+
+    fn run_audit(args: &[&str]) -> Output {
+        phraze_command()
+            .arg("audit-list")
+            .args(args)
+            .output()
+            .unwrap()
+    }
+
+    #[test]
+    fn a_good_list_passes_every_check() {
+        assert_eq!(failed_checks("audit_good_list.txt", 12), vec![]);
+    }
+
+    #[test]
+    fn each_fixture_fails_its_check() {
+        use AuditCheck::*;
+        assert_eq!(failed_checks("audit_good_list.txt", 13), vec![MinSize]);
+        assert_eq!(
+            failed_checks("audit_duplicates_list.txt", 10),
+            vec![Duplicates]
+        );
+        // Words that only differ in normalization can't all be in the same normalization
+        assert_eq!(
+            failed_checks("audit_normalized_duplicates_list.txt", 10),
+            vec![NormalizedDuplicates, UniformNormalization]
+        );
+        assert_eq!(
+            failed_checks("audit_mixed_normalization_list.txt", 10),
+            vec![UniformNormalization]
+        );
+        assert_eq!(failed_checks("audit_prefix_list.txt", 10), vec![PrefixFree]);
+        assert_eq!(
+            failed_checks("audit_separators_list.txt", 10),
+            vec![NoSeparators]
+        );
+        assert_eq!(
+            failed_checks("audit_lengths_list.txt", 10),
+            vec![LengthDistribution]
+        );
+    }
+
+    #[test]
+    fn exits_with_an_error_only_if_a_required_check_fails() {
+        let output = run_audit(&["--min-words", "10", "tests/fixtures/audit_good_list.txt"]);
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(
+            stdout
+                .lines()
+                .filter(|line| line.starts_with("PASS"))
+                .count(),
+            7
+        );
+
+        let output = run_audit(&[
+            "--min-words",
+            "10",
+            "tests/fixtures/audit_separators_list.txt",
+        ]);
+        assert!(!output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("FAIL  separators: "));
+        assert!(stdout.contains("'ice cream'"));
+
+        // Prefix-free is only a warning by default
+        let output = run_audit(&["--min-words", "10", "tests/fixtures/audit_prefix_list.txt"]);
+        assert!(output.status.success());
+        assert!(String::from_utf8(output.stdout)
+            .unwrap()
+            .contains("WARN  prefix-free: "));
+    }
+
+    #[test]
+    fn warnings_are_configurable() {
+        let separators = "tests/fixtures/audit_separators_list.txt";
+        let output = run_audit(&["--min-words", "10", "--warn", "separators", separators]);
+        assert!(output.status.success());
+
+        let prefixes = "tests/fixtures/audit_prefix_list.txt";
+        let output = run_audit(&["--min-words", "10", "--require", "prefix-free", prefixes]);
+        assert!(!output.status.success());
+
+        // The default minimum size is much bigger than these fixtures
+        assert!(!run_audit(&["tests/fixtures/audit_good_list.txt"])
+            .status
+            .success());
+        assert!(
+            !run_audit(&["--warn", "min-size", "--require", "min-size", prefixes])
+                .status
+                .success()
+        );
+        assert!(!run_audit(&["--warn", "spelling", prefixes])
+            .status
+            .success());
+    }
+}
@@ -0,0 +1,23 @@
+mod common;
+
+mod benchmark_command_tests {
+    use crate::common::phraze;
+
+    #[test]
+    fn can_report_generation_throughput() {
+        let output = phraze(&["benchmark", "-l", "s", "-w", "4", "--iterations", "1000"]);
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.starts_with("Generated 1000 passphrases of 4 words in "));
+        assert!(stdout.trim_end().ends_with(" passphrases per second"));
+    }
+
+    #[test]
+    fn benchmark_is_its_own_command() {
+        // Options for generating passphrases don't go with the benchmark
+        let output = phraze(&["-n", "3", "benchmark", "--iterations", "10"]);
+        assert!(!output.status.success());
+        let output = phraze(&["benchmark", "--iterations", "0"]);
+        assert!(!output.status.success());
+    }
+}
@@ -0,0 +1,87 @@
+mod common;
+
+mod clean_list_tests {
+    use crate::common::phraze;
+    use phraze::file_reader::*;
+    use std::path::Path;
+
+    #[test]
+    fn can_clean_a_messy_list() {
+        let cleaned = clean_list(Path::new("tests/fixtures/messy_list.txt"), false).unwrap();
+        assert_eq!(
+            cleaned.words,
+            vec![
+                "# fruit and animals",
+                "Apple",
+                "apple",
+                "café",
+                "mango",
+                "zebra"
+            ]
+        );
+        assert_eq!(cleaned.directives.separator, Some("_".to_string()));
+        assert_eq!(
+            cleaned.report,
+            CleaningReport {
+                trimmed: 2,
+                blank_lines: 2,
+                diceware_numbers: 0,
+                normalized: 1,
+                duplicates: 2,
+                case_duplicates: 0,
+            }
+        );
+
+        let cleaned = clean_list(Path::new("tests/fixtures/messy_list.txt"), true).unwrap();
+        assert_eq!(
+            cleaned.words,
+            vec!["# fruit and animals", "Apple", "café", "mango", "zebra"]
+        );
+        assert_eq!(cleaned.report.case_duplicates, 1);
+    }
+
+    #[test]
+    fn can_take_off_diceware_numbers() {
+        let path = Path::new("tests/fixtures/diceware_numbered_list.txt");
+        let cleaned = clean_list(path, false).unwrap();
+        assert_eq!(cleaned.words, vec!["abacus", "abdomen", "abide", "abiding"]);
+        assert_eq!(cleaned.report.diceware_numbers, 4);
+        // Words that happen to start with a number are left alone
+        let cleaned = clean_list(Path::new("tests/fixtures/messy_list.txt"), false).unwrap();
+        assert_eq!(cleaned.report.diceware_numbers, 0);
+    }
+
+    #[test]
+    fn cleaned_list_reads_back_in_the_same() {
+        let cleaned = clean_list(Path::new("tests/fixtures/messy_list.txt"), false).unwrap();
+        let path = std::env::temp_dir().join(format!("phraze-clean-list-{}", std::process::id()));
+        std::fs::write(&path, format_cleaned_list(&cleaned)).unwrap();
+        let (words, directives) = read_in_custom_list_with_directives(&path).unwrap();
+        // Cleaning it again changes nothing
+        let cleaned_again = clean_list(&path, false).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(words, cleaned.words);
+        assert_eq!(directives, cleaned.directives);
+        assert_eq!(cleaned_again.words, cleaned.words);
+        assert_eq!(cleaned_again.report, CleaningReport::default());
+    }
+
+    #[test]
+    fn clean_list_command_refuses_lists_that_are_too_short() {
+        let output = phraze(&[
+            "clean-list",
+            "tests/fixtures/messy_list.txt",
+            "--min-words",
+            "7",
+        ]);
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+
+        let output = phraze(&["clean-list", "tests/fixtures/diceware_numbered_list.txt"]);
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout, "abacus\nabdomen\nabide\nabiding\n");
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("Diceware numbers taken off: 4"));
+    }
+}
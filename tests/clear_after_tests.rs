@@ -0,0 +1,88 @@
+mod common;
+
+mod clear_after_tests {
+    use crate::common::{phraze, PHRAZE};
+    use std::io::Write;
+    use std::process::{Command, Output, Stdio};
+
+    /// The escape code crossterm sends to erase from the cursor down
+    const CLEAR_DOWN: &str = "\x1b[J";
+
+    /// Run Phraze in a pseudo-terminal, using util-linux's `script` as the harness, typing `keys`
+    /// into it after a second. Returns None if `script` isn't installed.
+    #[cfg(target_os = "linux")]
+    fn phraze_in_terminal(args: &str, keys: &[u8]) -> Option<Output> {
+        let command = format!("{} {}", PHRAZE, args);
+        let mut child = match Command::new("script")
+            .args(["-qec", &command, "/dev/null"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(_) => return None,
+        };
+        let mut stdin = child.stdin.take().unwrap();
+        std::thread::sleep(std::time::Duration::from_secs(1));
+        stdin.write_all(keys).unwrap();
+        // Keep the terminal's input open until Phraze is done
+        let output = child.wait_with_output().unwrap();
+        drop(stdin);
+        Some(output)
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn can_clear_passphrases_once_time_is_up() {
+        let output = match phraze_in_terminal("--clear-after 1 -n 2", b"") {
+            Some(output) => output,
+            None => return,
+        };
+        assert!(output.status.success());
+        let shown = String::from_utf8(output.stdout).unwrap();
+        assert!(shown.contains("(Clearing in 1 second. Press Enter to clear now.)"));
+        assert!(shown.contains("scrollback"));
+        // Two passphrases and the prompt are cleared, and the clearing comes last
+        assert!(shown.ends_with(&format!("\x1b[3A\x1b[1G{}", CLEAR_DOWN)));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn can_clear_passphrases_early() {
+        let started = std::time::Instant::now();
+        let output = match phraze_in_terminal("--clear-after 30", b"\r") {
+            Some(output) => output,
+            None => return,
+        };
+        assert!(output.status.success());
+        assert!(started.elapsed().as_secs() < 30);
+        assert!(String::from_utf8(output.stdout)
+            .unwrap()
+            .ends_with(CLEAR_DOWN));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn still_clears_passphrases_on_ctrl_c() {
+        let output = match phraze_in_terminal("--clear-after 30", b"\x03") {
+            Some(output) => output,
+            None => return,
+        };
+        // Exits like it was interrupted, but only after clearing
+        assert_eq!(output.status.code(), Some(130));
+        assert!(String::from_utf8(output.stdout)
+            .unwrap()
+            .ends_with(CLEAR_DOWN));
+    }
+
+    #[test]
+    fn refuses_to_clear_passphrases_if_not_printing_to_a_terminal() {
+        let output = phraze(&["--clear-after", "1"]);
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+        assert!(String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("only works when printing passphrases to a terminal"));
+    }
+}
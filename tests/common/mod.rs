@@ -0,0 +1,18 @@
+// Each test file only uses some of these
+#![allow(dead_code)]
+
+use std::process::{Command, Output};
+
+/// Path to the phraze binary being tested, for harnesses (like `script`) that run it for us.
+pub const PHRAZE: &str = env!("CARGO_BIN_EXE_phraze");
+
+/// A command to run phraze with, for tests that need more than its arguments set, like
+/// environment variables or a pipe to its stdin.
+pub fn phraze_command() -> Command {
+    Command::new(PHRAZE)
+}
+
+/// Run phraze with the given arguments, and wait for it to finish.
+pub fn phraze(args: &[&str]) -> Output {
+    phraze_command().args(args).output().unwrap()
+}
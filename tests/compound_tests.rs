@@ -0,0 +1,94 @@
+mod common;
+
+mod compound_tests {
+    use phraze::*;
+
+    const COLLISIONS_LIST: &str = "tests/fixtures/compound_collisions_list.txt";
+
+    fn phraze(args: &[&str]) -> (bool, String, String) {
+        let output = crate::common::phraze(args);
+        (
+            output.status.success(),
+            String::from_utf8(output.stdout).unwrap(),
+            String::from_utf8(output.stderr).unwrap(),
+        )
+    }
+
+    #[test]
+    fn fills_each_slot_with_two_words() {
+        let (success, passphrase, _) =
+            phraze(&["--compound", "--compound-joiner", "'", "-w", "3", "-l", "e"]);
+        assert!(success);
+        let slots: Vec<&str> = passphrase.trim_end().split('-').collect();
+        assert_eq!(slots.len(), 3);
+        let list = fetch_list(ListChoice::Eff);
+        for slot in slots {
+            let words: Vec<&str> = slot.split('\'').collect();
+            assert_eq!(words.len(), 2);
+            assert!(words.iter().all(|word| list.contains(word)));
+        }
+    }
+
+    #[test]
+    fn counts_slots_as_two_words_worth_of_entropy() {
+        // 80 bits takes 7 words from the Medium list, but only 4 slots of 2 words each
+        let (success, passphrase, verbose) = phraze(&["--compound", "-v"]);
+        assert!(success);
+        assert_eq!(passphrase.trim_end().split('-').count(), 4);
+        assert!(verbose.contains("104.00 bits of entropy (8 words from a list of 8192 words)"));
+        assert!(verbose.contains("each of the 4 slots is 2 words joined together"));
+        // Built-in lists are uniquely decodable, so there's nothing to warn about
+        assert!(!verbose.contains("WARNING"));
+
+        let (success, stats, _) = phraze(&["--compound", "--stats-only"]);
+        assert!(success);
+        assert!(stats.contains("Bits per slot: 26.00 (2 words each)"));
+        assert!(stats.contains("Slots per passphrase: 4"));
+        assert!(stats.contains("Words per passphrase: 8"));
+    }
+
+    #[test]
+    fn warns_when_two_pairs_of_words_make_the_same_compound() {
+        let (success, _, warnings) = phraze(&["--compound", "-c", COLLISIONS_LIST]);
+        assert!(success);
+        assert!(warnings.contains("like sun+flowerpot and sunflower+pot"));
+
+        // A joiner keeps them apart
+        let (success, _, warnings) = phraze(&[
+            "--compound",
+            "--compound-joiner",
+            "'",
+            "-c",
+            COLLISIONS_LIST,
+        ]);
+        assert!(success);
+        assert!(!warnings.contains("WARNING"));
+    }
+
+    #[test]
+    fn has_room_for_long_joiners() {
+        // The joiners count toward how much (locked, with the mlock feature) memory the
+        // passphrases need
+        let joiner = "_".repeat(22);
+        let (success, passphrase, _) = phraze(&[
+            "--compound",
+            "--compound-joiner",
+            &joiner,
+            "-w",
+            "2",
+            "-n",
+            "1",
+        ]);
+        assert!(success);
+        assert_eq!(passphrase.matches(&joiner).count(), 2);
+    }
+
+    #[test]
+    fn refuses_options_that_need_one_word_per_slot() {
+        let (success, passphrase, _) = phraze(&["--compound", "--inject-word", "elmhurst"]);
+        assert!(!success);
+        assert!(passphrase.is_empty());
+        let (success, _, _) = phraze(&["--compound-joiner", "'"]);
+        assert!(!success);
+    }
+}
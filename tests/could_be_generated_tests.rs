@@ -0,0 +1,53 @@
+mod common;
+
+mod could_be_generated_tests {
+    use crate::common::{phraze, phraze_command};
+
+    #[test]
+    fn says_yes_to_a_passphrase_from_the_list() {
+        let output = phraze(&["-l", "e", "--could-be-generated", "mocker-t-shirt-wafer"]);
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "Yes: all 3 words of this passphrase are on the list\n"
+        );
+    }
+
+    #[test]
+    fn says_no_to_a_passphrase_with_words_not_on_the_list() {
+        let output = phraze(&[
+            "-l",
+            "e",
+            "--could-be-generated",
+            "mocker-tshirt-wafer-zzyzx",
+        ]);
+        assert!(!output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.starts_with("No: "));
+        assert!(stdout.contains("(2 of 4): 'tshirt', 'zzyzx'"));
+    }
+
+    #[test]
+    fn checks_case_and_separator_settings() {
+        let check = |args: &[&str]| {
+            phraze_command()
+                .args(["-l", "e"])
+                .args(args)
+                .output()
+                .unwrap()
+                .status
+                .success()
+        };
+        assert!(!check(&["--could-be-generated", "Mocker-Wafer"]));
+        assert!(check(&["-t", "--could-be-generated", "Mocker-Wafer"]));
+        assert!(check(&[
+            "-t",
+            "-s",
+            "",
+            "--could-be-generated",
+            "MockerWafer"
+        ]));
+        assert!(!check(&["--could-be-generated", "mocker_wafer"]));
+        assert!(check(&["-s", "_n", "--could-be-generated", "mocker4wafer"]));
+    }
+}
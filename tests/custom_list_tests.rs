@@ -0,0 +1,265 @@
+mod common;
+
+mod custom_list_tests {
+    use crate::common::phraze;
+    use phraze::file_reader::*;
+    use phraze::weighted::*;
+    use std::path::Path;
+
+    #[test]
+    fn can_strip_a_byte_order_mark() {
+        let list = read_in_custom_list(Path::new("tests/fixtures/list_with_bom.txt")).unwrap();
+        assert_eq!(list, vec!["alpha", "beta", "gamma"]);
+        assert!(list.iter().all(|word| !word.contains('\u{feff}')));
+    }
+
+    #[test]
+    fn can_read_a_bilingual_list_with_definitions() {
+        let path = Path::new("tests/fixtures/bilingual_list.csv");
+        let list = read_in_definitions_list(path, 1, 2).unwrap();
+        assert_eq!(list.len(), 6);
+        assert!(list.contains(&("perro".to_string(), "dog".to_string())));
+        // Both definitions of a word are kept
+        assert!(list.contains(&(
+            "pez".to_string(),
+            "fish; fish (as food: pescado)".to_string()
+        )));
+        // Columns can be either way around
+        let reversed = read_in_definitions_list(path, 2, 1).unwrap();
+        assert!(reversed.contains(&("dog".to_string(), "perro".to_string())));
+        assert!(read_in_definitions_list(path, 1, 3).is_err());
+        assert!(read_in_definitions_list(path, 0, 1).is_err());
+    }
+
+    #[test]
+    fn prints_definitions_of_the_chosen_words() {
+        let output = phraze(&[
+            "-c",
+            "tests/fixtures/bilingual_list.csv",
+            "--show-definitions",
+            "-w",
+            "4",
+            "-n",
+            "3",
+        ]);
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        let list =
+            read_in_definitions_list(Path::new("tests/fixtures/bilingual_list.csv"), 1, 2).unwrap();
+        // Each passphrase's words, with their definitions, in order
+        let mut definition_lines = stderr.lines();
+        for passphrase in stdout.lines() {
+            for word in passphrase.split('-') {
+                let (_, definition) = list.iter().find(|(listed, _)| listed == word).unwrap();
+                assert_eq!(
+                    definition_lines.next(),
+                    Some(format!("{}: {}", word, definition).as_str())
+                );
+            }
+        }
+        // Definitions only go to standard error
+        assert!(!stdout.contains("dog") && !stdout.contains(':'));
+    }
+
+    #[test]
+    fn can_read_lists_with_windows_line_endings() {
+        let list = read_in_custom_list(Path::new("tests/fixtures/crlf_list.txt")).unwrap();
+        assert_eq!(list, vec!["alpha", "beta", "delta", "gamma"]);
+        // A "\r" left on a word would make it look different from the same word without one
+        assert!(list.iter().all(|word| !word.contains('\r')));
+
+        // Some lines ending in "\n" and some in "\r\n" (and a last line with neither)
+        let list =
+            read_in_custom_list(Path::new("tests/fixtures/mixed_line_endings_list.txt")).unwrap();
+        assert_eq!(list, vec!["alpha", "beta", "delta", "epsilon", "gamma"]);
+
+        let weighted_list =
+            read_in_weighted_custom_list(Path::new("tests/fixtures/crlf_weighted_list.txt"))
+                .unwrap();
+        assert_eq!(
+            weighted_list,
+            vec![
+                ("alpha".to_string(), 2.0),
+                ("beta".to_string(), 1.0),
+                ("gamma".to_string(), 1.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn errors_on_old_mac_line_endings() {
+        // Lines that only end in "\r" all come through as one line, so that's an error rather
+        // than a single strange word
+        let error = read_in_custom_list(Path::new("tests/fixtures/cr_only_list.txt")).unwrap_err();
+        assert!(error.starts_with("Line 1 of word list file has a carriage return"));
+    }
+
+    #[test]
+    fn can_read_directives_from_a_list_header() {
+        let path = Path::new("tests/fixtures/list_with_directives.txt");
+        let (list, directives) = read_in_custom_list_with_directives(path).unwrap();
+        assert_eq!(directives.separator, Some("_".to_string()));
+        assert_eq!(directives.title_case, Some(true));
+        // Directives aren't words, but comments after the header are
+        assert_eq!(list, vec!["# not a directive", "alpha", "beta", "gamma"]);
+        assert_eq!(read_in_custom_list(path).unwrap(), list);
+
+        // A separator can be quoted, to use a space
+        let path = std::env::temp_dir().join(format!("phraze-spaced-list-{}", std::process::id()));
+        std::fs::write(&path, "# separator: ' '\nalpha\n").unwrap();
+        let (list, directives) = read_in_custom_list_with_directives(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(list, vec!["alpha"]);
+        assert_eq!(directives.separator, Some(" ".to_string()));
+        assert_eq!(directives.title_case, None);
+        assert!(custom_list_from_reader("# case: shouting\nalpha\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn can_merge_words_in_different_normalizations() {
+        let list =
+            read_in_custom_list(Path::new("tests/fixtures/mixed_normalization_list.txt")).unwrap();
+        // "café" appears in both NFC and NFD, but should only be counted once
+        assert_eq!(list.len(), 2);
+        assert!(list.contains(&"tea".to_string()));
+
+        let weighted_list =
+            read_in_weighted_custom_list(Path::new("tests/fixtures/mixed_normalization_list.txt"))
+                .unwrap();
+        assert_eq!(weighted_list.len(), 2);
+        // Both spellings' weights are added up
+        assert_eq!(weighted_list[0].1, 2.0);
+    }
+
+    #[test]
+    fn can_read_in_a_weighted_list() {
+        let weighted_list =
+            read_in_weighted_custom_list(Path::new("tests/fixtures/weighted_list.txt")).unwrap();
+        assert_eq!(
+            weighted_list,
+            vec![
+                // apple appears twice, so its weights are added together
+                ("apple".to_string(), 4.0),
+                ("banana".to_string(), 1.0),
+                // cherry has no weight, so it gets a weight of 1
+                ("cherry".to_string(), 1.0),
+                ("date".to_string(), 0.5),
+            ]
+        );
+        let weights: Vec<f64> = weighted_list.iter().map(|(_, weight)| *weight).collect();
+        assert!(shannon_entropy_per_word(&weights) < 2.0);
+    }
+
+    #[test]
+    fn rejects_weighted_lists_with_non_positive_weights() {
+        let result =
+            read_in_weighted_custom_list(Path::new("tests/fixtures/weighted_list_bad_weight.txt"));
+        assert!(result.unwrap_err().contains("line 2"));
+    }
+
+    #[test]
+    fn returns_an_error_for_a_list_file_that_does_not_exist() {
+        let result = read_in_custom_list(Path::new("tests/fixtures/no_such_list.txt"));
+        assert!(result.unwrap_err().contains("Error reading word list file"));
+        assert!(read_lines(Path::new("tests/fixtures/no_such_list.txt")).is_err());
+    }
+
+    #[test]
+    fn returns_an_error_for_a_list_file_that_is_not_utf8() {
+        let path = Path::new("tests/fixtures/invalid_utf8_list.txt");
+        assert!(read_in_custom_list(path).is_err());
+        assert!(read_in_weighted_custom_list(path).is_err());
+        assert!(read_in_part_of_speech_list(path).is_err());
+        assert!(read_lines(path).is_err());
+    }
+
+    #[test]
+    fn returns_an_error_for_a_line_that_is_too_long() {
+        // One 2,000-byte "word", like a binary file without any newlines
+        let path = Path::new("tests/fixtures/oversized_line_list.txt");
+        let error = read_in_custom_list(path).unwrap_err();
+        assert!(error.contains("Line 2 is longer than 1024 bytes"));
+        assert!(read_in_weighted_custom_list(path).is_err());
+        assert!(read_lines(path).is_err());
+        let limits = ListLimits {
+            max_line_bytes: 2000,
+            ..ListLimits::default()
+        };
+        assert_eq!(
+            read_in_custom_list_with_limits(path, &limits)
+                .unwrap()
+                .len(),
+            3
+        );
+
+        // A reader that never ends, and never has a newline, stops at the first line
+        let endless = std::io::BufReader::new(std::io::repeat(0));
+        assert!(custom_list_from_reader(endless)
+            .unwrap_err()
+            .contains("Line 1 is longer"));
+    }
+
+    #[test]
+    fn returns_an_error_for_a_list_with_too_many_lines() {
+        // Stands in for a huge file, without having to make one
+        let path = Path::new("tests/fixtures/pos_list.csv");
+        let limits = ListLimits {
+            max_lines: 10,
+            ..ListLimits::default()
+        };
+        let error = read_in_custom_list_with_limits(path, &limits).unwrap_err();
+        assert!(error.contains("more than 10 lines"));
+        let limits = ListLimits {
+            max_lines: 15,
+            ..ListLimits::default()
+        };
+        assert!(read_in_custom_list_with_limits(path, &limits).is_ok());
+    }
+
+    #[test]
+    fn can_read_lines_without_cleaning_them_up() {
+        let lines = read_lines(Path::new("tests/fixtures/weighted_list.txt")).unwrap();
+        // Blank lines, weights and duplicates are all still there
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0], "apple\t3");
+        assert_eq!(lines[3], "");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn can_read_a_list_from_a_named_pipe() {
+        let path = std::env::temp_dir().join(format!("phraze-list-fifo-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let status = std::process::Command::new("mkfifo")
+            .arg(&path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let writer_path = path.clone();
+        let writer =
+            std::thread::spawn(move || std::fs::write(writer_path, "gamma\nalpha\nbeta\n"));
+        let list = read_in_custom_list(&path).unwrap();
+        writer.join().unwrap().unwrap();
+        assert_eq!(list, vec!["alpha", "beta", "gamma"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn can_read_a_list_from_a_unix_socket() {
+        use std::io::Write;
+        use std::os::unix::net::UnixListener;
+        let path = std::env::temp_dir().join(format!("phraze-list-socket-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream.write_all(b"delta\ncharlie\n").unwrap();
+        });
+        let list = read_in_custom_list(&path).unwrap();
+        server.join().unwrap();
+        assert_eq!(list, vec!["charlie", "delta"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,127 @@
+mod common;
+
+mod dice_tests {
+    use crate::common::phraze;
+    use phraze::dice::*;
+    use phraze::*;
+
+    #[test]
+    fn can_map_six_sided_rolls_to_words() {
+        let list = fetch_list(ListChoice::Eff);
+        let rolls_per_word = rolls_per_word(list.len(), 6).unwrap();
+        assert_eq!(rolls_per_word, 5);
+        // Like the printed EFF list, 11111 is the first word and 66666 is the last
+        assert_eq!(index_to_rolls(0, 6, 5), vec![1, 1, 1, 1, 1]);
+        assert_eq!(index_to_rolls(7775, 6, 5), vec![6, 6, 6, 6, 6]);
+        let words = parse_rolls("11111 66666", 6, 5).unwrap();
+        assert_eq!(list[rolls_to_index(&words[0], 6).unwrap()], "abacus");
+        assert_eq!(list[rolls_to_index(&words[1], 6).unwrap()], "zoom");
+    }
+
+    #[test]
+    fn can_map_ten_sided_rolls() {
+        assert_eq!(rolls_per_word(1000, 10).unwrap(), 3);
+        assert_eq!(index_to_rolls(0, 10, 3), vec![1, 1, 1]);
+        assert_eq!(index_to_rolls(999, 10, 3), vec![10, 10, 10]);
+        assert_eq!(index_to_rolls(123, 10, 3), vec![2, 3, 4]);
+        let words = parse_rolls("2 3 4, 10 10 10", 10, 3).unwrap();
+        assert_eq!(rolls_to_index(&words[0], 10).unwrap(), 123);
+        assert_eq!(rolls_to_index(&words[1], 10).unwrap(), 999);
+    }
+
+    #[test]
+    fn can_map_twenty_sided_rolls() {
+        assert_eq!(rolls_per_word(8000, 20).unwrap(), 3);
+        for index in [0, 1, 19, 20, 399, 400, 4321, 7999] {
+            let rolls = index_to_rolls(index, 20, 3);
+            assert!(rolls.iter().all(|roll| (1..=20).contains(roll)));
+            assert_eq!(rolls_to_index(&rolls, 20).unwrap(), index);
+        }
+        assert_eq!(index_to_rolls(7999, 20, 3), vec![20, 20, 20]);
+        assert!(rolls_to_index(&[21, 1, 1], 20).is_err());
+    }
+
+    #[test]
+    fn rejects_lists_that_are_not_a_power_of_the_base() {
+        assert!(rolls_per_word(8192, 6).is_err());
+        assert!(rolls_per_word(7776, 10).is_err());
+        assert!(rolls_per_word(1296, 1).is_err());
+        assert!(parse_rolls("1111", 6, 5).is_err());
+    }
+
+    #[test]
+    fn can_make_a_dice_worksheet() {
+        let list: Vec<String> = (0..36).map(|i| format!("word{}", i)).collect();
+        let worksheet = dice_worksheet(&list, "test list", 6).unwrap();
+        assert!(worksheet.contains("11  word0\n"));
+        // Words are grouped by their first roll
+        assert!(worksheet.contains("16  word5\n\n21  word6\n"));
+        assert!(worksheet.ends_with("66  word35\n"));
+        // Rolls of more than 9 are written with dashes between them
+        let list: Vec<String> = (0..100).map(|i| format!("word{}", i)).collect();
+        let worksheet = dice_worksheet(&list, "test list", 10).unwrap();
+        assert!(worksheet.contains(" 1-1  word0\n"));
+        assert!(worksheet.ends_with("10-10  word99\n"));
+
+        assert!(dice_worksheet(&list, "test list", 6).is_err());
+    }
+
+    #[test]
+    fn worksheet_has_an_entry_for_every_word() {
+        let output = phraze(&["-l", "e", "--worksheet"]);
+        assert!(output.status.success());
+        let worksheet = String::from_utf8(output.stdout).unwrap();
+        let entries: Vec<&str> = worksheet
+            .lines()
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_digit()))
+            .collect();
+        assert_eq!(entries.len(), 7776);
+        assert_eq!(entries[0], "11111  abacus");
+        assert_eq!(entries[7775], "66666  zoom");
+    }
+
+    #[test]
+    fn refuses_to_make_a_worksheet_for_lists_that_do_not_fit_the_dice() {
+        // The Orchard Street Medium list has 8,192 words
+        let output = phraze(&["-l", "m", "--worksheet"]);
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+    }
+
+    #[test]
+    fn can_find_how_much_of_a_list_fits_the_dice() {
+        assert_eq!(dice_compatible_length(8192, 6).unwrap(), 7776);
+        assert_eq!(dice_compatible_length(7776, 6).unwrap(), 7776);
+        assert_eq!(dice_compatible_length(7775, 6).unwrap(), 1296);
+        assert_eq!(dice_compatible_length(8192, 20).unwrap(), 8000);
+        assert_eq!(dice_compatible_length(6, 6).unwrap(), 6);
+        assert!(dice_compatible_length(5, 6).is_err());
+        assert!(dice_compatible_length(100, 1).is_err());
+    }
+
+    #[test]
+    fn can_cut_a_list_down_to_fit_the_dice() {
+        let output = phraze(&["-l", "m", "--dice-compatible", "--worksheet"]);
+        assert!(output.status.success());
+        let worksheet = String::from_utf8(output.stdout).unwrap();
+        assert!(worksheet.contains("(7776 words)"));
+        assert!(worksheet.ends_with("66666  uprising\n"));
+        let note = String::from_utf8(output.stderr).unwrap();
+        assert!(note.contains("dropped 416 of this list's 8192 words"));
+        assert!(note.contains("12.92 bits"));
+
+        // The same rolls always pick the same words
+        let output = phraze(&[
+            "-l",
+            "m",
+            "--dice-compatible",
+            "--dice-input",
+            "11111 66666",
+        ]);
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "abandon-uprising\n"
+        );
+    }
+}
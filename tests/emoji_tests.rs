@@ -0,0 +1,87 @@
+mod common;
+
+mod emoji_tests {
+    use crate::common::phraze;
+    use phraze::output::{heatmap, pad_words, passphrase_pieces};
+    use phraze::*;
+    use unicode_segmentation::UnicodeSegmentation;
+
+    /// Emoji made of more than one character: ZWJ sequences, skin tones and a keycap.
+    const SEQUENCES_LIST: &str = "tests/fixtures/emoji_sequences_list.txt";
+
+    fn sequences() -> Vec<String> {
+        std::fs::read_to_string(SEQUENCES_LIST)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn every_emoji_on_the_built_in_list_is_one_grapheme() {
+        let list = fetch_list(ListChoice::Emoji);
+        assert_eq!(list.len(), 975);
+        assert!(list.iter().all(|emoji| grapheme_length(emoji) == 1));
+        // Some of them are more than one character, like a ❤️ with its variation selector
+        assert!(list.iter().any(|emoji| emoji.chars().count() > 1));
+        assert!(list.contains(&"👁️‍🗨️"));
+    }
+
+    #[test]
+    fn can_pick_emoji_whole_even_without_separators() {
+        let list = fetch_list(ListChoice::Emoji);
+        for _ in 0..200 {
+            let passphrase = generate_passphrase(9, "", false, list);
+            // Joined together, the emoji still come apart into exactly the ones that were
+            // picked, rather than merging or splitting
+            let emoji: Vec<&str> = passphrase.graphemes(true).collect();
+            assert_eq!(emoji.len(), 9);
+            assert!(emoji.iter().all(|emoji| list.contains(emoji)));
+        }
+    }
+
+    #[test]
+    fn keeps_emoji_sequences_whole() {
+        let list = sequences();
+        for _ in 0..200 {
+            let passphrase = generate_passphrase(5, "-", false, &list);
+            assert!(passphrase
+                .split('-')
+                .all(|word| list.contains(&word.to_string())));
+            assert_eq!(grapheme_length(&passphrase), 9);
+        }
+    }
+
+    #[test]
+    fn lines_up_and_colors_emoji_sequences_as_single_units() {
+        let words = ["👩🏿‍🚀", "1️⃣", "🏳️‍🌈"];
+        let passphrase = words.join("-");
+        let word_lengths: Vec<usize> = words.iter().map(|word| word.chars().count()).collect();
+
+        assert_eq!(
+            passphrase_pieces(&passphrase, &word_lengths, 1),
+            ["👩🏿‍🚀-", "1️⃣-", "🏳️‍🌈"]
+        );
+        // Every emoji is one grapheme wide, so there's nothing to pad
+        assert_eq!(pad_words(&passphrase, &word_lengths, 1), passphrase);
+        // And every one is the same length, so they're all colored the same, each as a whole
+        let colored = heatmap(&passphrase, &word_lengths, 1);
+        let colors: Vec<&str> = colored
+            .split("\x1b[0m")
+            .filter(|piece| !piece.is_empty())
+            .zip(words)
+            .map(|(piece, word)| piece.trim_start_matches('-').strip_suffix(word).unwrap())
+            .collect();
+        assert_eq!(colors.len(), 3);
+        assert!(colors.iter().all(|color| *color == colors[0]));
+    }
+
+    #[test]
+    fn gives_a_hint_counting_each_emoji_once() {
+        let output = phraze(&["-c", SEQUENCES_LIST, "-w", "4", "--hint"]);
+        assert!(output.status.success());
+        assert!(String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("4 words: 1-1-1-1 letters"));
+    }
+}
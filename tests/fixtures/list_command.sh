@@ -0,0 +1,21 @@
+#!/bin/sh
+# Stands in for a program that makes a word list, for --list-cmd tests
+case "$1" in
+    words)
+        printf 'alpha\nbeta\ngamma\ndelta\n  beta  \n\nepsilon\n'
+        ;;
+    fail)
+        echo "couldn't reach the word service" >&2
+        exit 3
+        ;;
+    hang)
+        sleep 30
+        ;;
+    long-line)
+        head -c 5000 /dev/zero | tr '\0' 'a'
+        echo
+        ;;
+    endless)
+        yes word
+        ;;
+esac
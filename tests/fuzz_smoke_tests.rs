@@ -0,0 +1,65 @@
+#![cfg(feature = "fuzzing")]
+//! A quick smoke test of each fuzz target, so they don't bit-rot between real fuzzing runs.
+//! Run with `cargo test --features fuzzing`.
+
+mod fuzz_smoke_tests {
+    use phraze::fuzzing::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::fs;
+    use std::time::{Duration, Instant};
+
+    /// How long to throw random input at each target
+    const SMOKE_TEST_DURATION: Duration = Duration::from_secs(3);
+
+    /// Run a fuzz target over its committed corpus, then over random mutations of the corpus
+    /// (and purely random bytes) for a few seconds.
+    fn smoke_test(target_name: &str, target: fn(&[u8])) {
+        let corpus_dir = format!("{}/fuzz/corpus/{}", env!("CARGO_MANIFEST_DIR"), target_name);
+        let corpus: Vec<Vec<u8>> = fs::read_dir(corpus_dir)
+            .unwrap()
+            .map(|entry| fs::read(entry.unwrap().path()).unwrap())
+            .collect();
+        assert!(!corpus.is_empty());
+        for input in &corpus {
+            target(input);
+        }
+
+        // Bias random bytes toward ones that mean something to our parsers
+        const INTERESTING_BYTES: &[u8] = b"\n\r\t _nsb.-1e9\xef\xbb\xbf\xff\xc3";
+        let mut rng = StdRng::seed_from_u64(0);
+        let start = Instant::now();
+        while start.elapsed() < SMOKE_TEST_DURATION {
+            let mut input = if rng.gen_bool(0.5) {
+                corpus[rng.gen_range(0..corpus.len())].clone()
+            } else {
+                vec![]
+            };
+            for _ in 0..rng.gen_range(0..32) {
+                let byte = if rng.gen_bool(0.5) {
+                    INTERESTING_BYTES[rng.gen_range(0..INTERESTING_BYTES.len())]
+                } else {
+                    rng.gen()
+                };
+                let position = rng.gen_range(0..=input.len());
+                input.insert(position, byte);
+            }
+            target(&input);
+        }
+    }
+
+    #[test]
+    fn custom_list_target_does_not_panic() {
+        smoke_test("custom_list", fuzz_custom_list);
+    }
+
+    #[test]
+    fn weighted_custom_list_target_does_not_panic() {
+        smoke_test("weighted_custom_list", fuzz_weighted_custom_list);
+    }
+
+    #[test]
+    fn separator_target_does_not_panic() {
+        smoke_test("separator", fuzz_separator);
+    }
+}
@@ -0,0 +1,268 @@
+mod common;
+
+mod generate_passphrase_tests {
+    use phraze::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn can_generate_the_same_passphrases_from_the_same_seeded_rng() {
+        let list = fetch_list(ListChoice::Medium);
+        let mut rng1 = StdRng::seed_from_u64(42);
+        let mut rng2 = StdRng::seed_from_u64(42);
+        for _ in 0..5 {
+            assert_eq!(
+                generate_passphrase_with_rng(&mut rng1, 7, "-", false, list),
+                generate_passphrase_with_rng(&mut rng2, 7, "-", false, list)
+            );
+        }
+    }
+
+    #[test]
+    fn gives_an_error_rather_than_panicking_for_an_empty_list() {
+        let empty: [&str; 0] = [];
+        assert!(try_generate_passphrase(7, "-", false, &empty[..]).is_err());
+        let mut rng = StdRng::seed_from_u64(42);
+        assert!(try_generate_passphrase_with_rng(&mut rng, 7, "-", false, &empty[..]).is_err());
+        assert!(try_calculate_number_words_needed(None, None, 0, empty.len(), None).is_err());
+        assert!(try_calculate_number_words_needed(Some(7), None, 0, empty.len(), None).is_err());
+        // A list of 1 word can't reach a minimum entropy either
+        assert!(try_calculate_number_words_needed(None, Some(80), 0, 1, None).is_err());
+
+        let list = fetch_list(ListChoice::Medium);
+        assert_eq!(
+            try_calculate_number_words_needed(None, None, 0, list.len(), None),
+            Ok(7)
+        );
+        assert_eq!(
+            try_generate_passphrase_with_rng(&mut rng, 7, "-", false, list)
+                .unwrap()
+                .split('-')
+                .count(),
+            7
+        );
+    }
+
+    #[test]
+    fn built_in_and_custom_lists_are_both_word_lists() {
+        fn first_and_last<L: WordList + ?Sized>(list: &L) -> (String, String) {
+            (
+                list.word(0).to_string(),
+                list.word(list.len() - 1).to_string(),
+            )
+        }
+        let built_in = fetch_list(ListChoice::Effshort);
+        let custom: Vec<String> = built_in.iter().map(|word| word.to_string()).collect();
+        assert_eq!(first_and_last(built_in), first_and_last(&custom));
+        assert_eq!(WordList::len(built_in), 1296);
+        assert!(custom.words().eq(built_in.iter().copied()));
+        assert!(!WordList::is_empty(&custom));
+
+        let passphrase = generate_passphrase(3, "-", false, &custom);
+        assert!(passphrase
+            .split('-')
+            .all(|word| custom.contains(&word.to_string())));
+    }
+
+    #[test]
+    fn choosing_words_from_a_word_list_matches_choosing_from_a_slice() {
+        // Seeded passphrases from before word lists were a trait should come out the same
+        use rand::seq::SliceRandom;
+        let list = fetch_list(ListChoice::Long);
+        let mut rng1 = StdRng::seed_from_u64(7);
+        let mut rng2 = StdRng::seed_from_u64(7);
+        for _ in 0..100 {
+            assert_eq!(
+                choose_word(&mut rng1, list),
+                *list.choose(&mut rng2).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn can_pick_words_with_exactly_the_bits_they_need() {
+        use rand::rngs::mock::StepRng;
+        let list = ["a", "b", "c", "d"];
+        // Each word takes the next 2 bits, starting from the top, so 32 words use up one u64
+        // and the 33rd starts on the next one
+        let mut rng = StepRng::new(0b10_01_11_00 << 56, 1 << 62);
+        let words = generate_words_from_bits(&mut rng, 33, &list).unwrap();
+        assert_eq!(words[..4], ["c", "b", "d", "a"]);
+        assert_eq!(words[32], "d");
+
+        // With 13 bits a word, the fifth word is made of the last 12 bits of one u64 and the
+        // first bit of the next
+        let list: Vec<String> = (0..8192).map(|i| i.to_string()).collect();
+        let mut rng = StepRng::new(0, u64::MAX);
+        let words = generate_words_from_bits(&mut rng, 6, &list).unwrap();
+        assert_eq!(words, ["0", "0", "0", "0", "1", "8191"]);
+
+        assert!(generate_words_from_bits(&mut rng, 3, fetch_list(ListChoice::Eff)).is_err());
+    }
+
+    #[test]
+    fn reusing_an_rng_continues_its_stream() {
+        let list = fetch_list(ListChoice::Medium);
+        let mut rng = StdRng::seed_from_u64(42);
+        let first = generate_passphrase_with_rng(&mut rng, 7, "-", false, list);
+        let second = generate_passphrase_with_rng(&mut rng, 7, "-", false, list);
+        assert_ne!(first, second);
+        assert_eq!(second.split('-').count(), 7);
+    }
+
+    #[test]
+    fn qwerty_flow_gives_up_on_lists_without_comfortable_transitions() {
+        use phraze::keyboard::*;
+        // "p" and "q" are on opposite ends of the keyboard, so no transition is comfortable
+        let list = ["qp", "qwop"];
+        let mut rng = StdRng::seed_from_u64(42);
+        let words = generate_words_with_qwerty_flow(&mut rng, 6, &list);
+        assert_eq!(words.len(), 6);
+        // With no comfortable transitions at all, words are uniform, and no entropy is lost
+        assert_eq!(qwerty_flow_entropy(6, &list), 6.0);
+    }
+
+    #[test]
+    fn qwerty_flow_reduces_entropy_slightly() {
+        use phraze::keyboard::*;
+        let list = fetch_list(ListChoice::Qwerty);
+        let full_entropy = (list.len() as f64).log2() * 8.0;
+        let flow_entropy = qwerty_flow_entropy(8, list);
+        assert!(flow_entropy < full_entropy);
+        // No word can become more than QWERTY_FLOW_MAX_TRIES times as likely as it would be
+        // normally, which bounds how much entropy each of the 7 transitions can lose
+        assert!(flow_entropy > full_entropy - 7.0 * (QWERTY_FLOW_MAX_TRIES as f64).log2());
+        let mut rng = StdRng::seed_from_u64(42);
+        let words = generate_words_with_qwerty_flow(&mut rng, 8, list);
+        assert_eq!(words.len(), 8);
+    }
+}
+
+mod seeded_generation_tests {
+    use phraze::rng::*;
+    use phraze::*;
+
+    fn config() -> PassphraseConfig {
+        PassphraseConfig {
+            number_of_words: 7,
+            separator: "-".to_string(),
+            title_case: false,
+        }
+    }
+
+    #[test]
+    fn nth_passphrase_matches_sequential_generation() {
+        let list = fetch_list(ListChoice::Medium);
+        let config = config();
+        let sequential: Vec<String> = (0..10)
+            .map(|i| {
+                generate_passphrase_with_rng(
+                    &mut seeded_rng(99, i),
+                    config.number_of_words,
+                    &config.separator,
+                    config.title_case,
+                    list,
+                )
+            })
+            .collect();
+        assert_eq!(nth_passphrase(99, 7, &config, list).unwrap(), sequential[7]);
+        assert_eq!(nth_passphrase(99, 0, &config, list).unwrap(), sequential[0]);
+        assert_eq!(nth_passphrase(99, 9, &config, list).unwrap(), sequential[9]);
+        let empty: &[&str] = &[];
+        assert!(nth_passphrase(99, 0, &config, empty).is_err());
+    }
+
+    #[test]
+    fn nth_passphrase_matches_the_cli() {
+        let phraze = |args: &[&str]| {
+            let output = crate::common::phraze(args);
+            assert!(output.status.success());
+            String::from_utf8(output.stdout).unwrap()
+        };
+        let batch = phraze(&["--seed", "7", "-n", "6", "-w", "4"]);
+        let sixth = batch.lines().nth(5).unwrap();
+        let config = PassphraseConfig {
+            number_of_words: 4,
+            ..config()
+        };
+        assert_eq!(
+            nth_passphrase(7, 5, &config, fetch_list(ListChoice::Medium)).unwrap(),
+            sixth
+        );
+        let on_its_own = phraze(&["--seed", "7", "--index", "5", "-n", "1", "-w", "4"]);
+        assert_eq!(on_its_own.trim_end(), sixth);
+
+        // So does everything else a config can hold
+        let batch = phraze(&[
+            "--seed", "7", "-n", "3", "-w", "4", "-t", "-s", "_", "-l", "s",
+        ]);
+        let config = PassphraseConfig {
+            number_of_words: 4,
+            separator: "_".to_string(),
+            title_case: true,
+        };
+        assert_eq!(
+            nth_passphrase(7, 2, &config, fetch_list(ListChoice::Effshort)).unwrap(),
+            batch.lines().nth(2).unwrap()
+        );
+    }
+
+    #[test]
+    fn different_indexes_and_seeds_give_different_passphrases() {
+        let list = fetch_list(ListChoice::Medium);
+        let config = config();
+        assert_ne!(
+            nth_passphrase(99, 0, &config, list).unwrap(),
+            nth_passphrase(99, 1, &config, list).unwrap()
+        );
+        assert_ne!(
+            nth_passphrase(99, 0, &config, list).unwrap(),
+            nth_passphrase(100, 0, &config, list).unwrap()
+        );
+    }
+
+    #[test]
+    fn salts_separate_passphrases_made_from_the_same_seed() {
+        let list = fetch_list(ListChoice::Medium);
+        let config = config();
+        let salted = |salt: &str, index: u64| {
+            generate_passphrase_with_rng(
+                &mut salted_seeded_rng(99, salt, index),
+                config.number_of_words,
+                &config.separator,
+                config.title_case,
+                list,
+            )
+        };
+        // Same seed and salt reproduce exactly
+        assert_eq!(salted("mail", 3), salted("mail", 3));
+        // A different salt, or no salt, gives a different passphrase
+        assert_ne!(salted("mail", 3), salted("bank", 3));
+        assert_ne!(
+            salted("mail", 0),
+            nth_passphrase(99, 0, &config, list).unwrap()
+        );
+        // Indices still pick out different passphrases within a salted batch
+        assert_ne!(salted("mail", 0), salted("mail", 1));
+    }
+
+    #[test]
+    fn token_responses_give_their_own_passphrases() {
+        let list = fetch_list(ListChoice::Medium);
+        let config = config();
+        let from_token = |response: &[u8], index: u64| {
+            generate_passphrase_with_rng(
+                &mut token_rng(response, index),
+                config.number_of_words,
+                &config.separator,
+                config.title_case,
+                list,
+            )
+        };
+        let response = [7u8; 20];
+        // The same response always gives the same passphrases
+        assert_eq!(from_token(&response, 0), from_token(&response, 0));
+        assert_ne!(from_token(&response, 0), from_token(&response, 1));
+        assert_ne!(from_token(&response, 0), from_token(&[8u8; 20], 0));
+    }
+}
@@ -0,0 +1,62 @@
+mod grammar_tests {
+    use phraze::file_reader::*;
+    use phraze::grammar::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::path::PathBuf;
+
+    fn pos_list() -> Vec<(String, String)> {
+        read_in_part_of_speech_list(&PathBuf::from("tests/fixtures/pos_list.csv")).unwrap()
+    }
+
+    #[test]
+    fn can_read_a_part_of_speech_list() {
+        let list = pos_list();
+        assert_eq!(list.len(), 15);
+        // Parts of speech are lowercased
+        assert!(list.contains(&("brave".to_string(), "adj".to_string())));
+        // A word can have more than one part of speech
+        assert!(list.contains(&("light".to_string(), "noun".to_string())));
+        assert!(list.contains(&("light".to_string(), "adj".to_string())));
+    }
+
+    #[test]
+    fn errors_on_a_line_without_a_part_of_speech() {
+        let result = part_of_speech_list_from_reader("happy,adj\notter\n".as_bytes());
+        assert!(result.unwrap_err().contains("Line 2"));
+    }
+
+    #[test]
+    fn words_match_their_slots_part_of_speech() {
+        let list = pos_list();
+        let slots = parse_grammar("adj noun verb noun", &list).unwrap();
+        assert_eq!(slots.len(), 4);
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..50 {
+            let words = generate_grammatical_words(&mut rng, &slots);
+            assert_eq!(words.len(), 4);
+            for (word, part_of_speech) in words.iter().zip(["adj", "noun", "verb", "noun"]) {
+                assert!(list.contains(&(word.to_string(), part_of_speech.to_string())));
+            }
+        }
+    }
+
+    #[test]
+    fn grammar_entropy_is_the_product_of_slot_sizes() {
+        let list = pos_list();
+        // 5 adjectives, 5 nouns, 4 verbs, 5 nouns
+        let slots = parse_grammar("adj noun verb noun", &list).unwrap();
+        let expected = (5.0 * 5.0 * 4.0 * 5.0f64).log2();
+        assert!((grammar_entropy(&slots) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn errors_when_a_part_of_speech_has_too_few_words() {
+        let list = pos_list();
+        // Only one adverb
+        assert!(parse_grammar("adj noun adv", &list).is_err());
+        // No conjunctions at all
+        assert!(parse_grammar("noun conj noun", &list).is_err());
+        assert!(parse_grammar("", &list).is_err());
+    }
+}
@@ -0,0 +1,35 @@
+mod common;
+
+mod heatmap_tests {
+    use crate::common::{phraze, PHRAZE};
+    use std::process::Command;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn can_color_words_in_a_terminal() {
+        // util-linux's script runs Phraze in a pseudo-terminal
+        let command = format!("{} --heatmap -w 4 -s _ -l e", PHRAZE);
+        let output = match Command::new("script")
+            .args(["-qec", &command, "/dev/null"])
+            .env_remove("NO_COLOR")
+            .output()
+        {
+            Ok(output) => output,
+            Err(_) => return,
+        };
+        assert!(output.status.success());
+        let shown = String::from_utf8(output.stdout).unwrap();
+        assert!(shown.contains("blue is shorter than average"));
+        let passphrase = shown.lines().last().unwrap();
+        // Each of the 4 words gets a color and a reset, and separators are left alone
+        assert_eq!(passphrase.matches("\x1b[38;5;").count(), 4);
+        assert_eq!(passphrase.matches("\x1b[0m_").count(), 3);
+    }
+
+    #[test]
+    fn refuses_to_draw_a_heatmap_if_not_printing_to_a_terminal() {
+        let output = phraze(&["--heatmap"]);
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+    }
+}
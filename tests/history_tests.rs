@@ -0,0 +1,59 @@
+mod history_tests {
+    use phraze::history::*;
+    use std::path::PathBuf;
+
+    fn temp_history_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("phraze-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn can_exclude_words_recorded_in_a_history_file() {
+        let path = temp_history_path("history-exclusion");
+        let mut history = History::load(&path).unwrap();
+        history.record("beta");
+        history.record("delta");
+        history.save(&path).unwrap();
+
+        // Words themselves should not be in the file
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(!contents.contains("beta"));
+        assert!(!contents.contains("delta"));
+
+        let history = History::load(&path).unwrap();
+        let mut list = vec!["alpha", "beta", "gamma", "delta"];
+        history.exclude_used_words(&mut list);
+        assert_eq!(list, vec!["alpha", "gamma"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn history_file_is_only_readable_by_its_owner() {
+        use std::os::unix::fs::PermissionsExt;
+        let path = temp_history_path("history-permissions");
+        // Even a file that was readable by everyone before
+        std::fs::write(&path, "").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        History::new().save(&path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn can_warn_when_history_nearly_exhausts_a_list() {
+        assert!(pool_exhaustion_warning(1000, 1296).is_none());
+        assert!(pool_exhaustion_warning(129, 1296).is_some());
+        assert!(pool_exhaustion_warning(0, 1296).is_some());
+    }
+
+    #[test]
+    fn rejects_history_files_without_a_salt() {
+        let path = temp_history_path("history-bad");
+        std::fs::write(&path, "abc123\n").unwrap();
+        assert!(History::load(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
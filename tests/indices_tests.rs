@@ -0,0 +1,110 @@
+mod common;
+
+mod indices_tests {
+    use phraze::indices::*;
+    use phraze::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn can_round_trip_a_passphrase_through_an_index_file() {
+        let list = fetch_list(ListChoice::Medium);
+        let mut rng = StdRng::seed_from_u64(3);
+        let words = generate_words(&mut rng, 7, list);
+        let passphrase = assemble_passphrase(&mut rng, &words, "-", false);
+
+        let path = std::env::temp_dir().join(format!("phraze-indices-{}", std::process::id()));
+        let indices = word_indices(&words, list).unwrap();
+        write_index_file(&path, &list_fingerprint(list), &[indices]).unwrap();
+
+        let (fingerprint, passphrase_indices) = read_index_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(fingerprint, list_fingerprint(list));
+        assert_eq!(passphrase_indices.len(), 1);
+        let replayed_words = words_from_indices(&passphrase_indices[0], list).unwrap();
+        assert_eq!(replayed_words, words);
+        assert_eq!(
+            assemble_passphrase(&mut rng, &replayed_words, "-", false),
+            passphrase
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn index_file_is_only_readable_by_its_owner() {
+        use std::os::unix::fs::PermissionsExt;
+        let path =
+            std::env::temp_dir().join(format!("phraze-indices-permissions-{}", std::process::id()));
+        let list = fetch_list(ListChoice::Medium);
+        write_index_file(&path, &list_fingerprint(list), &[vec![0, 1, 2]]).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn different_lists_have_different_fingerprints() {
+        assert_ne!(
+            list_fingerprint(fetch_list(ListChoice::Qwerty)),
+            list_fingerprint(fetch_list(ListChoice::Alpha))
+        );
+    }
+}
+
+mod index_encoding_tests {
+    use phraze::indices::*;
+
+    #[test]
+    fn can_round_trip_indices_through_base32_and_base58() {
+        let indices = vec![0, 8191, 4096, 17, 2222, 1, 7000];
+        for encoding in [IndexEncoding::Base32, IndexEncoding::Base58] {
+            let encoded = encode_indices(&indices, 8192, encoding).unwrap();
+            assert_eq!(decode_indices(&encoded, 8192, encoding).unwrap(), indices);
+        }
+    }
+
+    #[test]
+    fn encoded_indices_are_compact() {
+        // 7 words at 13 bits each is 91 bits, plus a byte for the word count, so 13 bytes
+        let indices = vec![5; 7];
+        let encoded = encode_indices(&indices, 8192, IndexEncoding::Base32).unwrap();
+        assert_eq!(encoded.len(), 21);
+        let encoded = encode_indices(&indices, 8192, IndexEncoding::Base58).unwrap();
+        assert!(encoded.len() <= 18);
+    }
+
+    #[test]
+    fn can_round_trip_indices_from_tiny_lists() {
+        let indices = vec![0, 1, 1, 0, 1];
+        let encoded = encode_indices(&indices, 2, IndexEncoding::Base58).unwrap();
+        assert_eq!(
+            decode_indices(&encoded, 2, IndexEncoding::Base58).unwrap(),
+            indices
+        );
+    }
+
+    #[test]
+    fn can_round_trip_a_passphrase_through_a_token() {
+        let phraze = |args: &[&str]| {
+            let output = crate::common::phraze(args);
+            assert!(output.status.success());
+            String::from_utf8(output.stdout).unwrap()
+        };
+        // --token prints a base58 token after the passphrase, and --from-token rebuilds it
+        let stdout = phraze(&["--token", "-l", "l", "-n", "5"]);
+        for line in stdout.lines() {
+            let (passphrase, token) = line.split_once('\t').unwrap();
+            let rebuilt = phraze(&["--from-token", token, "-l", "l"]);
+            assert_eq!(rebuilt.trim_end(), passphrase);
+            // It's the same as --indices with base58
+            let rebuilt = phraze(&["--indices", token, "--encode-indices", "base58", "-l", "l"]);
+            assert_eq!(rebuilt.trim_end(), passphrase);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_encoded_indices() {
+        assert!(decode_indices("0OIl", 8192, IndexEncoding::Base58).is_err());
+        assert!(decode_indices("", 8192, IndexEncoding::Base32).is_err());
+    }
+}
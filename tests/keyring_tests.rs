@@ -0,0 +1,26 @@
+mod common;
+
+mod keyring_tests {
+    use crate::common::phraze;
+
+    #[test]
+    fn refuses_to_store_more_than_one_passphrase() {
+        let output = phraze(&["--store-keyring", "phraze-tests", "nobody", "-n", "2"]);
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("-n more than 1"));
+    }
+
+    #[cfg(not(feature = "keyring"))]
+    #[test]
+    fn says_so_if_built_without_keyring_support() {
+        let output = phraze(&["--store-keyring", "phraze-tests", "nobody"]);
+        assert!(!output.status.success());
+        // The passphrase isn't printed instead
+        assert!(output.stdout.is_empty());
+        assert!(String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("the `keyring` feature"));
+    }
+}
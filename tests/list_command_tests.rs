@@ -0,0 +1,76 @@
+#![cfg(unix)]
+
+mod common;
+
+mod list_command_tests {
+    use crate::common::phraze_command;
+    use std::process::Output;
+    use std::time::{Duration, Instant};
+
+    /// Run Phraze with its list from the helper script, in the given mode, plus any other
+    /// arguments.
+    fn phraze_with_list_command(mode: &str, args: &[&str]) -> Output {
+        phraze_command()
+            .args([
+                "--list-cmd",
+                "sh",
+                "--list-cmd-arg",
+                "tests/fixtures/list_command.sh",
+                "--list-cmd-arg",
+                mode,
+            ])
+            .args(args)
+            .output()
+            .unwrap()
+    }
+
+    fn stderr(output: &Output) -> String {
+        String::from_utf8(output.stderr.clone()).unwrap()
+    }
+
+    #[test]
+    fn can_make_passphrases_from_a_commands_output() {
+        let output = phraze_with_list_command("words", &["-w", "4", "-n", "10"]);
+        assert!(output.status.success());
+        let words = ["alpha", "beta", "gamma", "delta", "epsilon"];
+        for passphrase in String::from_utf8(output.stdout).unwrap().lines() {
+            assert!(passphrase.split('-').all(|word| words.contains(&word)));
+        }
+        // The duplicate "beta" and the blank line are cleaned up like in any custom list
+        let output = phraze_with_list_command("words", &["--stats-only", "-w", "4"]);
+        assert!(String::from_utf8(output.stdout)
+            .unwrap()
+            .contains("List length: 5 words"));
+    }
+
+    #[test]
+    fn shows_why_a_list_command_failed() {
+        let output = phraze_with_list_command("fail", &[]);
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+        let stderr = stderr(&output);
+        assert!(stderr.contains("exit status: 3"));
+        assert!(stderr.contains("couldn't reach the word service"));
+    }
+
+    #[test]
+    fn stops_a_list_command_that_takes_too_long() {
+        let started = Instant::now();
+        let output = phraze_with_list_command("hang", &["--list-cmd-timeout", "1"]);
+        assert!(!output.status.success());
+        assert!(stderr(&output).contains("took longer than --list-cmd-timeout"));
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn limits_how_much_a_list_command_can_print() {
+        let output = phraze_with_list_command("long-line", &[]);
+        assert!(!output.status.success());
+        assert!(stderr(&output).contains("list command sh's output"));
+
+        // A command that never stops printing is stopped once it's printed too many lines
+        let output = phraze_with_list_command("endless", &["--list-cmd-timeout", "60"]);
+        assert!(!output.status.success());
+        assert!(stderr(&output).contains("list command sh's output"));
+    }
+}
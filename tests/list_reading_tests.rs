@@ -25,6 +25,16 @@ mod minimum_entropy_tests {
         assert!(list.len() == 1633);
     }
 
+    #[test]
+    fn can_iterate_over_the_words_of_each_list() {
+        for list_choice in ListChoice::all() {
+            assert_eq!(list_choice.words().count(), fetch_list(list_choice).len());
+            assert!(list_choice
+                .words()
+                .eq(fetch_list(list_choice).iter().copied()));
+        }
+    }
+
     #[test]
     fn can_read_in_lists_without_any_blank_words() {
         let list = fetch_list(ListChoice::Medium);
@@ -0,0 +1,64 @@
+mod common;
+
+mod messages_tests {
+    use crate::common::{phraze, phraze_command};
+
+    fn entropy_line(args: &[&str], lang: &str) -> String {
+        let output = phraze_command()
+            .args(args)
+            .env_remove("LC_ALL")
+            .env_remove("LC_MESSAGES")
+            .env("LANG", lang)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        stderr.lines().next().unwrap().to_string()
+    }
+
+    #[test]
+    fn can_print_messages_in_the_language_asked_for() {
+        assert_eq!(
+            entropy_line(&["--lang", "es", "-v", "-w", "4"], "en_US.UTF-8"),
+            "La frase de contraseña tiene una entropía estimada de 52.00 bits (4 palabras de una lista de 8192 palabras)"
+        );
+        assert_eq!(
+            entropy_line(&["--lang", "en", "-v", "-n", "2", "-w", "4"], "es_ES.UTF-8"),
+            "Each passphrase has an estimated 52.00 bits of entropy (4 words from a list of 8192 words)"
+        );
+    }
+
+    #[test]
+    fn can_use_the_language_of_the_locale() {
+        assert_eq!(
+            entropy_line(&["-v", "-w", "4"], "es_ES.UTF-8"),
+            "La frase de contraseña tiene una entropía estimada de 52.00 bits (4 palabras de una lista de 8192 palabras)"
+        );
+        // Phraze doesn't have French messages, so falls back to English
+        assert_eq!(
+            entropy_line(&["-v", "-w", "4"], "fr_FR.UTF-8"),
+            "Passphrase has an estimated 52.00 bits of entropy (4 words from a list of 8192 words)"
+        );
+    }
+
+    #[test]
+    fn can_print_warnings_in_another_language() {
+        let output = phraze(&["--lang", "es", "--pow2", "-l", "s", "-w", "2"]);
+        assert!(output.status.success());
+        assert!(String::from_utf8(output.stderr)
+            .unwrap()
+            .starts_with("ADVERTENCIA: --pow2 quitó 272 de las 1296 palabras"));
+    }
+
+    #[test]
+    fn can_print_errors_in_another_language() {
+        let output = phraze(&["--lang", "es", "--heatmap"]);
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("--heatmap solo funciona"));
+
+        let unknown = phraze(&["--lang", "fr"]);
+        assert!(!unknown.status.success());
+    }
+}
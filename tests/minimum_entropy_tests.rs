@@ -1,26 +1,66 @@
+mod common;
+
 mod minimum_entropy_tests {
+    use crate::common::{phraze, phraze_command};
+    use phraze::weighted::shannon_entropy_per_word;
     use phraze::*;
 
     #[test]
     fn can_accurately_calculate_the_number_of_words_to_put_in_a_passphrase_given_a_desired_number_of_words(
     ) {
-        assert_eq!(calculate_number_words_needed(Some(8), None, 0, 4000), 8);
+        assert_eq!(
+            calculate_number_words_needed(Some(8), None, 0, 4000, None),
+            8
+        );
     }
 
     #[test]
     fn can_accurately_calculate_the_number_of_words_to_put_in_a_passphrase_given_a_strength_count()
     {
         // 100 / 13 == a little over 7, so need 8 words to satisfy
-        assert_eq!(calculate_number_words_needed(None, None, 1, 8192), 8);
+        assert_eq!(calculate_number_words_needed(None, None, 1, 8192, None), 8);
         // 120 / 13 == a little over 9, so need 10 words to satisfy
-        assert_eq!(calculate_number_words_needed(None, None, 2, 8192), 10);
+        assert_eq!(calculate_number_words_needed(None, None, 2, 8192, None), 10);
     }
 
     #[test]
     fn can_accurately_calculate_the_number_of_words_to_put_in_a_passphrase_given_a_desired_minimum_entropy(
     ) {
-        assert_eq!(calculate_number_words_needed(None, Some(102), 0, 8192), 8);
-        assert_eq!(calculate_number_words_needed(None, Some(106), 0, 8192), 9);
+        assert_eq!(
+            calculate_number_words_needed(None, Some(102), 0, 8192, None),
+            8
+        );
+        assert_eq!(
+            calculate_number_words_needed(None, Some(106), 0, 8192, None),
+            9
+        );
+    }
+
+    #[test]
+    fn weighted_lists_need_more_words_for_the_same_minimum_entropy() {
+        // 4 equally likely words are worth 2 bits each, so 20 bits takes 10 words
+        assert_eq!(
+            calculate_number_words_needed(None, Some(20), 0, 4, None),
+            10
+        );
+        let even = shannon_entropy_per_word(&[1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(
+            calculate_number_words_needed(None, Some(20), 0, 4, Some(even)),
+            10
+        );
+        // Uneven weights make each word worth about 1.65 bits, so 20 bits takes 13 words
+        let uneven = shannon_entropy_per_word(&[1.0, 2.0, 5.0, 8.0]);
+        assert!(uneven < 2.0);
+        assert_eq!(
+            calculate_number_words_needed(None, Some(20), 0, 4, Some(uneven)),
+            13
+        );
+        assert_eq!(words_needed_for_entropy(20, uneven), 13);
+        // An exact number of words still wins
+        assert_eq!(
+            calculate_number_words_needed(Some(3), Some(20), 0, 4, Some(uneven)),
+            3
+        );
     }
 
     #[test]
@@ -39,4 +79,102 @@ mod minimum_entropy_tests {
             4
         );
     }
+
+    #[test]
+    fn can_recommend_word_counts_for_each_strength_level() {
+        // 13 bits per word
+        assert_eq!(
+            recommended_word_counts(ListChoice::Medium),
+            [(60, 5), (80, 7), (100, 8), (128, 10)]
+        );
+        // About 10.34 bits per word
+        assert_eq!(
+            recommended_word_counts(ListChoice::Effshort),
+            [(60, 6), (80, 8), (100, 10), (128, 13)]
+        );
+        for list_choice in ListChoice::all() {
+            let bits: Vec<usize> = recommended_word_counts(list_choice)
+                .iter()
+                .map(|(bits, _)| *bits)
+                .collect();
+            assert_eq!(bits, [60, 80, 100, 128]);
+        }
+    }
+
+    #[test]
+    fn can_calculate_the_minimum_list_size_for_a_target() {
+        // 8 words of 10 bits each
+        assert_eq!(minimum_list_size(80, 8).unwrap(), 1024);
+        // 128 / 6 is 21.33 bits per word, and 2^21.33 is a little over 2,642,245
+        assert_eq!(minimum_list_size(128, 6).unwrap(), 2_642_246);
+        // 2^(90 / 7) is about 7,419.69, so the EFF long list (7,776 words) is long enough
+        assert_eq!(minimum_list_size(90, 7).unwrap(), 7_420);
+        assert_eq!(minimum_list_size(0, 4).unwrap(), 1);
+        assert!(minimum_list_size(80, 0).is_err());
+        assert!(minimum_list_size(256, 1).is_err());
+        // Going back the other way gives the same number of words
+        let list_length = minimum_list_size(100, 7).unwrap() as usize;
+        assert_eq!(
+            convert_minimum_entropy_to_number_of_words(100, list_length),
+            7
+        );
+        assert_eq!(
+            convert_minimum_entropy_to_number_of_words(100, list_length - 1),
+            8
+        );
+    }
+
+    #[test]
+    fn can_floor_printed_entropy_estimates() {
+        // 5 words from the EFF short list give 5 × log2(1296) = 51.70 bits
+        let estimate = |args: &[&str]| {
+            let output = phraze_command()
+                .args(["-v", "-l", "s", "-w", "5"])
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(output.status.success());
+            String::from_utf8(output.stderr).unwrap()
+        };
+        assert!(estimate(&[]).contains("an estimated 51.70 bits of entropy"));
+        assert!(estimate(&["--entropy-precision", "0"]).contains("an estimated 52 bits"));
+        assert!(
+            estimate(&["--entropy-precision", "0", "--entropy-round", "floor"])
+                .contains("an estimated 51 bits")
+        );
+        assert!(
+            estimate(&["--entropy-precision", "4", "--entropy-round", "floor"])
+                .contains("an estimated 51.6992 bits")
+        );
+    }
+
+    #[test]
+    fn can_show_what_separators_and_case_would_add() {
+        let output = phraze(&[
+            "-v",
+            "-l",
+            "e",
+            "-w",
+            "6",
+            "-s",
+            "_s",
+            "--random-case",
+            "-n",
+            "20",
+        ]);
+        assert!(output.status.success());
+        let report = String::from_utf8(output.stderr).unwrap();
+        // The estimate itself only counts the words
+        assert!(report.contains("an estimated 77.55 bits of entropy"));
+        // 5 symbols are worth 5 × log2(27) = 23.77 bits, and 6 coin flips are worth 6 bits
+        assert!(report.contains("Words only: 77.55 bits; with separators and case: 107.32 bits"));
+
+        let passphrases = String::from_utf8(output.stdout).unwrap();
+        let first_letters: Vec<char> = passphrases
+            .split(|c: char| !c.is_alphabetic())
+            .filter_map(|word| word.chars().next())
+            .collect();
+        assert!(first_letters.iter().any(|c| c.is_uppercase()));
+        assert!(first_letters.iter().any(|c| c.is_lowercase()));
+    }
 }
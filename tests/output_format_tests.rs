@@ -0,0 +1,71 @@
+mod common;
+
+mod output_format_tests {
+    use crate::common::phraze;
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn can_print_passphrases_as_yaml() {
+        let output = phraze(&["--format", "yaml", "-n", "3"]);
+        assert!(output.status.success());
+        let yaml = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(
+            yaml.lines().filter(|line| line.starts_with("- ")).count(),
+            3
+        );
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn can_print_labeled_passphrases_as_a_yaml_map() {
+        let output = phraze(&[
+            "--format",
+            "yaml",
+            "--label",
+            "db-password",
+            "--label",
+            "api-key",
+            "-w",
+            "3",
+        ]);
+        assert!(output.status.success());
+        let yaml = String::from_utf8(output.stdout).unwrap();
+        let labels: Vec<&str> = yaml
+            .lines()
+            .map(|line| line.split(": ").next().unwrap())
+            .collect();
+        assert_eq!(labels, ["db-password", "api-key"]);
+    }
+
+    #[test]
+    fn labels_need_yaml_and_must_be_different() {
+        for args in [
+            &["--label", "db-password"][..],
+            &["--format", "yaml", "--label", "a", "--label", "a"],
+        ] {
+            let output = phraze(args);
+            assert!(!output.status.success());
+            assert!(output.stdout.is_empty());
+        }
+    }
+
+    #[cfg(not(feature = "yaml"))]
+    #[test]
+    fn refuses_yaml_before_generating_anything() {
+        let history_path =
+            std::env::temp_dir().join(format!("phraze-yaml-history-{}", std::process::id()));
+        let _ = std::fs::remove_file(&history_path);
+        let output = crate::common::phraze_command()
+            .args(["--format", "yaml", "--history-file"])
+            .arg(&history_path)
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+        assert!(String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("the `yaml` feature"));
+        // Nothing was generated, so there's nothing to record
+        assert!(!history_path.exists());
+    }
+}
@@ -0,0 +1,97 @@
+mod common;
+
+mod output_normalization_tests {
+    use crate::common::{phraze, phraze_command};
+    use phraze::unicode_normalization_check::{
+        canonical_form, is_normalized, normalize, NormalizationForm,
+    };
+
+    /// Run phraze on a list written in Normalization Form D, returning the passphrases it printed.
+    fn run_phraze(extra_args: &[&str]) -> Vec<String> {
+        let output = phraze_command()
+            .args([
+                "-c",
+                "tests/fixtures/decomposed_list.txt",
+                "-w",
+                "4",
+                "-n",
+                "20",
+            ])
+            .args(extra_args)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .map(|line| line.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn can_normalize_output_to_nfc_by_default() {
+        for passphrase in run_phraze(&["--output-normalization"]) {
+            assert!(is_normalized(&passphrase, NormalizationForm::Nfc));
+            assert!(!is_normalized(&passphrase, NormalizationForm::Nfd));
+        }
+    }
+
+    #[test]
+    fn can_round_trip_output_between_normalization_forms() {
+        for passphrase in run_phraze(&["--output-normalization", "nfc"]) {
+            let decomposed = normalize(&passphrase, NormalizationForm::Nfd);
+            assert_eq!(normalize(&decomposed, NormalizationForm::Nfc), passphrase);
+        }
+        for passphrase in run_phraze(&["--output-normalization", "NFKD"]) {
+            assert!(is_normalized(&passphrase, NormalizationForm::Nfkd));
+            let composed = normalize(&passphrase, NormalizationForm::Nfkc);
+            assert_eq!(normalize(&composed, NormalizationForm::Nfkd), passphrase);
+        }
+    }
+
+    #[test]
+    fn leaves_words_as_written_without_the_option() {
+        for passphrase in run_phraze(&[]) {
+            assert!(is_normalized(&passphrase, NormalizationForm::Nfd));
+        }
+    }
+
+    #[test]
+    fn can_canonicalize_accented_words() {
+        let output = phraze(&[
+            "-c",
+            "tests/fixtures/mixed_case_accented_list.txt",
+            "-w",
+            "4",
+            "-n",
+            "20",
+            // Overridden by --canonicalize
+            "--title-case",
+            "--canonicalize",
+        ]);
+        assert!(output.status.success());
+        for passphrase in String::from_utf8(output.stdout).unwrap().lines() {
+            assert!(is_normalized(passphrase, NormalizationForm::Nfc));
+            assert_eq!(passphrase, passphrase.to_lowercase());
+            assert_eq!(canonical_form(passphrase), passphrase);
+        }
+        // "Café" and "café" are counted once
+        assert!(String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("Merged 1 words on this list that were identical once lowercased"));
+    }
+
+    #[test]
+    fn canonical_form_matches_however_accents_were_typed() {
+        let composed = "Caf\u{e9}-Cr\u{e8}me-No\u{eb}l";
+        let decomposed = normalize(composed, NormalizationForm::Nfd);
+        assert_eq!(canonical_form(composed), canonical_form(&decomposed));
+        assert_eq!(canonical_form(composed), "caf\u{e9}-cr\u{e8}me-no\u{eb}l");
+    }
+
+    #[test]
+    fn rejects_unknown_normalization_forms() {
+        let output = phraze(&["--output-normalization", "nfx"]);
+        assert!(!output.status.success());
+    }
+}
@@ -0,0 +1,74 @@
+mod common;
+
+mod per_position_tests {
+    use crate::common::phraze;
+    use phraze::grammar::*;
+    use phraze::*;
+
+    #[test]
+    fn can_cycle_through_lists_for_each_position() {
+        let short = vec!["ab", "cd"];
+        let long = vec!["efgh", "ijkl", "mnop", "qrst"];
+        let slots = cycle_slots(&[short.clone(), long.clone()], 5);
+        assert_eq!(
+            slots,
+            vec![short.clone(), long.clone(), short.clone(), long, short]
+        );
+        // 1 + 2 + 1 + 2 + 1 bits
+        assert_eq!(grammar_entropy(&slots), 7.0);
+    }
+
+    #[test]
+    fn can_work_out_positions_needed_for_entropy() {
+        // 10.34 bits, then 13 bits, then cycling back to 10.34 bits
+        let lengths = [1296, 8192];
+        assert_eq!(positions_needed_for_entropy(10, &lengths), 1);
+        assert_eq!(positions_needed_for_entropy(23, &lengths), 2);
+        assert_eq!(positions_needed_for_entropy(24, &lengths), 3);
+        assert_eq!(positions_needed_for_entropy(80, &lengths), 7);
+        assert_eq!(positions_needed_for_entropy(0, &lengths), 0);
+    }
+
+    #[test]
+    fn each_position_draws_from_its_own_list() {
+        let output = phraze(&[
+            "--per-position",
+            "-l",
+            "q",
+            "-c",
+            "tests/fixtures/two_lengths_list.txt",
+            "-l",
+            "n",
+            "-w",
+            "5",
+            "-n",
+            "20",
+        ]);
+        assert!(output.status.success());
+        let custom_list = ["cat", "dog", "fox", "bird", "fish", "wolf"];
+        let lists = [
+            fetch_list(ListChoice::Qwerty),
+            &custom_list[..],
+            fetch_list(ListChoice::Mnemonicode),
+        ];
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout.lines().count(), 20);
+        for passphrase in stdout.lines() {
+            let words: Vec<&str> = passphrase.split('-').collect();
+            assert_eq!(words.len(), 5);
+            for (position, word) in words.iter().enumerate() {
+                assert!(lists[position % 3].contains(word), "{}", passphrase);
+            }
+        }
+    }
+
+    #[test]
+    fn entropy_summary_counts_lists_not_words() {
+        let output = phraze(&["--per-position", "-l", "q", "-l", "m", "-w", "3", "-v"]);
+        assert!(output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.starts_with(
+            "Passphrase has an estimated 33.68 bits of entropy (3 words from 2 lists)"
+        ));
+    }
+}
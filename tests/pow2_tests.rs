@@ -0,0 +1,43 @@
+mod common;
+
+mod pow2_tests {
+
+    fn phraze(args: &[&str]) -> (String, String) {
+        let output = crate::common::phraze(args);
+        assert!(output.status.success());
+        (
+            String::from_utf8(output.stdout).unwrap(),
+            String::from_utf8(output.stderr).unwrap(),
+        )
+    }
+
+    #[test]
+    fn leaves_lists_that_are_already_a_power_of_two_alone() {
+        let (passphrase, report) = phraze(&["-l", "m", "--pow2", "-v", "-w", "4"]);
+        assert!(report.contains("each word is worth exactly 13 bits"));
+        assert!(report.contains("52.00 bits of entropy (4 words from a list of 8192 words)"));
+        assert!(!report.contains("WARNING"));
+        assert_eq!(passphrase.trim_end().split('-').count(), 4);
+    }
+
+    #[test]
+    fn cuts_other_lists_down_to_a_power_of_two() {
+        let (passphrase, report) = phraze(&["-l", "e", "--pow2", "-v", "-w", "4"]);
+        assert!(report.contains(
+            "WARNING: --pow2 dropped 3680 of this list's 7776 words, keeping the first 4096 in sorted order. Each word is now worth exactly 12 bits."
+        ));
+        assert!(report.contains("48.00 bits of entropy (4 words from a list of 4096 words)"));
+        // The EFF long list is sorted, so only words from the start of it are kept
+        let last_kept = phraze::fetch_list(phraze::ListChoice::Eff)[4095];
+        assert!(passphrase
+            .trim_end()
+            .split('-')
+            .all(|word| word <= last_kept));
+    }
+
+    #[test]
+    fn makes_the_same_passphrases_from_the_same_seed() {
+        let args = ["-l", "e", "--pow2", "--seed", "7", "-n", "3"];
+        assert_eq!(phraze(&args).0, phraze(&args).0);
+    }
+}
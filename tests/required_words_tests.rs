@@ -0,0 +1,125 @@
+mod common;
+
+mod required_words_tests {
+    use crate::common::phraze;
+    use phraze::required::*;
+    use phraze::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn can_insert_required_words_at_random_positions() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let themed_list = vec!["comet".to_string(), "nebula".to_string()];
+        let mut positions_used = [0; 6];
+        for _ in 0..200 {
+            let main_words = generate_words(&mut rng, 4, fetch_list(ListChoice::Medium));
+            let words = insert_required_words(&mut rng, &main_words, &themed_list, 2);
+            assert_eq!(words.len(), 6);
+            // Main words are all still there, in order
+            let remaining: Vec<&str> = words
+                .iter()
+                .copied()
+                .filter(|word| !themed_list.iter().any(|themed| themed == word))
+                .collect();
+            assert_eq!(remaining, main_words);
+            for (position, word) in words.iter().enumerate() {
+                if themed_list.iter().any(|themed| themed == word) {
+                    positions_used[position] += 1;
+                }
+            }
+        }
+        // Over 200 passphrases, every position should get a required word at some point
+        assert!(positions_used.iter().all(|count| *count > 0));
+        assert_eq!(positions_used.iter().sum::<usize>(), 400);
+    }
+
+    #[test]
+    fn required_words_contribute_their_own_lists_entropy() {
+        assert_eq!(required_words_entropy(2, 64), 12.0);
+        assert_eq!(required_words_entropy(0, 64), 0.0);
+    }
+
+    #[test]
+    fn can_find_number_of_words_needed_with_required_words() {
+        // 2 words at 6 bits leave 68 bits, which takes 6 words at 13 bits
+        assert_eq!(number_of_words_needed_with_required(80, 13.0, 2, 64), 8);
+        // Required words alone can be enough
+        assert_eq!(number_of_words_needed_with_required(10, 13.0, 2, 64), 2);
+        // Words worth less, like from a weighted list, take more of them
+        assert_eq!(number_of_words_needed_with_required(80, 6.5, 2, 64), 13);
+    }
+
+    #[test]
+    fn reaches_the_minimum_entropy_with_a_weighted_list_and_required_words() {
+        let output = phraze(&[
+            "-c",
+            "tests/fixtures/weighted_list.txt",
+            "--weighted",
+            "--require-from",
+            "tests/fixtures/audit_good_list.txt",
+            "-e",
+            "20",
+            "-v",
+        ]);
+        assert!(output.status.success());
+        let verbose = String::from_utf8(output.stderr).unwrap();
+        let bits: f64 = verbose
+            .split("Passphrase has an estimated ")
+            .nth(1)
+            .and_then(|rest| rest.split(' ').next())
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(bits >= 20.0, "{}", verbose);
+    }
+
+    #[test]
+    fn can_inject_a_word_of_your_own_exactly_once() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut positions_used = [0; 5];
+        for _ in 0..200 {
+            let main_words = generate_words(&mut rng, 4, fetch_list(ListChoice::Medium));
+            let words = inject_word(&mut rng, &main_words, "elmhurst");
+            assert_eq!(words.len(), 5);
+            assert_eq!(words.iter().filter(|word| **word == "elmhurst").count(), 1);
+            let position = words.iter().position(|word| *word == "elmhurst").unwrap();
+            positions_used[position] += 1;
+            // The other words are still there, in order
+            let mut remaining = words.clone();
+            remaining.remove(position);
+            assert_eq!(remaining, main_words);
+        }
+        // It can go before, between or after any of the words
+        assert!(positions_used.iter().all(|count| *count > 0));
+        // Only its position is worth anything
+        assert_eq!(injected_word_entropy(3), 2.0);
+    }
+
+    #[test]
+    fn can_inject_a_word_into_passphrases() {
+        let output = phraze(&["--inject-word", "elmhurst", "-w", "4", "-n", "20"]);
+        assert!(output.status.success());
+        for passphrase in String::from_utf8(output.stdout).unwrap().lines() {
+            let words: Vec<&str> = passphrase.split('-').collect();
+            assert_eq!(words.len(), 5);
+            assert_eq!(words.iter().filter(|word| **word == "elmhurst").count(), 1);
+        }
+    }
+
+    #[test]
+    fn has_room_for_a_long_injected_word() {
+        // Longer than any word on the list, so it has to be counted on its own when sizing
+        // the (locked, with the mlock feature) memory for passphrases
+        let word = "pneumonoultramicroscopicsilicovolcanoconiosis";
+        let output = phraze(&["--inject-word", word, "-w", "2", "-n", "1"]);
+        assert!(output.status.success());
+        assert!(String::from_utf8(output.stdout).unwrap().contains(word));
+    }
+
+    #[test]
+    fn rejects_more_required_words_than_fit() {
+        assert!(check_required_count(3, 4).is_err());
+        assert!(check_required_count(4, 4).is_ok());
+    }
+}
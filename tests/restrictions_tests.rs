@@ -0,0 +1,251 @@
+mod common;
+
+mod restrictions_tests {
+    use crate::common::phraze;
+    use phraze::restrictions::*;
+    use phraze::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn can_catch_words_that_run_together_into_a_common_password() {
+        // With no separator, these words can only make four passphrases, and one of them
+        // spells out "password"
+        let list = ["pass", "word"];
+        let mut rng = StdRng::seed_from_u64(8);
+        let mut rejected = 0;
+        for _ in 0..100 {
+            let passphrase = generate_passphrase_with_rng(&mut rng, 2, "", false, &list);
+            if find_common_substring(&passphrase).is_some() {
+                assert_eq!(passphrase, "password");
+                rejected += 1;
+            }
+        }
+        assert!(rejected > 0 && rejected < 100);
+        // A separator keeps them apart
+        assert_eq!(find_common_substring("pass-word"), None);
+    }
+
+    #[test]
+    fn can_find_runs_of_same_length_words() {
+        assert_eq!(longest_same_length_run(&[]), 0);
+        assert_eq!(longest_same_length_run(&["oak"]), 1);
+        assert_eq!(longest_same_length_run(&["oak", "elm", "ash", "birch"]), 3);
+        assert_eq!(
+            longest_same_length_run(&["oak", "birch", "elm", "cedar"]),
+            1
+        );
+        // Lengths are counted in characters, not bytes
+        assert_eq!(longest_same_length_run(&["café", "cake"]), 2);
+    }
+
+    #[test]
+    fn retries_passphrases_with_long_same_length_runs() {
+        // With only two word lengths, most 4-word passphrases have a run of at least 2, so
+        // these only pass by retrying until the lengths alternate
+        let output = phraze(&[
+            "-c",
+            "tests/fixtures/two_lengths_list.txt",
+            "-w",
+            "4",
+            "-n",
+            "20",
+            "--max-same-length-run",
+            "1",
+        ]);
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout.lines().count(), 20);
+        for passphrase in stdout.lines() {
+            let words: Vec<&str> = passphrase.split('-').collect();
+            assert_eq!(longest_same_length_run(&words), 1, "{}", passphrase);
+        }
+
+        // Every word in this list is the same length, so no number of retries can help
+        let output = phraze(&[
+            "-c",
+            "tests/fixtures/same_length_list.txt",
+            "-w",
+            "3",
+            "--max-same-length-run",
+            "2",
+        ]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("Couldn't generate a passphrase that passes --max-same-length-run"));
+    }
+
+    #[test]
+    fn can_count_only_the_letters_in_words() {
+        assert_eq!(word_character_count(&["oak", "birch"]), 8);
+        assert_eq!(word_character_count(&["drop-down", "t-shirt"]), 14);
+        assert_eq!(word_character_count(&["cafe\u{301}", "caf\u{e9}"]), 8);
+        assert_eq!(word_character_count(&["4th", "of", "july"]), 8);
+    }
+
+    #[test]
+    fn can_work_out_what_share_of_letters_are_vowels() {
+        assert_eq!(vowel_ratio(&["banana"]), 0.5);
+        assert_eq!(vowel_ratio(&["strengths", "idea"]), 4.0 / 13.0);
+        // Accented vowels count, however they're written, and so do capitals
+        assert_eq!(vowel_ratio(&["cafe\u{301}", "CAF\u{e9}"]), 0.5);
+        assert_eq!(vowel_ratio(&["rhythm"]), 0.0);
+        assert_eq!(vowel_ratio(&["42"]), 0.0);
+        assert_eq!(highest_vowel_ratio(&["rhythm", "banana", "idea"]), 0.75);
+    }
+
+    #[test]
+    fn retries_passphrases_with_too_few_vowels() {
+        let output = phraze(&["-w", "4", "-n", "20", "-v", "--min-vowel-ratio", "0.45"]);
+        assert!(output.status.success());
+        let passphrases = String::from_utf8(output.stdout).unwrap();
+        for passphrase in passphrases.lines() {
+            let words: Vec<&str> = passphrase.split('-').collect();
+            assert!(vowel_ratio(&words) >= 0.45);
+        }
+        // Plenty of the Medium list's passphrases have fewer vowels than that
+        let report = String::from_utf8(output.stderr).unwrap();
+        assert!(report.contains("Threw out and re-generated"));
+    }
+
+    #[test]
+    fn warns_when_no_passphrase_can_have_enough_vowels() {
+        // Every word on this list is at most 40% vowels
+        let output = phraze(&[
+            "-c",
+            "tests/fixtures/audit_good_list.txt",
+            "-w",
+            "3",
+            "--min-vowel-ratio",
+            "0.6",
+        ]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("WARNING: No word on this list is 60% vowels (the most is 40%)"));
+    }
+
+    #[test]
+    fn min_word_chars_ignores_separators() {
+        let run = |separator: &str| {
+            phraze(&[
+                "-c",
+                "tests/fixtures/two_lengths_list.txt",
+                "-w",
+                "3",
+                "-n",
+                "20",
+                "-s",
+                separator,
+                "--min-word-chars",
+                "11",
+            ])
+        };
+        // Three words of 3 or 4 letters only have 11 letters with at least two 4-letter words
+        let output = run("-");
+        assert!(output.status.success());
+        for passphrase in String::from_utf8(output.stdout).unwrap().lines() {
+            let words: Vec<&str> = passphrase.split('-').collect();
+            assert!(word_character_count(&words) >= 11, "{}", passphrase);
+        }
+        // Long separators make every passphrase far longer than 11 characters, but they don't
+        // count, so it still takes 11 letters
+        let output = run("=====");
+        assert!(output.status.success());
+        for passphrase in String::from_utf8(output.stdout).unwrap().lines() {
+            let words: Vec<&str> = passphrase.split("=====").collect();
+            assert!(word_character_count(&words) >= 11, "{}", passphrase);
+        }
+
+        // No three words from this list have 13 letters, whatever the separator
+        let output = phraze(&[
+            "-c",
+            "tests/fixtures/two_lengths_list.txt",
+            "-w",
+            "3",
+            "-s",
+            "_____",
+            "--min-word-chars",
+            "13",
+        ]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("Couldn't generate a passphrase that passes --min-word-chars"));
+    }
+
+    #[test]
+    fn can_limit_how_often_a_word_shows_up_in_a_batch() {
+        // Each of these 12 words is in about a quarter of 3-word passphrases, so a limit of 30%
+        // (6 of 20) only needs a retry now and then
+        let output = phraze(&[
+            "-c",
+            "tests/fixtures/audit_good_list.txt",
+            "-w",
+            "3",
+            "-n",
+            "20",
+            "--max-word-frequency",
+            "0.3",
+        ]);
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout.lines().count(), 20);
+        let mut passphrases_with_word = std::collections::HashMap::new();
+        for passphrase in stdout.lines() {
+            let words: std::collections::HashSet<&str> = passphrase.split('-').collect();
+            for word in words {
+                *passphrases_with_word.entry(word).or_insert(0) += 1;
+            }
+        }
+        assert!(passphrases_with_word.values().all(|count| *count <= 6));
+
+        // Only once each means at most 4 passphrases, so the fifth runs out of tries
+        let output = phraze(&[
+            "-c",
+            "tests/fixtures/audit_good_list.txt",
+            "-w",
+            "3",
+            "-n",
+            "20",
+            "--max-word-frequency",
+            "0.05",
+        ]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("Couldn't generate a passphrase that passes --max-word-frequency"));
+    }
+
+    #[test]
+    fn can_leave_out_words_that_look_like_other_words() {
+        // "barn" and "bam" look alike, and "clear" looks like "dear", from the built-in lists
+        let output = phraze(&[
+            "-c",
+            "tests/fixtures/visual_confusables_list.txt",
+            "--avoid-visual-confusables",
+            "-v",
+            "-w",
+            "4",
+            "-n",
+            "30",
+        ]);
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout
+            .lines()
+            .flat_map(|passphrase| passphrase.split('-'))
+            .all(|word| ["badger", "heron", "otter", "walrus", "wren"].contains(&word)));
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("Left out 3 words that could be misread as other words, leaving 5"));
+        assert!(stderr.contains("(4 words from a list of 5 words)"));
+    }
+
+    #[test]
+    fn errors_when_list_is_too_small_for_distinct_first_words() {
+        let list = ["alpha", "beta", "gamma"];
+        assert!(check_distinct_first_words(3, list.len()).is_ok());
+        let error = check_distinct_first_words(4, list.len()).unwrap_err();
+        assert_eq!(
+            error,
+            "Can't give 4 passphrases different first words, since only 3 different words can start a passphrase"
+        );
+    }
+}
@@ -0,0 +1,66 @@
+mod common;
+
+mod reveal_tests {
+    use crate::common::{phraze, PHRAZE};
+    use std::process::Command;
+
+    /// Run Phraze in a pseudo-terminal with util-linux's script, or return None if we can't.
+    #[cfg(target_os = "linux")]
+    fn run_in_terminal(args: &str) -> Option<String> {
+        let command = format!("{} {}", PHRAZE, args);
+        let output = Command::new("script")
+            .args(["-qec", &command, "/dev/null"])
+            .output()
+            .ok()?;
+        assert!(output.status.success());
+        Some(String::from_utf8(output.stdout).unwrap())
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn can_reveal_a_passphrase_word_by_word() {
+        let started = std::time::Instant::now();
+        let shown = match run_in_terminal("--reveal-delay 100 -w 3 -s _") {
+            Some(shown) => shown,
+            None => return,
+        };
+        // One wait after each word
+        assert!(started.elapsed().as_millis() >= 300);
+        let passphrase = shown.trim_end();
+        assert_eq!(passphrase.split('_').count(), 3);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn can_clear_each_word_before_showing_the_next() {
+        let shown = match run_in_terminal("--reveal-delay 10 --reveal-clear -w 3 -s _") {
+            Some(shown) => shown,
+            None => return,
+        };
+        assert!(shown.contains("Word 1 of 3: "));
+        assert!(shown.contains("Word 3 of 3: "));
+        // Each word is erased, including the last, and the passphrase is never on one line
+        assert_eq!(shown.matches("\x1b[J").count(), 3);
+        assert!(shown.ends_with("\x1b[J"));
+        assert!(!shown.contains('\n'));
+    }
+
+    #[test]
+    fn prints_passphrases_as_usual_if_not_printing_to_a_terminal() {
+        let started = std::time::Instant::now();
+        let output = phraze(&[
+            "--reveal-delay",
+            "1000",
+            "--reveal-clear",
+            "-w",
+            "4",
+            "-n",
+            "2",
+        ]);
+        assert!(output.status.success());
+        assert!(started.elapsed().as_millis() < 1000);
+        let printed = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(printed.lines().count(), 2);
+        assert!(printed.lines().all(|line| line.split('-').count() == 4));
+    }
+}
@@ -0,0 +1,60 @@
+mod common;
+
+mod security_answers_tests {
+    use crate::common::phraze;
+
+    fn answer_lines(args: &[&str]) -> (Vec<String>, String) {
+        let output = phraze(args);
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        (
+            stdout.lines().map(|line| line.to_string()).collect(),
+            String::from_utf8(output.stderr).unwrap(),
+        )
+    }
+
+    #[test]
+    fn answers_are_numbered_and_two_words_by_default() {
+        let (lines, stderr) = answer_lines(&["--security-answers", "3"]);
+        assert_eq!(lines.len(), 3);
+        for (i, line) in lines.iter().enumerate() {
+            let answer = line.strip_prefix(&format!("{}. ", i + 1)).unwrap();
+            assert_eq!(answer.split(' ').count(), 2);
+        }
+        assert!(stderr.contains(
+            "Each answer has an estimated 26.00 bits of entropy (2 words from a list of 8192 words)"
+        ));
+    }
+
+    #[test]
+    fn normal_flags_change_the_answers() {
+        let (lines, stderr) = answer_lines(&["--security-answers", "2", "-w", "3", "-s", "_"]);
+        assert_eq!(lines.len(), 2);
+        for line in &lines {
+            let (_, answer) = line.split_once(". ").unwrap();
+            assert_eq!(answer.split('_').count(), 3);
+        }
+        assert!(stderr.contains("39.00 bits"));
+    }
+
+    #[test]
+    fn seeded_answers_are_reproducible() {
+        let args = ["--security-answers", "2", "--seed", "7"];
+        assert_eq!(answer_lines(&args).0, answer_lines(&args).0);
+    }
+
+    #[test]
+    fn cannot_also_ask_for_passphrases() {
+        let output = phraze(&["--security-answers", "2", "-n", "3"]);
+        assert!(!output.status.success());
+        let output = phraze(&["--security-answers", "2", "--format", "json"]);
+        assert!(!output.status.success());
+    }
+
+    #[test]
+    fn cannot_ask_for_no_answers() {
+        let output = phraze(&["--security-answers", "0"]);
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+    }
+}
@@ -0,0 +1,72 @@
+mod separator_tests {
+    use phraze::separators::*;
+    use phraze::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::HashSet;
+
+    #[test]
+    fn can_require_distinct_separators() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let words = ["alpha", "beta", "gamma", "delta", "epsilon", "zeta", "eta"];
+        for _ in 0..100 {
+            let separators = generate_distinct_separators(&mut rng, "_n", 6, 4).unwrap();
+            assert_eq!(separators.len(), 6);
+            assert!(separators.iter().collect::<HashSet<_>>().len() >= 4);
+            assert!(separators.iter().all(|c| c.is_ascii_digit()));
+
+            let passphrase = assemble_passphrase_with_separators(&words, &separators, false);
+            let digits: HashSet<char> = passphrase.chars().filter(|c| c.is_ascii_digit()).collect();
+            assert!(digits.len() >= 4);
+        }
+    }
+
+    #[test]
+    fn rejects_impossible_distinct_separator_requirements() {
+        // 3 words only have 2 gaps between them
+        let error = check_min_distinct_separators("_s", 3, 3).unwrap_err();
+        assert!(error.contains("only has 2 separators"));
+        // There are only 10 digits
+        assert!(check_min_distinct_separators("_n", 20, 11).is_err());
+        // Plain separators are always the same
+        assert!(check_min_distinct_separators("-", 7, 2).is_err());
+
+        let mut rng = StdRng::seed_from_u64(3);
+        assert!(generate_distinct_separators(&mut rng, "_b", 2, 3).is_err());
+    }
+
+    #[test]
+    fn can_draw_separators_from_a_pool() {
+        let pool = parse_separator_pool("digits,custom:+=/", std::iter::empty()).unwrap();
+        assert_eq!(pool.len(), 13);
+        // Repeated characters only count once
+        let pool = parse_separator_pool("digits,custom:0123+", std::iter::empty()).unwrap();
+        assert_eq!(pool.len(), 11);
+        assert!((separator_pool_entropy(5, pool.len()) - 4.0 * 11f64.log2()).abs() < 1e-9);
+
+        // Custom characters take the rest of the pool, commas included
+        let pool = parse_separator_pool("custom:,;", std::iter::empty()).unwrap();
+        assert_eq!(pool, vec![',', ';']);
+
+        // Unused letters leave out every letter in the list's words
+        let words = ["Alpha", "beta", "gamma", "delta"];
+        let pool = parse_separator_pool("unused-letters", words.into_iter()).unwrap();
+        assert!(!pool.contains(&'a') && !pool.contains(&'p') && !pool.contains(&'t'));
+        assert!(pool.contains(&'z'));
+
+        let mut rng = StdRng::seed_from_u64(6);
+        let separators = generate_pool_separators(&mut rng, &pool, 99);
+        assert_eq!(separators.len(), 99);
+        assert!(separators.iter().all(|c| pool.contains(c)));
+    }
+
+    #[test]
+    fn rejects_bad_separator_pools() {
+        assert!(parse_separator_pool("digits,emoji", std::iter::empty()).is_err());
+        assert!(parse_separator_pool("custom:", std::iter::empty()).is_err());
+        assert!(parse_separator_pool("", std::iter::empty()).is_err());
+        // Every letter is used, so there are none left for separators
+        let words = ["abcdefghijklm", "nopqrstuvwxyz"];
+        assert!(parse_separator_pool("unused-letters", words.into_iter()).is_err());
+    }
+}
@@ -0,0 +1,93 @@
+mod common;
+
+mod sinks_tests {
+    use crate::common::phraze_command;
+    use std::path::PathBuf;
+    use std::process::Output;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("phraze-{}-{}", name, std::process::id()))
+    }
+
+    fn phraze(clipboard_command: &str, args: &[&str]) -> Output {
+        phraze_command()
+            .env("PHRAZE_CLIPBOARD_COMMAND", clipboard_command)
+            .args(args)
+            .output()
+            .unwrap()
+    }
+
+    #[test]
+    fn can_send_passphrases_to_a_file_and_the_clipboard() {
+        let output_path = temp_path("sinks-output");
+        let clipboard_path = temp_path("sinks-clipboard");
+        let output = phraze(
+            &format!("tee {}", clipboard_path.display()),
+            &[
+                "-n",
+                "3",
+                "--copy",
+                "--output",
+                output_path.to_str().unwrap(),
+            ],
+        );
+        assert!(output.status.success());
+        // Nothing's printed, since the passphrases went elsewhere
+        assert!(output.stdout.is_empty());
+        let written = std::fs::read_to_string(&output_path).unwrap();
+        let copied = std::fs::read_to_string(&clipboard_path).unwrap();
+        std::fs::remove_file(&output_path).unwrap();
+        std::fs::remove_file(&clipboard_path).unwrap();
+        assert_eq!(written.lines().count(), 3);
+        assert_eq!(written, copied);
+    }
+
+    #[test]
+    fn still_writes_the_file_when_the_clipboard_is_unavailable() {
+        let output_path = temp_path("sinks-no-clipboard");
+        let output = phraze(
+            "phraze-test-no-such-clipboard-tool",
+            &["--copy", "--output", output_path.to_str().unwrap()],
+        );
+        let written = std::fs::read_to_string(&output_path).unwrap();
+        std::fs::remove_file(&output_path).unwrap();
+        assert!(!output.status.success());
+        assert_eq!(written.lines().count(), 1);
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("WARNING: Couldn't send passphrases to the clipboard"));
+        assert!(stderr.contains("but they did go to"));
+    }
+
+    #[test]
+    fn still_copies_when_the_file_cannot_be_written() {
+        let clipboard_path = temp_path("sinks-no-file");
+        let output = phraze(
+            &format!("tee {}", clipboard_path.display()),
+            &[
+                "--copy",
+                "--output",
+                "/nonexistent-directory/passphrases.txt",
+            ],
+        );
+        let copied = std::fs::read_to_string(&clipboard_path).unwrap();
+        std::fs::remove_file(&clipboard_path).unwrap();
+        assert!(!output.status.success());
+        assert_eq!(copied.lines().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn output_file_is_only_readable_by_its_owner() {
+        use std::os::unix::fs::PermissionsExt;
+        let output_path = temp_path("sinks-permissions");
+        let _ = std::fs::remove_file(&output_path);
+        let output = phraze("", &["--output", output_path.to_str().unwrap()]);
+        assert!(output.status.success());
+        let mode = std::fs::metadata(&output_path)
+            .unwrap()
+            .permissions()
+            .mode();
+        std::fs::remove_file(&output_path).unwrap();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
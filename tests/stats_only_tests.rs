@@ -0,0 +1,50 @@
+mod common;
+
+mod stats_only_tests {
+    use crate::common::phraze;
+
+    /// Run phraze with the given arguments, returning what it printed to standard output.
+    fn run_phraze(args: &[&str]) -> String {
+        let output = phraze(args);
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    const LABELS: [&str; 6] = [
+        "List: ",
+        "List length: ",
+        "Bits per word: ",
+        "Words per passphrase: ",
+        "Entropy per passphrase: ",
+        "Expected length: ",
+    ];
+
+    #[test]
+    fn prints_the_plan_without_any_passphrases() {
+        let stdout = run_phraze(&["--stats-only", "-e", "100", "-l", "l"]);
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines.len(), LABELS.len());
+        for (line, label) in lines.iter().zip(LABELS) {
+            assert!(line.starts_with(label), "Unexpected line: {}", line);
+        }
+        assert!(stdout.contains("List: Orchard Street Long list"));
+        assert!(stdout.contains("Words per passphrase: 8"));
+    }
+
+    #[test]
+    fn can_check_a_custom_list_without_generating_from_it() {
+        let stdout = run_phraze(&[
+            "--stats-only",
+            "-c",
+            "tests/fixtures/list_with_bom.txt",
+            "-w",
+            "4",
+        ]);
+        assert!(stdout.contains("List length: 3 words"));
+        assert!(stdout.contains("Expected length: about 22 characters"));
+        // None of the list's words are ever printed
+        for word in ["alpha", "beta", "gamma"] {
+            assert!(!stdout.contains(word));
+        }
+    }
+}
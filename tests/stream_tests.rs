@@ -0,0 +1,95 @@
+mod common;
+
+mod stream_tests {
+    use crate::common::{phraze, phraze_command};
+    use std::io::{BufRead, BufReader};
+    use std::process::{Child, Command, Stdio};
+
+    fn spawn_phraze(args: &[&str]) -> Child {
+        phraze_command()
+            .args(args)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap()
+    }
+
+    /// Read some lines of a running Phraze's output, then stop reading, like `head` does.
+    fn read_lines_then_stop(child: &mut Child, count: usize) -> Vec<String> {
+        let stdout = child.stdout.take().unwrap();
+        // Dropping the reader closes our end of the pipe
+        BufReader::new(stdout)
+            .lines()
+            .take(count)
+            .map(|line| line.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn can_stream_until_the_reader_stops() {
+        let mut child = spawn_phraze(&["--stream", "-w", "3"]);
+        let lines = read_lines_then_stop(&mut child, 500);
+        assert_eq!(lines.len(), 500);
+        assert!(lines.iter().all(|line| line.split('-').count() == 3));
+        // A closed pipe isn't an error
+        assert!(child.wait().unwrap().success());
+    }
+
+    #[test]
+    fn can_stop_printing_a_batch_when_the_reader_stops() {
+        // Far more than fits in a pipe's buffer, so Phraze is still printing when we stop
+        let mut child = spawn_phraze(&["-n", "100000"]);
+        assert_eq!(read_lines_then_stop(&mut child, 1).len(), 1);
+        assert!(child.wait().unwrap().success());
+    }
+
+    #[test]
+    fn can_stop_printing_other_outputs_when_the_reader_stops() {
+        for args in [
+            &["--security-answers", "100000"][..],
+            &["--entropy-histogram", "-n", "100000", "-w", "2"],
+        ] {
+            let mut child = spawn_phraze(args);
+            assert_eq!(read_lines_then_stop(&mut child, 1).len(), 1);
+            assert!(child.wait().unwrap().success());
+        }
+        // The plan is short, so stop reading before Phraze has printed any of it
+        let mut child = spawn_phraze(&["--stats-only", "-w", "3"]);
+        drop(child.stdout.take());
+        assert!(child.wait().unwrap().success());
+    }
+
+    #[test]
+    fn can_throttle_a_stream() {
+        let started = std::time::Instant::now();
+        let mut child = spawn_phraze(&["--stream", "--rate", "20"]);
+        assert_eq!(read_lines_then_stop(&mut child, 5).len(), 5);
+        assert!(child.wait().unwrap().success());
+        // The first passphrase comes right away, then one every 50 milliseconds
+        assert!(started.elapsed().as_millis() >= 200);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn flushes_a_stream_on_ctrl_c() {
+        let child = spawn_phraze(&["--stream", "--rate", "100"]);
+        std::thread::sleep(std::time::Duration::from_millis(500));
+        let killed = Command::new("kill")
+            .args(["-INT", &child.id().to_string()])
+            .status()
+            .unwrap();
+        assert!(killed.success());
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        let printed = String::from_utf8(output.stdout).unwrap();
+        assert!(!printed.is_empty());
+        // Every passphrase is printed whole
+        assert!(printed.ends_with('\n'));
+    }
+
+    #[test]
+    fn rejects_a_rate_of_zero() {
+        let output = phraze(&["--stream", "--rate", "0"]);
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+    }
+}
@@ -0,0 +1,116 @@
+#![cfg(feature = "structured-lists")]
+
+mod common;
+
+mod structured_list_tests {
+    use crate::common::{phraze, phraze_command};
+    use phraze::file_reader::*;
+    use std::path::Path;
+
+    #[test]
+    fn json_and_toml_lists_read_in_like_text_lists() {
+        let text_list =
+            read_in_custom_list(Path::new("tests/fixtures/structured_list.txt")).unwrap();
+        assert_eq!(text_list, vec!["apple", "kiwi", "mango", "otter", "zebra"]);
+        for (path, format) in [
+            ("tests/fixtures/structured_list.json", ListFormat::Json),
+            ("tests/fixtures/structured_list.toml", ListFormat::Toml),
+        ] {
+            assert_eq!(ListFormat::from_path(Path::new(path)), format);
+            let (list, metadata) = read_in_structured_list(Path::new(path), format).unwrap();
+            assert_eq!(list, text_list);
+            assert_eq!(metadata.name, Some("Fruit and animals".to_string()));
+            assert_eq!(
+                metadata.source,
+                Some("https://example.com/lists/fruit-and-animals".to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn can_read_a_json_array_of_words() {
+        let (list, metadata) = read_in_structured_list(
+            Path::new("tests/fixtures/array_list.json"),
+            ListFormat::Json,
+        )
+        .unwrap();
+        assert_eq!(list, vec!["badger", "heron", "walrus"]);
+        assert_eq!(metadata, ListMetadata::default());
+    }
+
+    #[test]
+    fn errors_say_where_the_problem_is() {
+        let error = read_in_structured_list(
+            Path::new("tests/fixtures/invalid_list.json"),
+            ListFormat::Json,
+        )
+        .unwrap_err();
+        assert!(error.contains("tests/fixtures/invalid_list.json"));
+        assert!(error.contains("line 3"));
+
+        let error = read_in_structured_list(
+            Path::new("tests/fixtures/invalid_list.toml"),
+            ListFormat::Toml,
+        )
+        .unwrap_err();
+        assert!(error.contains("tests/fixtures/invalid_list.toml"));
+        assert!(error.contains("Word 3 of \"words\" isn't a string"));
+
+        // Reading a JSON list as TOML doesn't work either
+        let error = read_in_structured_list(
+            Path::new("tests/fixtures/structured_list.json"),
+            ListFormat::Toml,
+        )
+        .unwrap_err();
+        assert!(error.contains("line 1"));
+    }
+
+    #[test]
+    fn can_generate_passphrases_from_json_and_toml_lists() {
+        for path in [
+            "tests/fixtures/structured_list.json",
+            "tests/fixtures/structured_list.toml",
+        ] {
+            let output = phraze(&["-c", path, "-w", "4"]);
+            assert!(output.status.success());
+            let passphrase = String::from_utf8(output.stdout).unwrap();
+            assert!(passphrase
+                .trim()
+                .split('-')
+                .all(|word| ["apple", "kiwi", "mango", "otter", "zebra"].contains(&word)));
+        }
+    }
+
+    #[test]
+    fn format_can_be_given_whatever_the_extension() {
+        let path =
+            std::env::temp_dir().join(format!("phraze-json-list-{}.txt", std::process::id()));
+        std::fs::copy("tests/fixtures/array_list.json", &path).unwrap();
+        let output = phraze_command()
+            .args([
+                "--custom-list-format",
+                "json",
+                "--stats-only",
+                "-w",
+                "3",
+                "-c",
+            ])
+            .arg(&path)
+            .output()
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(output.status.success());
+        assert!(String::from_utf8(output.stdout)
+            .unwrap()
+            .contains("List length: 3 words"));
+    }
+
+    #[test]
+    fn stats_only_shows_a_lists_name_and_source() {
+        let output = phraze(&["-c", "tests/fixtures/structured_list.toml", "--stats-only"]);
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("List name: Fruit and animals\n"));
+        assert!(stdout.contains("List source: https://example.com/lists/fruit-and-animals\n"));
+    }
+}
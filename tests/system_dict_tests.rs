@@ -0,0 +1,50 @@
+mod common;
+
+mod system_dict_tests {
+    use crate::common::phraze;
+    use phraze::file_reader::read_in_system_dictionary;
+    use std::path::Path;
+
+    const DICTIONARY: &str = "tests/fixtures/system_dict_words.txt";
+
+    #[test]
+    fn leaves_proper_nouns_possessives_and_odd_lengths_out_of_a_dictionary() {
+        let dictionary = read_in_system_dictionary(Path::new(DICTIONARY), 4, 8).unwrap();
+        assert_eq!(dictionary.entries, 22);
+        assert_eq!(
+            dictionary.words,
+            [
+                "aardvark", "abacus", "abacuses", "abandon", "abbey", "abbot", "abdomen", "abide",
+                "ability", "able"
+            ]
+        );
+        // Longer words are kept if the user asks for them
+        let dictionary = read_in_system_dictionary(Path::new(DICTIONARY), 4, 12).unwrap();
+        assert!(dictionary.words.contains(&"abbreviation".to_string()));
+    }
+
+    #[test]
+    fn can_make_passphrases_from_a_dictionary() {
+        let output = phraze(&["--system-dict", DICTIONARY, "-w", "5", "-v"]);
+        assert!(output.status.success());
+        let passphrase = String::from_utf8(output.stdout).unwrap();
+        let words: Vec<&str> = passphrase.trim().split('-').collect();
+        assert_eq!(words.len(), 5);
+        assert!(words
+            .iter()
+            .all(|word| word.starts_with('a') && !word.contains('\'')));
+        assert!(String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("Kept 10 of the 22 entries in tests/fixtures/system_dict_words.txt, so each word is worth 3.32 bits"));
+    }
+
+    #[test]
+    fn suggests_a_package_if_there_is_no_dictionary() {
+        let output = phraze(&["--system-dict", "tests/fixtures/no_such_dictionary"]);
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+        assert!(String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("wamerican"));
+    }
+}
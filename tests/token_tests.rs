@@ -0,0 +1,66 @@
+#![cfg(feature = "token")]
+
+mod common;
+
+mod token_tests {
+    use crate::common::{phraze, phraze_command};
+    use std::io::Write;
+    use std::process::Stdio;
+
+    const RESPONSE: &str = "00112233445566778899aabbccddeeff01234567";
+
+    fn phraze_with_stdin(args: &[&str], stdin: &str) -> std::process::Output {
+        let mut child = phraze_command()
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(stdin.as_bytes())
+            .unwrap();
+        child.wait_with_output().unwrap()
+    }
+
+    #[test]
+    fn can_make_the_same_passphrases_from_the_same_response() {
+        let first = phraze_with_stdin(&["--token-stdin", "-n", "3"], RESPONSE);
+        let again = phraze_with_stdin(&["--token-stdin", "-n", "3"], RESPONSE);
+        assert!(first.status.success());
+        assert_eq!(
+            String::from_utf8(first.stdout.clone())
+                .unwrap()
+                .lines()
+                .count(),
+            3
+        );
+        assert_eq!(first.stdout, again.stdout);
+
+        let other = phraze_with_stdin(
+            &["--token-stdin", "-n", "3"],
+            "ffeeddccbbaa99887766554433221100fedcba98",
+        );
+        assert_ne!(first.stdout, other.stdout);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn can_get_the_response_from_a_helper() {
+        let helper = format!("echo {}", RESPONSE);
+        let from_helper = phraze(&["--token-helper", &helper, "-n", "3"]);
+        assert!(from_helper.status.success());
+        let from_stdin = phraze_with_stdin(&["--token-stdin", "-n", "3"], RESPONSE);
+        assert_eq!(from_helper.stdout, from_stdin.stdout);
+    }
+
+    #[test]
+    fn rejects_responses_that_are_too_short() {
+        let output = phraze_with_stdin(&["--token-stdin"], "00112233");
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+    }
+}
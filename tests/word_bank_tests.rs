@@ -0,0 +1,55 @@
+mod common;
+
+mod word_bank_tests {
+    use crate::common::phraze;
+    use phraze::word_bank::read_word_bank;
+
+    fn run_phraze(args: &[&str]) -> String {
+        let output = phraze(args);
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn word_bank_is_only_readable_by_its_owner() {
+        use std::os::unix::fs::PermissionsExt;
+        let path = std::env::temp_dir().join(format!(
+            "phraze-word-bank-permissions-{}",
+            std::process::id()
+        ));
+        run_phraze(&["--word-bank", path.to_str().unwrap()]);
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn can_rebuild_passphrases_from_a_word_bank_and_selections() {
+        let path = std::env::temp_dir().join(format!("phraze-word-bank-{}", std::process::id()));
+        let bank_path = path.to_str().unwrap();
+        // The same seed gives the same passphrases, so we know what the bank should rebuild
+        let passphrases = run_phraze(&["--seed", "40", "-w", "5", "-n", "3"]);
+        let selections = run_phraze(&[
+            "--seed",
+            "40",
+            "-w",
+            "5",
+            "-n",
+            "3",
+            "--word-bank",
+            bank_path,
+        ]);
+
+        let bank = read_word_bank(&path).unwrap();
+        assert_eq!(bank.len(), 100);
+        for (passphrase, selections) in passphrases.lines().zip(selections.lines()) {
+            assert_ne!(passphrase, selections);
+            let rebuilt = run_phraze(&["--from-word-bank", bank_path, "--selections", selections]);
+            assert_eq!(rebuilt.trim_end(), passphrase);
+        }
+        // Selections alone aren't enough
+        assert!(!phraze(&["--selections", "1 2 3"]).status.success());
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "word-pattern")]
+
+mod common;
+
+mod word_pattern_tests {
+    use crate::common::{phraze, phraze_command};
+
+    fn words_made_with(args: &[&str]) -> Vec<String> {
+        let output = phraze_command()
+            .args(args)
+            .args(["-n", "50", "-s", "_"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .split(['_', '\n'])
+            .filter(|word| !word.is_empty())
+            .map(|word| word.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn can_limit_the_length_of_words() {
+        let words = words_made_with(&["--word-pattern", "^.{4,6}$"]);
+        assert!(words.len() >= 50);
+        assert!(words
+            .iter()
+            .all(|word| (4..=6).contains(&word.chars().count())));
+    }
+
+    #[test]
+    fn can_limit_which_characters_words_start_with() {
+        let words = words_made_with(&["-l", "e", "--word-pattern", "^[a-m]"]);
+        assert!(words
+            .iter()
+            .all(|word| ('a'..='m').contains(&word.chars().next().unwrap())));
+
+        let words = words_made_with(&[
+            "--custom-list",
+            "tests/fixtures/audit_good_list.txt",
+            "--word-pattern",
+            "[ln]",
+        ]);
+        assert!(words.iter().all(|word| word.contains(['l', 'n'])));
+    }
+
+    #[test]
+    fn entropy_is_worked_out_from_the_words_that_match() {
+        let output = phraze_command()
+            .args(["-l", "e", "--word-pattern", "^[a-m]", "-w", "4", "-v"])
+            .output()
+            .unwrap();
+        let report = String::from_utf8(output.stderr).unwrap();
+        let kept: usize = report.split_whitespace().nth(1).unwrap().parse().unwrap();
+        assert!(kept < 7776);
+        assert!(report.contains(&format!("(4 words from a list of {} words)", kept)));
+    }
+
+    #[test]
+    fn rejects_patterns_that_leave_too_few_words() {
+        for pattern in ["^zzzz", "[a-"] {
+            let output = phraze(&["--word-pattern", pattern]);
+            assert!(!output.status.success());
+            assert!(output.stdout.is_empty());
+        }
+    }
+}